@@ -0,0 +1,174 @@
+//! Alarm match logic evaluated without the device
+
+use crate::calendar::days_in_month;
+use crate::{AlarmDateTime, AlarmMatching, DateTime, Hours};
+
+impl AlarmDateTime {
+    /// Returns whether the alarm comparator would match at the given date/time
+    /// with the given trigger rate.
+    ///
+    /// This mirrors the device logic: only the fields selected by the trigger
+    /// rate are compared, so that e.g. an alarm with `WeekdayMatches` matches
+    /// during every second of that weekday.
+    /// The hour is compared taking its 12-hour or 24-hour format into account.
+    pub fn would_match(&self, matching: AlarmMatching, datetime: &DateTime) -> bool {
+        let hour_matches = || alarm_hour(self.hour) == Some(datetime.hour());
+        match matching {
+            AlarmMatching::SecondsMatch => datetime.second() == self.second,
+            AlarmMatching::MinutesMatch => datetime.minute() == self.minute,
+            AlarmMatching::HoursMatch => hour_matches(),
+            AlarmMatching::WeekdayMatches | AlarmMatching::DayMatches => {
+                self.matches_date(matching, datetime)
+            }
+            AlarmMatching::AllMatch => {
+                self.matches_date(matching, datetime)
+                    && hour_matches()
+                    && datetime.minute() == self.minute
+                    && datetime.second() == self.second
+            }
+        }
+    }
+
+    /// Returns the first date/time after `now` at which the alarm comparator
+    /// would match with the given trigger rate.
+    ///
+    /// This is the next time the device will set the alarm matched flag
+    /// (ALMxIF) once it has been cleared.
+    /// Returns `None` if the alarm never matches until the end of 2099,
+    /// e.g. a `DayMatches` alarm on day 32 or an `AllMatch` alarm on
+    /// February 30th.
+    ///
+    /// With the `rtcc` feature, the result can be converted into a
+    /// `NaiveDateTime`.
+    pub fn next_match(&self, matching: AlarmMatching, now: &DateTime) -> Option<DateTime> {
+        let is_date_valid = match matching {
+            AlarmMatching::WeekdayMatches => (1..=7).contains(&self.weekday),
+            AlarmMatching::DayMatches => (1..=31).contains(&self.day),
+            AlarmMatching::AllMatch => {
+                (1..=7).contains(&self.weekday)
+                    && (1..=12).contains(&self.month)
+                    && (1..=31).contains(&self.day)
+            }
+            _ => true,
+        };
+        if !is_date_valid {
+            return None;
+        }
+        let (hour, minute, second) = match matching {
+            AlarmMatching::SecondsMatch => (None, None, Some(field(self.second, 59)?)),
+            AlarmMatching::MinutesMatch => (None, Some(field(self.minute, 59)?), None),
+            AlarmMatching::HoursMatch => (Some(alarm_hour(self.hour)?), None, None),
+            AlarmMatching::WeekdayMatches | AlarmMatching::DayMatches => (None, None, None),
+            AlarmMatching::AllMatch => (
+                Some(alarm_hour(self.hour)?),
+                Some(field(self.minute, 59)?),
+                Some(field(self.second, 59)?),
+            ),
+        };
+        let (mut year, mut month, mut day) = (now.year(), now.month(), now.day());
+        let mut from = seconds_of_day(now) + 1;
+        loop {
+            // Fails after 2099-12-31
+            let date = DateTime::new(year, month, day, 0, 0, 0)?;
+            if self.matches_date(matching, &date) {
+                if let Some(time) = first_time_from(from, hour, minute, second) {
+                    return DateTime::new(
+                        year,
+                        month,
+                        day,
+                        (time / 3600) as u8,
+                        (time % 3600 / 60) as u8,
+                        (time % 60) as u8,
+                    );
+                }
+            }
+            from = 0;
+            day += 1;
+            if day > days_in_month(year, month) {
+                day = 1;
+                month += 1;
+                if month > 12 {
+                    month = 1;
+                    year += 1;
+                }
+            }
+        }
+    }
+
+    fn matches_date(&self, matching: AlarmMatching, date: &DateTime) -> bool {
+        match matching {
+            AlarmMatching::WeekdayMatches => date.weekday() == self.weekday,
+            AlarmMatching::DayMatches => date.day() == self.day,
+            AlarmMatching::AllMatch => {
+                date.weekday() == self.weekday
+                    && date.day() == self.day
+                    && date.month() == self.month
+            }
+            _ => true,
+        }
+    }
+}
+
+fn field(value: u8, max: u8) -> Option<u8> {
+    (value <= max).then_some(value)
+}
+
+// Hour [0-23] of an alarm hour in any format or `None` if it is invalid.
+fn alarm_hour(hour: Hours) -> Option<u8> {
+    match hour {
+        Hours::H24(h) if h < 24 => Some(h),
+        Hours::AM(h) if (1..=12).contains(&h) => Some(h % 12),
+        Hours::PM(h) if (1..=12).contains(&h) => Some(h % 12 + 12),
+        _ => None,
+    }
+}
+
+fn seconds_of_day(datetime: &DateTime) -> u32 {
+    u32::from(datetime.hour()) * 3600
+        + u32::from(datetime.minute()) * 60
+        + u32::from(datetime.second())
+}
+
+// First second of the day at or after `from` whose hour, minute and second
+// match the given values, if any.
+fn first_time_from(
+    mut from: u32,
+    hour: Option<u8>,
+    minute: Option<u8>,
+    second: Option<u8>,
+) -> Option<u32> {
+    while from < 86_400 {
+        let (h, m, s) = (from / 3600, from % 3600 / 60, from % 60);
+        if let Some(hour) = hour.map(u32::from) {
+            if h != hour {
+                if h > hour {
+                    return None;
+                }
+                from = hour * 3600;
+                continue;
+            }
+        }
+        if let Some(minute) = minute.map(u32::from) {
+            if m != minute {
+                from = if m < minute {
+                    h * 3600 + minute * 60
+                } else {
+                    (h + 1) * 3600
+                };
+                continue;
+            }
+        }
+        if let Some(second) = second.map(u32::from) {
+            if s != second {
+                from = if s < second {
+                    h * 3600 + m * 60 + second
+                } else {
+                    h * 3600 + (m + 1) * 60
+                };
+                continue;
+            }
+        }
+        return Some(from);
+    }
+    None
+}
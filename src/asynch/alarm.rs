@@ -0,0 +1,217 @@
+//! Asynchronous alarm methods
+
+use super::Mcp794xxAsync;
+use crate::common::alarm::{
+    alarm_after, alarm_datetime, alarm_from_registers, alarm_polarity_to_register,
+    alarm_seconds_register, alarm_to_registers, alarm_weekday_register,
+};
+#[cfg(feature = "rtcc")]
+use crate::common::{alarm::weekly_alarm_datetime, datetime::datetime_from_naive};
+use crate::{
+    interface, marker, Alarm, AlarmConfig, AlarmDateTime, AlarmMatching, AlarmOutputPinPolarity,
+    BitFlags, Cron, DateTime, Error, Register,
+};
+#[cfg(feature = "rtcc")]
+use crate::{NaiveDateTime, Weekday};
+use core::time::Duration;
+
+impl<DI, E, IC> Mcp794xxAsync<DI, IC>
+where
+    DI: interface::AsyncWriteData<Error = Error<E>> + interface::AsyncReadData<Error = Error<E>>,
+    IC: marker::WithAlarms,
+{
+    /// Enable alarm
+    pub async fn enable_alarm(&mut self, alarm: Alarm) -> Result<(), Error<E>> {
+        match alarm {
+            Alarm::Zero => {
+                self.write_control(self.control.with_high(BitFlags::ALM0EN))
+                    .await
+            }
+            Alarm::One => {
+                self.write_control(self.control.with_high(BitFlags::ALM1EN))
+                    .await
+            }
+        }
+    }
+
+    /// Disable alarm
+    pub async fn disable_alarm(&mut self, alarm: Alarm) -> Result<(), Error<E>> {
+        match alarm {
+            Alarm::Zero => {
+                self.write_control(self.control.with_low(BitFlags::ALM0EN))
+                    .await
+            }
+            Alarm::One => {
+                self.write_control(self.control.with_low(BitFlags::ALM1EN))
+                    .await
+            }
+        }
+    }
+
+    /// Set alarm for date/time with a trigger rate and an output pin polarity.
+    ///
+    /// Note that this clears the alarm has matched flag and the alarm needs to be
+    /// enabled separately.
+    /// Note that the output pin polarity will be set to the same value for both alarms.
+    pub async fn set_alarm(
+        &mut self,
+        alarm: Alarm,
+        when: AlarmDateTime,
+        matching: AlarmMatching,
+        polarity: AlarmOutputPinPolarity,
+    ) -> Result<(), Error<E>> {
+        let payload = alarm_to_registers(
+            alarm,
+            when,
+            matching,
+            polarity,
+            self.is_running_in_24h_mode,
+            IC::HAS_ALARM1_MONTH,
+        )?;
+        if polarity != self.alarm_output_pin_polarity && alarm == Alarm::One {
+            let data = self.iface.read_register(Register::ALM0WKDAY).await?;
+            self.iface
+                .write_register(
+                    Register::ALM0WKDAY,
+                    alarm_polarity_to_register(data, polarity),
+                )
+                .await?;
+            self.alarm_output_pin_polarity = polarity;
+        }
+        self.iface.write_data(&payload).await?;
+        self.alarm_output_pin_polarity = polarity;
+        Ok(())
+    }
+
+    /// Set alarm for a date/time with a trigger rate and an output pin polarity.
+    ///
+    /// The weekday is calculated from the date as in
+    /// [`set_datetime()`](#method.set_datetime) and the hour is stored in the
+    /// format the RTC is running in.
+    /// Returns `Error::InvalidInputData` if the year is not in the range
+    /// 2000-2099 since the weekday would not correspond to the device calendar.
+    ///
+    /// See [`set_alarm()`](#method.set_alarm) for further notes.
+    #[cfg(feature = "rtcc")]
+    pub async fn set_alarm_at(
+        &mut self,
+        alarm: Alarm,
+        datetime: &NaiveDateTime,
+        matching: AlarmMatching,
+        polarity: AlarmOutputPinPolarity,
+    ) -> Result<(), Error<E>> {
+        let when = alarm_datetime(&datetime_from_naive(datetime)?);
+        self.set_alarm(alarm, when, matching, polarity).await
+    }
+
+    /// Set alarm for a weekday with an output pin polarity.
+    ///
+    /// This uses the `WeekdayMatches` trigger rate. Note that in this mode
+    /// the device only compares the weekday, so the alarm matches as soon
+    /// as the weekday starts, at midnight.
+    ///
+    /// See [`set_alarm()`](#method.set_alarm) for further notes.
+    #[cfg(feature = "rtcc")]
+    pub async fn set_weekly_alarm(
+        &mut self,
+        alarm: Alarm,
+        weekday: Weekday,
+        polarity: AlarmOutputPinPolarity,
+    ) -> Result<(), Error<E>> {
+        let when = weekly_alarm_datetime(weekday);
+        self.set_alarm(alarm, when, AlarmMatching::WeekdayMatches, polarity)
+            .await
+    }
+
+    /// Set and enable an alarm to match once after a duration from the
+    /// current date/time and return the date/time at which it will match.
+    ///
+    /// The duration is rounded down to whole seconds. The alarm uses the
+    /// `AllMatch` trigger rate so that it matches only once.
+    ///
+    /// Returns `Error::InvalidInputData` if the duration is shorter than a
+    /// second, ends after 2099 or is so long that the alarm would match
+    /// earlier, since the alarm registers do not include the year.
+    ///
+    /// See [`set_alarm()`](#method.set_alarm) for further notes.
+    pub async fn set_alarm_in(
+        &mut self,
+        alarm: Alarm,
+        duration: Duration,
+        polarity: AlarmOutputPinPolarity,
+    ) -> Result<DateTime, Error<E>> {
+        let now = self.read_datetime().await?;
+        let (when, target) = alarm_after(&now, duration)?;
+        self.set_alarm(alarm, when, AlarmMatching::AllMatch, polarity)
+            .await?;
+        self.enable_alarm(alarm).await?;
+        Ok(target)
+    }
+
+    /// Set and enable an alarm to match at the next occurrence of a cron
+    /// expression after the current date/time and return the date/time at
+    /// which it will match.
+    ///
+    /// The alarm uses the `AllMatch` trigger rate. Call this again after
+    /// the alarm has matched to set the following occurrence.
+    ///
+    /// Returns `Error::InvalidInputData` if there is no occurrence until the
+    /// end of 2099 or the alarm would match earlier since the alarm registers
+    /// do not include the year.
+    ///
+    /// See [`set_alarm()`](#method.set_alarm) for further notes.
+    pub async fn set_cron_alarm(
+        &mut self,
+        alarm: Alarm,
+        cron: &Cron,
+        polarity: AlarmOutputPinPolarity,
+    ) -> Result<DateTime, Error<E>> {
+        let now = self.read_datetime().await?;
+        let next = cron.next_after(&now).ok_or(Error::InvalidInputData)?;
+        let when = alarm_datetime(&next);
+        if when.next_match(AlarmMatching::AllMatch, &now) != Some(next) {
+            return Err(Error::InvalidInputData);
+        }
+        self.set_alarm(alarm, when, AlarmMatching::AllMatch, polarity)
+            .await?;
+        self.enable_alarm(alarm).await?;
+        Ok(next)
+    }
+
+    /// Read the configuration of an alarm.
+    ///
+    /// The hour is returned in the format it was stored in.
+    /// The output pin polarity is shared by both alarms and is always read
+    /// from the alarm 0 registers.
+    pub async fn get_alarm(&mut self, alarm: Alarm) -> Result<AlarmConfig, Error<E>> {
+        let mut data = [0; 6];
+        self.iface
+            .read_data(alarm_seconds_register(alarm), &mut data)
+            .await?;
+        let alm0_weekday = match alarm {
+            Alarm::Zero => data[3],
+            Alarm::One => self.iface.read_register(Register::ALM0WKDAY).await?,
+        };
+        let control = self.iface.read_register(Register::CONTROL).await?;
+        alarm_from_registers(alarm, &data, alm0_weekday, control)
+    }
+
+    /// Returns whether the alarm has matched.
+    ///
+    /// Once this is true, it will stay as such until cleared. e.g. with
+    /// [`clear_alarm_matched_flag()`](#method.clear_alarm_matched_flag)
+    pub async fn has_alarm_matched(&mut self, alarm: Alarm) -> Result<bool, Error<E>> {
+        let reg = alarm_weekday_register(alarm);
+        let data = self.iface.read_register(reg).await?;
+        Ok((data & BitFlags::ALMIF) != 0)
+    }
+
+    /// Clears the alarm matched flag.
+    pub async fn clear_alarm_matched_flag(&mut self, alarm: Alarm) -> Result<(), Error<E>> {
+        let reg = alarm_weekday_register(alarm);
+        let data = self.iface.read_register(reg).await?;
+        self.iface
+            .write_register(reg, data & !BitFlags::ALMIF)
+            .await
+    }
+}
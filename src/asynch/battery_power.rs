@@ -0,0 +1,91 @@
+//! Asynchronous backup battery power methods
+
+use super::Mcp794xxAsync;
+use crate::battery_power::{power_fail_from_registers, power_outage_from_registers};
+use crate::{interface, marker, BitFlags, Error, PowerFailDateTime, PowerOutage, Register};
+
+impl<DI, E, IC> Mcp794xxAsync<DI, IC>
+where
+    DI: interface::AsyncWriteData<Error = Error<E>> + interface::AsyncReadData<Error = Error<E>>,
+    IC: marker::WithBatteryPower,
+{
+    /// Returns whether the primary power has failed.
+    pub async fn has_power_failed(&mut self) -> Result<bool, Error<E>> {
+        let data = self.iface.read_register(Register::WEEKDAY).await?;
+        Ok((data & BitFlags::PWRFAIL) != 0)
+    }
+
+    /// Clears the power failed status flag and power-fail time-stamp registers.
+    pub async fn clear_power_failed(&mut self) -> Result<(), Error<E>> {
+        let data = self.iface.read_register(Register::WEEKDAY).await?;
+        let data = data & !BitFlags::PWRFAIL;
+        self.iface.write_register(Register::WEEKDAY, data).await
+    }
+
+    /// Returns date/time when the power failed went down (under Vtrip).
+    ///
+    /// Note that the registers need to be cleared by calling
+    /// [`clear_power_failed()`](#method.clear_power_failed)
+    pub async fn get_power_down_datetime(&mut self) -> Result<PowerFailDateTime, Error<E>> {
+        self.get_power_fail(Register::PWRDNMIN).await
+    }
+
+    /// Returns date/time when the power went back up (above Vtrip).
+    ///
+    /// Note that the registers need to be cleared by calling
+    /// [`clear_power_failed()`](#method.clear_power_failed)
+    pub async fn get_power_up_datetime(&mut self) -> Result<PowerFailDateTime, Error<E>> {
+        self.get_power_fail(Register::PWRUPMIN).await
+    }
+
+    /// Returns the power outage recorded in the power-fail time-stamps with
+    /// the full date/time of power down and power up.
+    ///
+    /// The device does not store the year so it is inferred from the current
+    /// date as the latest year in which the time-stamp is not in the future.
+    /// This handles outages crossing a new year but outages longer than a
+    /// year cannot be detected. The seconds are not stored either and are 0.
+    ///
+    /// Note that the registers need to be cleared by calling
+    /// [`clear_power_failed()`](#method.clear_power_failed)
+    pub async fn get_power_outage(&mut self) -> Result<PowerOutage, Error<E>> {
+        let mut now = [0; 7];
+        self.iface.read_data(Register::SECONDS, &mut now).await?;
+        let mut timestamps = [0; 8];
+        self.iface
+            .read_data(Register::PWRDNMIN, &mut timestamps)
+            .await?;
+        power_outage_from_registers(&now, &timestamps)
+    }
+
+    async fn get_power_fail(
+        &mut self,
+        starting_register: u8,
+    ) -> Result<PowerFailDateTime, Error<E>> {
+        let mut data = [0; 4];
+        self.iface.read_data(starting_register, &mut data).await?;
+        Ok(power_fail_from_registers(&data))
+    }
+
+    /// Enable usage of backup battery power.
+    ///
+    /// Note that this clears the power failed flag.
+    pub async fn enable_backup_battery_power(&mut self) -> Result<(), Error<E>> {
+        let data = self.iface.read_register(Register::WEEKDAY).await?;
+        let data = data | BitFlags::VBATEN;
+        self.iface.write_register(Register::WEEKDAY, data).await?;
+        self.is_battery_power_enabled = true;
+        Ok(())
+    }
+
+    /// Disable usage of backup battery power (default).
+    ///
+    /// Note that this clears the power failed flag.
+    pub async fn disable_backup_battery_power(&mut self) -> Result<(), Error<E>> {
+        let data = self.iface.read_register(Register::WEEKDAY).await?;
+        let data = data & !BitFlags::VBATEN;
+        self.iface.write_register(Register::WEEKDAY, data).await?;
+        self.is_battery_power_enabled = false;
+        Ok(())
+    }
+}
@@ -0,0 +1,395 @@
+//! Asynchronous date/time methods
+
+use super::Mcp794xxAsync;
+use crate::common::conversion::{
+    decimal_to_packed_bcd, hours_from_register, hours_to_register, packed_bcd_to_decimal,
+};
+#[cfg(feature = "rtcc")]
+use crate::common::datetime::{
+    date_from_registers, date_to_registers, datetime_from_naive, time_from_registers,
+    time_to_registers,
+};
+use crate::common::datetime::{
+    datetime_from_registers, datetime_from_timestamp, datetime_to_registers, is_possible_carry,
+    seconds_to_register, CONSISTENT_READ_ATTEMPTS,
+};
+use crate::{interface, BitFlags, DateTime, Error, Hours, Register};
+#[cfg(feature = "rtcc")]
+use crate::{NaiveDate, NaiveDateTime, NaiveTime};
+use embedded_hal_async::delay::DelayNs;
+
+impl<DI, E, IC> Mcp794xxAsync<DI, IC>
+where
+    DI: interface::AsyncWriteData<Error = Error<E>> + interface::AsyncReadData<Error = Error<E>>,
+{
+    /// Read the date and time.
+    ///
+    /// This device can compensate for leap years up to 2399
+    /// but only the two last year digits are stored so we will return
+    /// the year as in the range 2000-2099.
+    ///
+    /// If the seconds read are 0, the registers are read again until
+    /// they are stable in case a carry was in progress.
+    /// See [`consistent_datetime()`](#method.consistent_datetime).
+    pub async fn read_datetime(&mut self) -> Result<DateTime, Error<E>> {
+        let data = self.read_time_keeping_registers(false).await?;
+        datetime_from_registers(&data)
+    }
+
+    /// Set the date and time.
+    ///
+    /// Note that this clears the power failed flag.
+    pub async fn write_datetime(&mut self, datetime: &DateTime) -> Result<(), Error<E>> {
+        let payload = datetime_to_registers(datetime, self.is_enabled);
+        self.iface.write_data(&payload).await?;
+        self.is_running_in_24h_mode = true;
+        Ok(())
+    }
+
+    /// Read the date and time.
+    ///
+    /// See [`read_datetime()`](#method.read_datetime).
+    #[cfg(feature = "rtcc")]
+    pub async fn datetime(&mut self) -> Result<NaiveDateTime, Error<E>> {
+        self.read_datetime().await.map(NaiveDateTime::from)
+    }
+
+    /// Set the date and time.
+    ///
+    /// Note that this clears the power failed flag.
+    /// This device can compensate for leap years up to 2399
+    /// but only the two last year digits are stored so we only
+    /// support the range 2000-2099.
+    #[cfg(feature = "rtcc")]
+    pub async fn set_datetime(&mut self, datetime: &NaiveDateTime) -> Result<(), Error<E>> {
+        self.write_datetime(&datetime_from_naive(datetime)?).await
+    }
+
+    /// Read the seconds.
+    pub async fn seconds(&mut self) -> Result<u8, Error<E>> {
+        let seconds = self.iface.read_register(Register::SECONDS).await?;
+        Ok(packed_bcd_to_decimal(seconds & !BitFlags::ST))
+    }
+
+    /// Read the minutes.
+    pub async fn minutes(&mut self) -> Result<u8, Error<E>> {
+        let minutes = self.iface.read_register(Register::MINUTES).await?;
+        Ok(packed_bcd_to_decimal(minutes))
+    }
+
+    /// Read the hours.
+    pub async fn hours(&mut self) -> Result<Hours, Error<E>> {
+        let data = self.iface.read_register(Register::HOURS).await?;
+        Ok(hours_from_register(data))
+    }
+
+    /// Read the time.
+    ///
+    /// If the seconds read are 0, the registers are read again until
+    /// they are stable in case a carry was in progress.
+    #[cfg(feature = "rtcc")]
+    pub async fn time(&mut self) -> Result<NaiveTime, Error<E>> {
+        let data = self.read_time_keeping_registers(false).await?;
+        time_from_registers(&data)
+    }
+
+    /// Read the day of the week [1-7].
+    pub async fn weekday(&mut self) -> Result<u8, Error<E>> {
+        let data = self.iface.read_register(Register::WEEKDAY).await?;
+        Ok(packed_bcd_to_decimal(data & 0b111))
+    }
+
+    /// Read the day of the month [1-31].
+    pub async fn day(&mut self) -> Result<u8, Error<E>> {
+        let day = self.iface.read_register(Register::DAY).await?;
+        Ok(packed_bcd_to_decimal(day))
+    }
+
+    /// Read the month [1-12].
+    pub async fn month(&mut self) -> Result<u8, Error<E>> {
+        let value = self.iface.read_register(Register::MONTH).await?;
+        Ok(packed_bcd_to_decimal(value & !BitFlags::LEAPYEAR))
+    }
+
+    /// Read the year.
+    ///
+    /// This device can compensate for leap years up to 2399
+    /// but only the two last year digits are stored so we will return
+    /// the year as in the range 2000-2099.
+    pub async fn year(&mut self) -> Result<u16, Error<E>> {
+        let value = self.iface.read_register(Register::YEAR).await?;
+        Ok(2000 + u16::from(packed_bcd_to_decimal(value)))
+    }
+
+    /// Read the date.
+    ///
+    /// The time is read as well so that if the seconds are 0, the registers
+    /// are read again until they are stable in case a carry was in progress.
+    #[cfg(feature = "rtcc")]
+    pub async fn date(&mut self) -> Result<NaiveDate, Error<E>> {
+        let data: [u8; 7] = self.read_time_keeping_registers(false).await?;
+        let mut date = [0; 3];
+        date.copy_from_slice(&data[Register::DAY as usize..]);
+        date_from_registers(&date)
+    }
+
+    /// Set the seconds [0-59].
+    pub async fn set_seconds(&mut self, seconds: u8) -> Result<(), Error<E>> {
+        Self::check_lt(seconds, 60)?;
+        let value = seconds_to_register(seconds, self.is_enabled);
+        self.iface.write_register(Register::SECONDS, value).await
+    }
+
+    /// Set the minutes [0-59].
+    pub async fn set_minutes(&mut self, minutes: u8) -> Result<(), Error<E>> {
+        Self::check_lt(minutes, 60)?;
+        let minutes = decimal_to_packed_bcd(minutes);
+        self.iface.write_register(Register::MINUTES, minutes).await
+    }
+
+    /// Set the hours.
+    ///
+    /// Changes the operating mode to 12h/24h depending on the parameter.
+    pub async fn set_hours(&mut self, hours: Hours) -> Result<(), Error<E>> {
+        let value = hours_to_register(hours)?;
+        self.iface.write_register(Register::HOURS, value).await?;
+        self.is_running_in_24h_mode = matches!(hours, Hours::H24(_));
+        Ok(())
+    }
+
+    /// Set the time.
+    #[cfg(feature = "rtcc")]
+    pub async fn set_time(&mut self, time: &NaiveTime) -> Result<(), Error<E>> {
+        let payload = time_to_registers(time, self.is_enabled)?;
+        self.iface.write_data(&payload).await?;
+        self.is_running_in_24h_mode = true;
+        Ok(())
+    }
+
+    /// Set the day of the week [1-7].
+    ///
+    /// Note that this clears the power failed flag.
+    pub async fn set_weekday(&mut self, weekday: u8) -> Result<(), Error<E>> {
+        Self::check_lt(weekday, 8)?;
+        Self::check_gt(weekday, 0)?;
+        let value = decimal_to_packed_bcd(weekday);
+        let value = if self.is_battery_power_enabled {
+            value | BitFlags::VBATEN
+        } else {
+            value
+        };
+        self.iface.write_register(Register::WEEKDAY, value).await
+    }
+
+    /// Set the day of the month [1-31].
+    pub async fn set_day(&mut self, day: u8) -> Result<(), Error<E>> {
+        Self::check_lt(day, 32)?;
+        Self::check_gt(day, 0)?;
+        let data = decimal_to_packed_bcd(day);
+        self.iface.write_register(Register::DAY, data).await
+    }
+
+    /// Set the month [1-12].
+    pub async fn set_month(&mut self, month: u8) -> Result<(), Error<E>> {
+        Self::check_lt(month, 13)?;
+        Self::check_gt(month, 0)?;
+        let data = decimal_to_packed_bcd(month);
+        self.iface.write_register(Register::MONTH, data).await
+    }
+
+    /// Set the year [2000-2099].
+    ///
+    /// This device can compensate for leap years up to 2399
+    /// but only the two last year digits are stored so we only
+    /// support the range 2000-2099.
+    pub async fn set_year(&mut self, year: u16) -> Result<(), Error<E>> {
+        Self::check_lt(year, 2100)?;
+        let value = decimal_to_packed_bcd((year - 2000) as u8);
+        self.iface.write_register(Register::YEAR, value).await
+    }
+
+    /// Set the date.
+    #[cfg(feature = "rtcc")]
+    pub async fn set_date(&mut self, date: &NaiveDate) -> Result<(), Error<E>> {
+        let payload = date_to_registers(date)?;
+        self.iface.write_data(&payload).await
+    }
+
+    /// Returns whether the current year is a leap year.
+    pub async fn is_leap_year(&mut self) -> Result<bool, Error<E>> {
+        let data = self.iface.read_register(Register::MONTH).await?;
+        Ok((data & BitFlags::LEAPYEAR) != 0)
+    }
+
+    /// Read the date and time as a Unix timestamp (seconds since
+    /// 1970-01-01 00:00:00).
+    ///
+    /// The date and time stored in the device are interpreted as UTC.
+    pub async fn timestamp(&mut self) -> Result<i64, Error<E>> {
+        Ok(self.read_datetime().await?.timestamp())
+    }
+
+    /// Set the date and time from a Unix timestamp (seconds since
+    /// 1970-01-01 00:00:00).
+    ///
+    /// The date and time are stored as UTC.
+    /// Only the range 2000-2099 is supported (timestamps
+    /// `[946_684_800-4_102_444_799]`).
+    /// `Error::InvalidInputData` will be returned for other timestamps.
+    ///
+    /// Note that this clears the power failed flag.
+    pub async fn set_timestamp(&mut self, timestamp: i64) -> Result<(), Error<E>> {
+        let datetime = datetime_from_timestamp(timestamp)?;
+        self.write_datetime(&datetime).await
+    }
+
+    /// Read the date and time making sure no rollover happened during the read.
+    ///
+    /// The registers are read repeatedly until two consecutive reads are
+    /// equal. Use this instead of composing the individual elements
+    /// like [`seconds()`](#method.seconds) and [`minutes()`](#method.minutes),
+    /// which can mix values across a rollover.
+    ///
+    /// The registers are read at most 4 times. If no two consecutive reads
+    /// are equal, the last read is returned, which may still mix values
+    /// across a rollover. This can only happen if each read takes around a
+    /// second, e.g. on a heavily contended bus.
+    pub async fn consistent_datetime(&mut self) -> Result<DateTime, Error<E>> {
+        let data = self.read_time_keeping_registers(true).await?;
+        datetime_from_registers(&data)
+    }
+
+    // Reads the first N registers starting at SECONDS. If `always_check` is
+    // set or the seconds are 0, the registers are read again until two
+    // consecutive reads are equal or the attempts are exhausted, in which
+    // case the last read is returned.
+    async fn read_time_keeping_registers<const N: usize>(
+        &mut self,
+        always_check: bool,
+    ) -> Result<[u8; N], Error<E>> {
+        let mut data = [0; N];
+        self.iface.read_data(Register::SECONDS, &mut data).await?;
+        if !always_check && !is_possible_carry(&data) {
+            return Ok(data);
+        }
+        for _ in 0..CONSISTENT_READ_ATTEMPTS {
+            let mut next = [0; N];
+            self.iface.read_data(Register::SECONDS, &mut next).await?;
+            if next == data {
+                break;
+            }
+            data = next;
+        }
+        Ok(data)
+    }
+
+    #[allow(clippy::needless_pass_by_value)]
+    fn check_lt<T: PartialOrd>(value: T, reference: T) -> Result<(), Error<E>> {
+        if value < reference {
+            Ok(())
+        } else {
+            Err(Error::InvalidInputData)
+        }
+    }
+
+    #[allow(clippy::needless_pass_by_value)]
+    fn check_gt<T: PartialOrd>(value: T, reference: T) -> Result<(), Error<E>> {
+        if value > reference {
+            Ok(())
+        } else {
+            Err(Error::InvalidInputData)
+        }
+    }
+}
+
+impl<DI, E, IC> Mcp794xxAsync<DI, IC>
+where
+    DI: interface::AsyncWriteData<Error = Error<E>> + interface::AsyncReadData<Error = Error<E>>,
+{
+    /// Set the date and time stopping the oscillator during the write.
+    ///
+    /// As recommended in the datasheet, the oscillator is stopped and
+    /// the driver waits until it is no longer running before the time-keeping
+    /// registers are written. This prevents a rollover from corrupting the
+    /// registers mid-write. Afterwards the oscillator is started again if it
+    /// was enabled and the driver waits until it is running.
+    ///
+    /// If the oscillator does not stop or start, `Error::OscillatorStopTimeout`
+    /// or `Error::OscillatorStartTimeout` will be returned.
+    /// See [`set_oscillator_poll_attempts()`](#method.set_oscillator_poll_attempts).
+    ///
+    /// Note that this clears the power failed flag.
+    pub async fn set_datetime_safely<D: DelayNs>(
+        &mut self,
+        datetime: &DateTime,
+        delay: &mut D,
+    ) -> Result<(), Error<E>> {
+        let payload = datetime_to_registers(datetime, false);
+        self.write_stopped(&payload, delay).await?;
+        self.is_running_in_24h_mode = true;
+        Ok(())
+    }
+
+    /// Set the time stopping the oscillator during the write.
+    ///
+    /// See [`set_datetime_safely()`](#method.set_datetime_safely).
+    #[cfg(feature = "rtcc")]
+    pub async fn set_time_safely<D: DelayNs>(
+        &mut self,
+        time: &NaiveTime,
+        delay: &mut D,
+    ) -> Result<(), Error<E>> {
+        let payload = time_to_registers(time, false)?;
+        self.write_stopped(&payload, delay).await?;
+        self.is_running_in_24h_mode = true;
+        Ok(())
+    }
+
+    /// Set the maximum amount of times the oscillator is polled while
+    /// waiting for it to stop or start (default: 1000).
+    ///
+    /// The driver waits 1ms between attempts.
+    pub fn set_oscillator_poll_attempts(&mut self, attempts: u32) {
+        self.oscillator_poll_attempts = attempts;
+    }
+
+    // Writes a payload starting at the SECONDS register with the ST bit
+    // cleared while the oscillator is stopped.
+    async fn write_stopped<D: DelayNs>(
+        &mut self,
+        payload: &[u8],
+        delay: &mut D,
+    ) -> Result<(), Error<E>> {
+        let seconds = payload[1];
+        self.iface
+            .write_register(Register::SECONDS, seconds)
+            .await?;
+        self.wait_oscillator(false, delay).await?;
+        self.iface.write_data(payload).await?;
+        if self.is_enabled {
+            self.iface
+                .write_register(Register::SECONDS, seconds | BitFlags::ST)
+                .await?;
+            self.wait_oscillator(true, delay).await?;
+        }
+        Ok(())
+    }
+
+    async fn wait_oscillator<D: DelayNs>(
+        &mut self,
+        running: bool,
+        delay: &mut D,
+    ) -> Result<(), Error<E>> {
+        for _ in 0..self.oscillator_poll_attempts {
+            if self.is_oscillator_running().await? == running {
+                return Ok(());
+            }
+            delay.delay_ms(1).await;
+        }
+        if running {
+            Err(Error::OscillatorStartTimeout)
+        } else {
+            Err(Error::OscillatorStopTimeout)
+        }
+    }
+}
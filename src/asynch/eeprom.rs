@@ -0,0 +1,233 @@
+//! Asynchronous EEPROM methods
+
+use super::Mcp794xxAsync;
+use crate::eeprom::{
+    is_eeprom_address, is_eeprom_data_range, is_protected_eeprom_address,
+    is_protected_eeprom_data_range, page_writes, write_protection_to_register,
+};
+use crate::{interface, marker, EepromWriteProtection, Error};
+
+impl<DI, E, IC> Mcp794xxAsync<DI, IC>
+where
+    DI: interface::AsyncWriteData<Error = Error<E>> + interface::AsyncReadData<Error = Error<E>>,
+    IC: marker::WithProtectedEeprom,
+{
+    /// Read a single byte from an address in the protected EEPROM.
+    ///
+    /// Valid addresses are in the range `[0xF0-0xF7]`.
+    /// `Error::InvalidInputData` will be returned for invalid addresses.
+    pub async fn read_protected_eeprom_byte(&mut self, address: u8) -> Result<u8, Error<E>> {
+        if !is_protected_eeprom_address(address) {
+            return Err(Error::InvalidInputData);
+        }
+        let mut data = [0];
+        self.iface
+            .read_protected_eeprom_data(address, &mut data)
+            .await
+            .and(Ok(data[0]))
+    }
+
+    /// Read protected EEPROM starting in an address as many bytes as
+    /// necessary to fill the data array provided.
+    ///
+    /// Valid addresses are in the range `[0xF0-0xF7]`.
+    /// `Error::InvalidInputData` will be returned for invalid addresses or
+    /// if the reading would overflow the size of the protected EEPROM.
+    pub async fn read_protected_eeprom_data(
+        &mut self,
+        address: u8,
+        data: &mut [u8],
+    ) -> Result<(), Error<E>> {
+        if !is_protected_eeprom_data_range(address, data.len()) {
+            return Err(Error::InvalidInputData);
+        }
+        self.iface.read_protected_eeprom_data(address, data).await
+    }
+
+    /// Unlock protected EEPROM and write a single byte to an address.
+    ///
+    /// Valid addresses are in the range `[0xF0-0xF7]`.
+    /// `Error::InvalidInputData` will be returned for invalid addresses.
+    pub async fn write_protected_eeprom_byte(
+        &mut self,
+        address: u8,
+        data: u8,
+    ) -> Result<(), Error<E>> {
+        if !is_protected_eeprom_address(address) {
+            return Err(Error::InvalidInputData);
+        }
+        self.iface
+            .write_protected_eeprom_data(&[address, data])
+            .await?;
+        self.wait_eeprom_write().await
+    }
+
+    /// Write data array starting in an address in the protected EEPROM.
+    ///
+    /// Valid addresses are in the range `[0xF0-0xF7]`.
+    /// `Error::InvalidInputData` will be returned for invalid addresses or
+    /// if the writing would overflow the size of the protected EEPROM.
+    pub async fn write_protected_eeprom_data(
+        &mut self,
+        address: u8,
+        data: &[u8],
+    ) -> Result<(), Error<E>> {
+        if !is_protected_eeprom_data_range(address, data.len()) {
+            return Err(Error::InvalidInputData);
+        }
+        let mut payload = [0; 9]; // max size
+        payload[0] = address;
+        payload[1..=data.len()].copy_from_slice(data);
+        self.iface
+            .write_protected_eeprom_data(&payload[..=data.len()])
+            .await?;
+        self.wait_eeprom_write().await
+    }
+}
+
+impl<DI, E, IC> Mcp794xxAsync<DI, IC>
+where
+    DI: interface::AsyncWriteData<Error = Error<E>> + interface::AsyncReadData<Error = Error<E>>,
+    IC: marker::WithEeprom,
+{
+    /// Set the EEPROM block write protection
+    pub async fn set_eeprom_write_protection(
+        &mut self,
+        protection: EepromWriteProtection,
+    ) -> Result<(), Error<E>> {
+        self.iface
+            .write_eeprom_status(write_protection_to_register(protection))
+            .await?;
+        self.wait_eeprom_write().await
+    }
+
+    /// Read a single byte from an address in EEPROM.
+    ///
+    /// Valid addresses are in the range `[0x00-0x7F]` (`[0x00-0xFF]` for
+    /// devices with 2 Kbit EEPROM).
+    /// `Error::InvalidInputData` will be returned for invalid addresses.
+    pub async fn read_eeprom_byte(&mut self, address: u8) -> Result<u8, Error<E>> {
+        if is_eeprom_address(address, IC::EEPROM_SIZE) {
+            self.iface.read_eeprom_byte(address).await
+        } else {
+            Err(Error::InvalidInputData)
+        }
+    }
+
+    /// Read EEPROM starting in an address as many bytes as necessary to fill
+    /// the data array provided.
+    ///
+    /// Valid addresses are in the range `[0x00-0x7F]` (`[0x00-0xFF]` for
+    /// devices with 2 Kbit EEPROM).
+    /// `Error::InvalidInputData` will be returned for invalid addresses or
+    /// if the reading would overflow the size of the EEPROM.
+    pub async fn read_eeprom_data(&mut self, address: u8, data: &mut [u8]) -> Result<(), Error<E>> {
+        if is_eeprom_data_range(address, data.len(), IC::EEPROM_SIZE) {
+            self.iface.read_eeprom_data(address, data).await
+        } else {
+            Err(Error::InvalidInputData)
+        }
+    }
+
+    /// Write a single byte to an address in EEPROM.
+    ///
+    /// Valid addresses are in the range `[0x00-0x7F]` (`[0x00-0xFF]` for
+    /// devices with 2 Kbit EEPROM).
+    /// `Error::InvalidInputData` will be returned for invalid addresses.
+    pub async fn write_eeprom_byte(&mut self, address: u8, data: u8) -> Result<(), Error<E>> {
+        if is_eeprom_address(address, IC::EEPROM_SIZE) {
+            self.iface.write_eeprom_byte(address, data).await?;
+            self.wait_eeprom_write().await
+        } else {
+            Err(Error::InvalidInputData)
+        }
+    }
+
+    /// Write data array starting in an address in EEPROM.
+    ///
+    /// The data is split into several writes at the 8-byte EEPROM page
+    /// boundaries and the completion of each write cycle is waited for.
+    ///
+    /// Valid addresses are in the range `[0x00-0x7F]` (`[0x00-0xFF]` for
+    /// devices with 2 Kbit EEPROM).
+    /// `Error::InvalidInputData` will be returned for invalid addresses or
+    /// if the writing would overflow the size of the EEPROM.
+    pub async fn write_eeprom_data(&mut self, address: u8, data: &[u8]) -> Result<(), Error<E>> {
+        if !is_eeprom_data_range(address, data.len(), IC::EEPROM_SIZE) {
+            return Err(Error::InvalidInputData);
+        }
+        for page in page_writes(address, data) {
+            self.iface.write_eeprom_data(page.payload()).await?;
+            self.wait_eeprom_write().await?;
+        }
+        Ok(())
+    }
+}
+
+impl<DI, E, IC> Mcp794xxAsync<DI, IC>
+where
+    DI: interface::AsyncReadData<Error = Error<E>>,
+{
+    /// Set the maximum amount of times the device is polled while waiting
+    /// for an EEPROM write cycle to complete (default: 1000).
+    ///
+    /// Each attempt takes roughly the time needed to transfer one byte
+    /// on the bus. A write cycle can take up to 5ms.
+    /// If the write cycle has not completed after this amount of attempts,
+    /// `Error::EepromWriteTimeout` will be returned.
+    pub fn set_eeprom_write_poll_attempts(&mut self, attempts: u32) {
+        self.eeprom_poll_attempts = attempts;
+    }
+
+    async fn wait_eeprom_write(&mut self) -> Result<(), Error<E>> {
+        for _ in 0..self.eeprom_poll_attempts {
+            if !self.iface.is_eeprom_write_in_progress().await? {
+                return Ok(());
+            }
+        }
+        Err(Error::EepromWriteTimeout)
+    }
+}
+
+impl<DI, E, IC> Mcp794xxAsync<DI, IC>
+where
+    DI: interface::AsyncReadCurrent<Error = Error<E>>,
+{
+    /// Read a single byte from the current address in EEPROM.
+    ///
+    /// The current address corresponds to the last accessed address
+    /// (including addresses accessed in SRAM/RTCC) incremented by 1.
+    pub async fn read_eeprom_current_byte(&mut self) -> Result<u8, Error<E>> {
+        self.iface.read_eeprom().await
+    }
+}
+
+impl<DI, E, IC> Mcp794xxAsync<DI, IC>
+where
+    DI: interface::AsyncReadData<Error = Error<E>>,
+    IC: marker::WithEui48,
+{
+    /// Read pre-programmed EUI-48 node address from EEPROM.
+    pub async fn read_eui48(&mut self) -> Result<[u8; 6], Error<E>> {
+        let mut data = [0; 6];
+        self.iface
+            .read_protected_eeprom_data(0xF2, &mut data)
+            .await
+            .and(Ok(data))
+    }
+}
+
+impl<DI, E, IC> Mcp794xxAsync<DI, IC>
+where
+    DI: interface::AsyncReadData<Error = Error<E>>,
+    IC: marker::WithEui64,
+{
+    /// Read pre-programmed EUI-64 node address from EEPROM.
+    pub async fn read_eui64(&mut self) -> Result<[u8; 8], Error<E>> {
+        let mut data = [0; 8];
+        self.iface
+            .read_protected_eeprom_data(0xF0, &mut data)
+            .await
+            .and(Ok(data))
+    }
+}
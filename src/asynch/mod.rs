@@ -0,0 +1,214 @@
+//! Asynchronous driver based on the [`embedded-hal-async`] traits.
+//!
+//! [`embedded-hal-async`]: https://docs.rs/embedded-hal-async
+
+use crate::common::{status_from_registers, trimming_to_register, STATUS_REGISTER_COUNT};
+use crate::interface::{self, AsyncI2cInterface};
+use crate::{
+    ic, AlarmOutputPinPolarity, BitFlags, Config, Error, OutputPinLevel, Register, SqWFreq, Status,
+    DEFAULT_EEPROM_POLL_ATTEMPTS, DEFAULT_OSCILLATOR_POLL_ATTEMPTS,
+};
+use core::marker::PhantomData;
+use embedded_hal_async::i2c;
+
+mod alarm;
+mod battery_power;
+mod datetime;
+mod eeprom;
+mod register_dump;
+mod sram;
+
+/// Asynchronous MCP794xx RTCC driver
+///
+/// This offers the same functionality as [`Mcp794xx`](crate::Mcp794xx)
+/// but all methods are `async`.
+#[derive(Debug)]
+pub struct Mcp794xxAsync<DI, IC> {
+    pub(crate) iface: DI,
+    pub(crate) is_enabled: bool,
+    pub(crate) is_battery_power_enabled: bool,
+    pub(crate) is_running_in_24h_mode: bool,
+    pub(crate) control: Config,
+    pub(crate) alarm_output_pin_polarity: AlarmOutputPinPolarity,
+    pub(crate) eeprom_poll_attempts: u32,
+    pub(crate) oscillator_poll_attempts: u32,
+    pub(crate) _ic: PhantomData<IC>,
+}
+
+macro_rules! create_destroy_async_i2c {
+    ($ic:ident, $create:ident) => {
+        impl<I2C, E> Mcp794xxAsync<AsyncI2cInterface<I2C>, ic::$ic>
+        where
+            I2C: i2c::I2c<Error = E>,
+        {
+            /// Create a new instance of the device.
+            pub fn $create(i2c: I2C) -> Self {
+                Mcp794xxAsync {
+                    iface: AsyncI2cInterface { i2c },
+                    is_enabled: false,
+                    is_battery_power_enabled: false,
+                    is_running_in_24h_mode: false,
+                    control: Config {
+                        bits: BitFlags::OUT,
+                    },
+                    alarm_output_pin_polarity: AlarmOutputPinPolarity::Low,
+                    eeprom_poll_attempts: DEFAULT_EEPROM_POLL_ATTEMPTS,
+                    oscillator_poll_attempts: DEFAULT_OSCILLATOR_POLL_ATTEMPTS,
+                    _ic: PhantomData,
+                }
+            }
+
+            /// Destroy driver instance, return I²C bus instance.
+            pub fn destroy(self) -> I2C {
+                self.iface.i2c
+            }
+        }
+    };
+}
+create_destroy_async_i2c!(Mcp7940n, new_mcp7940n);
+create_destroy_async_i2c!(Mcp7940m, new_mcp7940m);
+create_destroy_async_i2c!(Mcp79400, new_mcp79400);
+create_destroy_async_i2c!(Mcp79401, new_mcp79401);
+create_destroy_async_i2c!(Mcp79402, new_mcp79402);
+create_destroy_async_i2c!(Mcp79410, new_mcp79410);
+create_destroy_async_i2c!(Mcp79411, new_mcp79411);
+create_destroy_async_i2c!(Mcp79412, new_mcp79412);
+
+impl<DI, E, IC> Mcp794xxAsync<DI, IC>
+where
+    DI: interface::AsyncWriteData<Error = Error<E>> + interface::AsyncReadData<Error = Error<E>>,
+{
+    /// Enable the oscillator (set the clock running).
+    pub async fn enable(&mut self) -> Result<(), Error<E>> {
+        let seconds = self.iface.read_register(Register::SECONDS).await?;
+        self.iface
+            .write_register(Register::SECONDS, seconds | BitFlags::ST)
+            .await?;
+        self.is_enabled = true;
+        Ok(())
+    }
+
+    /// Disable the oscillator (stops the clock) (default).
+    pub async fn disable(&mut self) -> Result<(), Error<E>> {
+        let seconds = self.iface.read_register(Register::SECONDS).await?;
+        self.iface
+            .write_register(Register::SECONDS, seconds & !BitFlags::ST)
+            .await?;
+        self.is_enabled = false;
+        Ok(())
+    }
+
+    /// Returns whether the oscillator is running.
+    #[allow(clippy::wrong_self_convention)]
+    pub async fn is_oscillator_running(&mut self) -> Result<bool, Error<E>> {
+        let data = self.iface.read_register(Register::WEEKDAY).await?;
+        Ok((data & BitFlags::OSCRUN) != 0)
+    }
+
+    /// Enable usage of external oscillator source.
+    pub async fn enable_external_oscillator(&mut self) -> Result<(), Error<E>> {
+        self.write_control(self.control.with_high(BitFlags::EXTOSC))
+            .await
+    }
+
+    /// Disable usage of external oscillator source (Will use internal source).
+    pub async fn disable_external_oscillator(&mut self) -> Result<(), Error<E>> {
+        self.write_control(self.control.with_low(BitFlags::EXTOSC))
+            .await
+    }
+
+    /// Enable square-wave output.
+    ///
+    /// Note that this is not available when running on backup battery power.
+    pub async fn enable_square_wave(&mut self) -> Result<(), Error<E>> {
+        self.write_control(self.control.with_high(BitFlags::SQWEN))
+            .await
+    }
+
+    /// Disable square-wave output.
+    pub async fn disable_square_wave(&mut self) -> Result<(), Error<E>> {
+        self.write_control(self.control.with_low(BitFlags::SQWEN))
+            .await
+    }
+
+    /// Set square-wave output frequency.
+    ///
+    /// Note that this setting will be ignored if the square-wave output is not
+    /// enabled or digital trimming is enabled.
+    pub async fn set_square_wave_frequency(&mut self, frequency: SqWFreq) -> Result<(), Error<E>> {
+        self.write_control(self.control.with_square_wave_frequency(frequency))
+            .await
+    }
+
+    /// Set output pin logic level.
+    ///
+    /// Note that this setting will be ignored if the square-wave output or any
+    /// of the alarm interrupt outputs are enabled.
+    pub async fn set_output_pin(&mut self, level: OutputPinLevel) -> Result<(), Error<E>> {
+        let control = match level {
+            OutputPinLevel::High => self.control.with_high(BitFlags::OUT),
+            OutputPinLevel::Low => self.control.with_low(BitFlags::OUT),
+        };
+        self.write_control(control).await
+    }
+
+    /// Enable coarse trim mode.
+    pub async fn enable_coarse_trim(&mut self) -> Result<(), Error<E>> {
+        self.write_control(self.control.with_high(BitFlags::CRSTRIM))
+            .await
+    }
+
+    /// Disable coarse trim mode.
+    pub async fn disable_coarse_trim(&mut self) -> Result<(), Error<E>> {
+        self.write_control(self.control.with_low(BitFlags::CRSTRIM))
+            .await
+    }
+
+    /// Set digital trimming value.
+    ///
+    /// See [`Mcp794xx::set_trimming()`](crate::Mcp794xx::set_trimming).
+    pub async fn set_trimming(&mut self, value: i8) -> Result<(), Error<E>> {
+        self.iface
+            .write_register(Register::OSCTRIM, trimming_to_register(value))
+            .await
+    }
+
+    /// Read the complete device status in a single transfer.
+    ///
+    /// This reads the registers from seconds up to the alarm 1 weekday at
+    /// once and decodes the status and configuration bits.
+    pub async fn status(&mut self) -> Result<Status, Error<E>> {
+        let mut data = [0; STATUS_REGISTER_COUNT];
+        self.iface.read_data(Register::SECONDS, &mut data).await?;
+        Ok(status_from_registers(&data))
+    }
+
+    /// Read the device state and update the driver state accordingly.
+    ///
+    /// See [`Mcp794xx::sync()`](crate::Mcp794xx::sync).
+    pub async fn sync(&mut self) -> Result<(), Error<E>> {
+        let mut data = [0; 8];
+        self.iface.read_data(Register::SECONDS, &mut data).await?;
+        let alm0_weekday = self.iface.read_register(Register::ALM0WKDAY).await?;
+        self.is_enabled = data[Register::SECONDS as usize] & BitFlags::ST != 0;
+        self.is_running_in_24h_mode = data[Register::HOURS as usize] & BitFlags::H24_H12 == 0;
+        self.is_battery_power_enabled = data[Register::WEEKDAY as usize] & BitFlags::VBATEN != 0;
+        self.control = Config {
+            bits: data[Register::CONTROL as usize],
+        };
+        self.alarm_output_pin_polarity = if alm0_weekday & BitFlags::ALMPOL != 0 {
+            AlarmOutputPinPolarity::High
+        } else {
+            AlarmOutputPinPolarity::Low
+        };
+        Ok(())
+    }
+
+    async fn write_control(&mut self, control: Config) -> Result<(), Error<E>> {
+        self.iface
+            .write_register(Register::CONTROL, control.bits)
+            .await?;
+        self.control = control;
+        Ok(())
+    }
+}
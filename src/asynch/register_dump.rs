@@ -0,0 +1,41 @@
+//! Asynchronous register dump methods
+
+use super::Mcp794xxAsync;
+use crate::register_dump::{dump_from_registers_and_sram, DUMP_WITH_SRAM_SIZE, REGISTER_COUNT};
+use crate::{interface, marker, Error, Register, RegisterDump};
+
+impl<DI, E, IC> Mcp794xxAsync<DI, IC>
+where
+    DI: interface::AsyncReadData<Error = Error<E>>,
+{
+    /// Read the registers `[0x00-0x1F]` for diagnostics.
+    pub async fn dump_registers(&mut self) -> Result<RegisterDump, Error<E>> {
+        let mut registers = [0; REGISTER_COUNT];
+        self.iface
+            .read_data(Register::SECONDS, &mut registers)
+            .await?;
+        Ok(RegisterDump::from_registers(registers))
+    }
+
+    /// Read the registers `[0x00-0x1F]` and the SRAM `[0x20-0x5F]` for
+    /// diagnostics in a single transfer.
+    pub async fn dump_registers_and_sram(&mut self) -> Result<RegisterDump, Error<E>> {
+        let mut data = [0; DUMP_WITH_SRAM_SIZE];
+        self.iface.read_data(Register::SECONDS, &mut data).await?;
+        Ok(dump_from_registers_and_sram(&data))
+    }
+}
+
+impl<DI, E, IC> Mcp794xxAsync<DI, IC>
+where
+    DI: interface::AsyncReadData<Error = Error<E>>,
+    IC: marker::WithEeprom,
+{
+    /// Read the EEPROM status register.
+    ///
+    /// This can be added to a register dump with
+    /// [`RegisterDump::with_eeprom_status()`](struct.RegisterDump.html#method.with_eeprom_status).
+    pub async fn read_eeprom_status(&mut self) -> Result<u8, Error<E>> {
+        self.iface.read_eeprom_status().await
+    }
+}
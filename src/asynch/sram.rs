@@ -0,0 +1,65 @@
+//! Asynchronous SRAM methods
+
+use super::Mcp794xxAsync;
+use crate::common::sram::{is_sram_address, is_sram_data_range};
+use crate::{interface, Error};
+
+impl<DI, E, IC> Mcp794xxAsync<DI, IC>
+where
+    DI: interface::AsyncWriteData<Error = Error<E>> + interface::AsyncReadData<Error = Error<E>>,
+{
+    /// Read a single byte from an address.
+    ///
+    /// Valid addresses are from 0x20 to 0x5F. Otherwise an
+    /// Error::InvalidInputData will be returned.
+    pub async fn read_sram_byte(&mut self, address: u8) -> Result<u8, Error<E>> {
+        if !is_sram_address(address) {
+            return Err(Error::InvalidInputData);
+        }
+        self.iface.read_register(address).await
+    }
+
+    /// Write a single byte to an address.
+    ///
+    /// Valid addresses are from 0x20 to 0x5F. Otherwise an
+    /// Error::InvalidInputData will be returned.
+    pub async fn write_sram_byte(&mut self, address: u8, data: u8) -> Result<(), Error<E>> {
+        if !is_sram_address(address) {
+            return Err(Error::InvalidInputData);
+        }
+        self.iface.write_register(address, data).await
+    }
+
+    /// Read SRAM starting in an address as many bytes as necessary to fill
+    /// the data array provided.
+    pub async fn read_sram_data(&mut self, address: u8, data: &mut [u8]) -> Result<(), Error<E>> {
+        if !is_sram_data_range(address, data.len()) {
+            return Err(Error::InvalidInputData);
+        }
+        self.iface.read_data(address, data).await
+    }
+
+    /// Write data array to SRAM starting in an address.
+    pub async fn write_sram_data(&mut self, address: u8, data: &[u8]) -> Result<(), Error<E>> {
+        if !is_sram_data_range(address, data.len()) {
+            return Err(Error::InvalidInputData);
+        }
+        let mut payload = [0; 65]; // max size
+        payload[0] = address;
+        payload[1..=data.len()].copy_from_slice(data);
+        self.iface.write_data(&payload[..=data.len()]).await
+    }
+}
+
+impl<DI, E, IC> Mcp794xxAsync<DI, IC>
+where
+    DI: interface::AsyncReadCurrent<Error = Error<E>>,
+{
+    /// Read a single byte from the current address.
+    ///
+    /// The current address corresponds to the last accessed address
+    /// (including addresses accessed in EEPROM) incremented by 1.
+    pub async fn read_sram_current_byte(&mut self) -> Result<u8, Error<E>> {
+        self.iface.read().await
+    }
+}
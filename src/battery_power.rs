@@ -1,7 +1,11 @@
 //! Backup battery power methods
 
 use crate::common::conversion::{hours_from_register, packed_bcd_to_decimal};
-use crate::{interface, marker, BitFlags, Error, Mcp794xx, PowerFailDateTime, Register};
+use crate::common::datetime::{datetime_from_registers, hours_to_h24};
+use crate::{
+    interface, marker, BitFlags, DateTime, Error, Mcp794xx, PowerFailDateTime, PowerOutage,
+    Register,
+};
 
 impl<DI, E, IC> Mcp794xx<DI, IC>
 where
@@ -37,16 +41,28 @@ where
         self.get_power_fail(Register::PWRUPMIN)
     }
 
+    /// Returns the power outage recorded in the power-fail time-stamps with
+    /// the full date/time of power down and power up.
+    ///
+    /// The device does not store the year so it is inferred from the current
+    /// date as the latest year in which the time-stamp is not in the future.
+    /// This handles outages crossing a new year but outages longer than a
+    /// year cannot be detected. The seconds are not stored either and are 0.
+    ///
+    /// Note that the registers need to be cleared by calling
+    /// [`clear_power_failed()`](#method.clear_power_failed)
+    pub fn get_power_outage(&mut self) -> Result<PowerOutage, Error<E>> {
+        let mut now = [0; 7];
+        self.iface.read_data(Register::SECONDS, &mut now)?;
+        let mut timestamps = [0; 8];
+        self.iface.read_data(Register::PWRDNMIN, &mut timestamps)?;
+        power_outage_from_registers(&now, &timestamps)
+    }
+
     fn get_power_fail(&mut self, starting_register: u8) -> Result<PowerFailDateTime, Error<E>> {
         let mut data = [0; 4];
         self.iface.read_data(starting_register, &mut data)?;
-        Ok(PowerFailDateTime {
-            minute: packed_bcd_to_decimal(data[0]),
-            hour: hours_from_register(data[1]),
-            day: packed_bcd_to_decimal(data[2]),
-            weekday: data[3] >> 5,
-            month: packed_bcd_to_decimal(data[3] & 0b0001_1111),
-        })
+        Ok(power_fail_from_registers(&data))
     }
 
     /// Enable usage of backup battery power.
@@ -71,3 +87,68 @@ where
         Ok(())
     }
 }
+
+// Decodes the PWRxxMIN..PWRxxMTH registers.
+pub(crate) fn power_fail_from_registers(data: &[u8; 4]) -> PowerFailDateTime {
+    PowerFailDateTime {
+        minute: packed_bcd_to_decimal(data[0]),
+        hour: hours_from_register(data[1]),
+        day: packed_bcd_to_decimal(data[2]),
+        weekday: data[3] >> 5,
+        month: packed_bcd_to_decimal(data[3] & 0b0001_1111),
+    }
+}
+
+// Decodes the PWRDNMIN..PWRUPMTH registers with the SECONDS..YEAR registers
+// as the reference to infer the years.
+pub(crate) fn power_outage_from_registers<E>(
+    now: &[u8; 7],
+    timestamps: &[u8; 8],
+) -> Result<PowerOutage, Error<E>> {
+    let now = datetime_from_registers(now)?;
+    let mut power_down = [0; 4];
+    let mut power_up = [0; 4];
+    power_down.copy_from_slice(&timestamps[..4]);
+    power_up.copy_from_slice(&timestamps[4..]);
+    let power_up = power_fail_to_datetime(&power_fail_from_registers(&power_up), &now)?;
+    let power_down = power_fail_to_datetime(&power_fail_from_registers(&power_down), &power_up)?;
+    Ok(PowerOutage {
+        power_down,
+        power_up,
+    })
+}
+
+// Returns the latest date/time matching the time-stamp which is not after
+// the reference. A 29th of February may be up to 8 years back.
+fn power_fail_to_datetime<E>(
+    timestamp: &PowerFailDateTime,
+    reference: &DateTime,
+) -> Result<DateTime, Error<E>> {
+    let hour = hours_to_h24(timestamp.hour);
+    if hour > 23 || timestamp.minute > 59 {
+        return Err(Error::InvalidTimeData(
+            hour.into(),
+            timestamp.minute.into(),
+            0,
+        ));
+    }
+    let year = reference.year();
+    (year.saturating_sub(8).max(2000)..=year)
+        .rev()
+        .filter_map(|year| {
+            DateTime::new(
+                year,
+                timestamp.month,
+                timestamp.day,
+                hour,
+                timestamp.minute,
+                0,
+            )
+        })
+        .find(|datetime| datetime <= reference)
+        .ok_or(Error::InvalidDateData(
+            year.into(),
+            timestamp.month.into(),
+            timestamp.day.into(),
+        ))
+}
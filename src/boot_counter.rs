@@ -0,0 +1,126 @@
+//! Boot counter and uptime accumulator in the battery-backed SRAM
+
+use crate::common::crc16;
+use crate::common::datetime::to_seconds_since_2000;
+use crate::common::sram::is_sram_data_range;
+use crate::{interface, BitFlags, Error, Mcp794xx, Register};
+use core::time::Duration;
+
+/// Boot counter and uptime accumulator in the battery-backed SRAM.
+///
+/// Created with [`Mcp794xx::init_boot_counter()`].
+///
+/// The region takes [`BOOT_COUNTER_SIZE`] bytes: the boot count, the
+/// accumulated uptime in seconds and the date/time of the last update as
+/// seconds since 2000-01-01, followed by a CRC-16 (CCITT).
+#[derive(Debug)]
+pub struct BootCounter<'a, DI, IC> {
+    rtc: &'a mut Mcp794xx<DI, IC>,
+    address: u8,
+    boot_count: u32,
+    uptime: u32,
+    last_update: u32,
+    was_lost: bool,
+}
+
+/// Size of the [`BootCounter`] SRAM region in bytes
+pub const BOOT_COUNTER_SIZE: usize = 14;
+
+impl<DI, E, IC> Mcp794xx<DI, IC>
+where
+    DI: interface::WriteData<Error = Error<E>> + interface::ReadData<Error = Error<E>>,
+{
+    /// Increment the boot counter stored in the SRAM at `address`.
+    ///
+    /// This should be called once after each MCU reset. The time elapsed
+    /// since the last update before the reset is not added to the uptime.
+    ///
+    /// If the CRC does not match or the oscillator has not been started
+    /// (ST cleared), the SRAM contents are considered lost and the counters
+    /// start again from zero. After the device has been without primary and
+    /// backup power, the RTCC registers are back in their reset state where
+    /// ST is cleared. Note that PWRFAIL is only set if the backup battery was
+    /// enabled so it cannot be used to detect this. OSCRUN is not used either
+    /// since it is also cleared for a short time after the oscillator is
+    /// started.
+    /// See [`BootCounter::was_lost()`].
+    ///
+    /// `Error::InvalidInputData` will be returned if the region does not
+    /// fit in the SRAM `[0x20-0x5F]`.
+    pub fn init_boot_counter(&mut self, address: u8) -> Result<BootCounter<'_, DI, IC>, Error<E>> {
+        if !is_sram_data_range(address, BOOT_COUNTER_SIZE) {
+            return Err(Error::InvalidInputData);
+        }
+        let mut data = [0; BOOT_COUNTER_SIZE];
+        self.read_sram_data(address, &mut data)?;
+        let has_lost_power = self.iface.read_register(Register::SECONDS)? & BitFlags::ST == 0;
+        let now = to_seconds_since_2000(&self.read_datetime()?);
+        let (boot_count, uptime, was_lost) = match counters_from_bytes(&data) {
+            Some((boot_count, uptime)) if !has_lost_power => (boot_count, uptime, false),
+            _ => (0, 0, true),
+        };
+        let mut counter = BootCounter {
+            rtc: self,
+            address,
+            boot_count: boot_count.wrapping_add(1),
+            uptime,
+            last_update: now,
+            was_lost,
+        };
+        counter.write()?;
+        Ok(counter)
+    }
+}
+
+fn counters_from_bytes(data: &[u8; BOOT_COUNTER_SIZE]) -> Option<(u32, u32)> {
+    let crc = crc16(&data[..12]).to_be_bytes();
+    if crc != data[12..] {
+        return None;
+    }
+    let boot_count = u32::from_le_bytes([data[0], data[1], data[2], data[3]]);
+    let uptime = u32::from_le_bytes([data[4], data[5], data[6], data[7]]);
+    Some((boot_count, uptime))
+}
+
+impl<DI, E, IC> BootCounter<'_, DI, IC>
+where
+    DI: interface::WriteData<Error = Error<E>> + interface::ReadData<Error = Error<E>>,
+{
+    /// Amount of boots including the current one
+    pub fn boot_count(&self) -> u32 {
+        self.boot_count
+    }
+
+    /// Accumulated uptime
+    pub fn uptime(&self) -> Duration {
+        Duration::from_secs(self.uptime.into())
+    }
+
+    /// Whether the SRAM contents were lost before this boot
+    pub fn was_lost(&self) -> bool {
+        self.was_lost
+    }
+
+    /// Add the time elapsed since the last update to the uptime.
+    ///
+    /// Returns the time added. If the date/time was set to an earlier value,
+    /// no time is added.
+    pub fn tick(&mut self) -> Result<Duration, Error<E>> {
+        let now = to_seconds_since_2000(&self.rtc.read_datetime()?);
+        let elapsed = now.saturating_sub(self.last_update);
+        self.uptime = self.uptime.saturating_add(elapsed);
+        self.last_update = now;
+        self.write()?;
+        Ok(Duration::from_secs(elapsed.into()))
+    }
+
+    fn write(&mut self) -> Result<(), Error<E>> {
+        let mut data = [0; BOOT_COUNTER_SIZE];
+        data[..4].copy_from_slice(&self.boot_count.to_le_bytes());
+        data[4..8].copy_from_slice(&self.uptime.to_le_bytes());
+        data[8..12].copy_from_slice(&self.last_update.to_le_bytes());
+        let crc = crc16(&data[..12]).to_be_bytes();
+        data[12..].copy_from_slice(&crc);
+        self.rtc.write_sram_data(self.address, &data)
+    }
+}
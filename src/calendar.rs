@@ -0,0 +1,311 @@
+//! Lightweight date/time type
+
+// Seconds from 1970-01-01 to 2000-01-01
+pub(crate) const Y2K_TIMESTAMP: i64 = 946_684_800;
+const SECONDS_PER_DAY: i64 = 86_400;
+// Days before each month in a non-leap year
+const DAYS_BEFORE_MONTH: [u16; 12] = [0, 31, 59, 90, 120, 151, 181, 212, 243, 273, 304, 334];
+
+/// Date and time in the range supported by the device (2000-2099)
+///
+/// The weekday is calculated from the date.
+/// This type does not depend on `chrono`. It can be converted from and into
+/// a `chrono::NaiveDateTime` with the `rtcc` feature, a
+/// `time::PrimitiveDateTime` with the `time` feature and a
+/// `jiff::civil::DateTime` with the `jiff` feature.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DateTime {
+    year: u16,
+    month: u8,
+    day: u8,
+    hour: u8,
+    minute: u8,
+    second: u8,
+    weekday: u8,
+}
+
+impl DateTime {
+    /// Create a date/time.
+    ///
+    /// Returns `None` if the year is not in the range 2000-2099 or any of the
+    /// values is not valid: month [1-12], day [1-28/29/30/31], hour [0-23],
+    /// minute [0-59], second [0-59].
+    pub fn new(year: u16, month: u8, day: u8, hour: u8, minute: u8, second: u8) -> Option<Self> {
+        let is_valid = (2000..=2099).contains(&year)
+            && (1..=12).contains(&month)
+            && (1..=days_in_month(year, month)).contains(&day)
+            && hour < 24
+            && minute < 60
+            && second < 60;
+        if !is_valid {
+            return None;
+        }
+        let weekday = ((days_since_2000(year, month, day) + 6) % 7 + 1) as u8;
+        Some(DateTime {
+            year,
+            month,
+            day,
+            hour,
+            minute,
+            second,
+            weekday,
+        })
+    }
+
+    /// Create a date/time from a Unix timestamp (seconds since
+    /// 1970-01-01 00:00:00).
+    ///
+    /// Returns `None` if the date is not in the range 2000-2099.
+    pub fn from_timestamp(timestamp: i64) -> Option<Self> {
+        let seconds = timestamp.checked_sub(Y2K_TIMESTAMP)?;
+        if seconds < 0 {
+            return None;
+        }
+        let mut days = seconds / SECONDS_PER_DAY;
+        let mut year = 2000;
+        while days >= days_in_year(year) {
+            days -= days_in_year(year);
+            year += 1;
+            if year > 2099 {
+                return None;
+            }
+        }
+        let mut month = 1;
+        while days >= i64::from(days_in_month(year, month)) {
+            days -= i64::from(days_in_month(year, month));
+            month += 1;
+        }
+        let time = seconds % SECONDS_PER_DAY;
+        DateTime::new(
+            year,
+            month,
+            days as u8 + 1,
+            (time / 3600) as u8,
+            (time % 3600 / 60) as u8,
+            (time % 60) as u8,
+        )
+    }
+
+    /// Unix timestamp (seconds since 1970-01-01 00:00:00)
+    pub fn timestamp(&self) -> i64 {
+        let days = days_since_2000(self.year, self.month, self.day);
+        let time =
+            i64::from(self.hour) * 3600 + i64::from(self.minute) * 60 + i64::from(self.second);
+        Y2K_TIMESTAMP + days * SECONDS_PER_DAY + time
+    }
+
+    /// Year [2000-2099]
+    pub fn year(&self) -> u16 {
+        self.year
+    }
+
+    /// Month [1-12]
+    pub fn month(&self) -> u8 {
+        self.month
+    }
+
+    /// Day of the month [1-31]
+    pub fn day(&self) -> u8 {
+        self.day
+    }
+
+    /// Weekday [1-7] where 1 is Sunday
+    pub fn weekday(&self) -> u8 {
+        self.weekday
+    }
+
+    /// Hour [0-23]
+    pub fn hour(&self) -> u8 {
+        self.hour
+    }
+
+    /// Minute [0-59]
+    pub fn minute(&self) -> u8 {
+        self.minute
+    }
+
+    /// Second [0-59]
+    pub fn second(&self) -> u8 {
+        self.second
+    }
+}
+
+/// Error converting a date/time outside of the range supported by the
+/// device (2000-2099) into a [`DateTime`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OutOfRange;
+
+impl core::fmt::Display for OutOfRange {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("date/time out of the range 2000-2099")
+    }
+}
+
+impl Default for DateTime {
+    /// 2000-01-01 00:00:00
+    fn default() -> Self {
+        DateTime {
+            year: 2000,
+            month: 1,
+            day: 1,
+            hour: 0,
+            minute: 0,
+            second: 0,
+            weekday: 7,
+        }
+    }
+}
+
+pub(crate) fn is_leap_year(year: u16) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+pub(crate) fn days_in_month(year: u16, month: u8) -> u8 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+fn days_in_year(year: u16) -> i64 {
+    if is_leap_year(year) {
+        366
+    } else {
+        365
+    }
+}
+
+// Days from 2000-01-01 to a valid date in the range 2000-2099.
+fn days_since_2000(year: u16, month: u8, day: u8) -> i64 {
+    let years = i64::from(year - 2000);
+    // 2000 is a leap year and 2100 is out of range
+    let leap_days = (years + 3) / 4;
+    let mut days = years * 365 + leap_days;
+    days += i64::from(DAYS_BEFORE_MONTH[usize::from(month - 1)]);
+    if month > 2 && is_leap_year(year) {
+        days += 1;
+    }
+    days + i64::from(day) - 1
+}
+
+#[cfg(feature = "rtcc")]
+mod rtcc_conversion {
+    use super::{DateTime, OutOfRange};
+    use crate::{Datelike, NaiveDate, NaiveDateTime, Timelike};
+
+    impl From<DateTime> for NaiveDateTime {
+        fn from(datetime: DateTime) -> Self {
+            NaiveDate::from_ymd_opt(
+                datetime.year.into(),
+                datetime.month.into(),
+                datetime.day.into(),
+            )
+            .and_then(|date| {
+                date.and_hms_opt(
+                    datetime.hour.into(),
+                    datetime.minute.into(),
+                    datetime.second.into(),
+                )
+            })
+            .expect("DateTime is always a valid date/time")
+        }
+    }
+
+    impl TryFrom<NaiveDateTime> for DateTime {
+        type Error = OutOfRange;
+
+        /// Fails if the year is not in the range 2000-2099.
+        /// Fractions of a second are discarded.
+        fn try_from(datetime: NaiveDateTime) -> Result<Self, Self::Error> {
+            let year = u16::try_from(datetime.year()).map_err(|_| OutOfRange)?;
+            DateTime::new(
+                year,
+                datetime.month() as u8,
+                datetime.day() as u8,
+                datetime.hour() as u8,
+                datetime.minute() as u8,
+                datetime.second() as u8,
+            )
+            .ok_or(OutOfRange)
+        }
+    }
+}
+
+#[cfg(feature = "time")]
+mod time_conversion {
+    use super::{DateTime, OutOfRange};
+    use time::{Date, Month, PrimitiveDateTime, Time};
+
+    impl From<DateTime> for PrimitiveDateTime {
+        fn from(datetime: DateTime) -> Self {
+            let date = Month::try_from(datetime.month)
+                .and_then(|month| {
+                    Date::from_calendar_date(datetime.year.into(), month, datetime.day)
+                })
+                .expect("DateTime is always a valid date");
+            let time = Time::from_hms(datetime.hour, datetime.minute, datetime.second)
+                .expect("DateTime is always a valid time");
+            PrimitiveDateTime::new(date, time)
+        }
+    }
+
+    impl TryFrom<PrimitiveDateTime> for DateTime {
+        type Error = OutOfRange;
+
+        /// Fails if the year is not in the range 2000-2099.
+        /// Fractions of a second are discarded.
+        fn try_from(datetime: PrimitiveDateTime) -> Result<Self, Self::Error> {
+            let year = u16::try_from(datetime.year()).map_err(|_| OutOfRange)?;
+            DateTime::new(
+                year,
+                datetime.month().into(),
+                datetime.day(),
+                datetime.hour(),
+                datetime.minute(),
+                datetime.second(),
+            )
+            .ok_or(OutOfRange)
+        }
+    }
+}
+
+#[cfg(feature = "jiff")]
+mod jiff_conversion {
+    use super::{DateTime, OutOfRange};
+
+    impl From<DateTime> for jiff::civil::DateTime {
+        fn from(datetime: DateTime) -> Self {
+            jiff::civil::DateTime::new(
+                datetime.year as i16,
+                datetime.month as i8,
+                datetime.day as i8,
+                datetime.hour as i8,
+                datetime.minute as i8,
+                datetime.second as i8,
+                0,
+            )
+            .expect("DateTime is always a valid date/time")
+        }
+    }
+
+    impl TryFrom<jiff::civil::DateTime> for DateTime {
+        type Error = OutOfRange;
+
+        /// Fails if the year is not in the range 2000-2099.
+        /// Fractions of a second are discarded.
+        fn try_from(datetime: jiff::civil::DateTime) -> Result<Self, Self::Error> {
+            let year = u16::try_from(datetime.year()).map_err(|_| OutOfRange)?;
+            DateTime::new(
+                year,
+                datetime.month() as u8,
+                datetime.day() as u8,
+                datetime.hour() as u8,
+                datetime.minute() as u8,
+                datetime.second() as u8,
+            )
+            .ok_or(OutOfRange)
+        }
+    }
+}
@@ -1,12 +1,21 @@
-use super::conversion::{convert_hours_to_format, decimal_to_packed_bcd, hours_to_register};
+use super::conversion::{
+    convert_hours_to_format, decimal_to_packed_bcd, hours_from_register, hours_to_register,
+    packed_bcd_to_decimal,
+};
+#[cfg(feature = "rtcc")]
+use super::datetime::datetime_from_naive;
 use crate::{
-    interface, Alarm, AlarmDateTime, AlarmMatching, AlarmOutputPinPolarity, BitFlags, Error,
-    Mcp794xx, Register,
+    interface, marker, Alarm, AlarmConfig, AlarmDateTime, AlarmMatching, AlarmOutputPinPolarity,
+    BitFlags, DateTime, Error, Hours, Mcp794xx, Register,
 };
+#[cfg(feature = "rtcc")]
+use crate::{NaiveDateTime, Weekday};
+use core::time::Duration;
 
 impl<DI, E, IC> Mcp794xx<DI, IC>
 where
     DI: interface::WriteData<Error = Error<E>> + interface::ReadData<Error = Error<E>>,
+    IC: marker::WithAlarms,
 {
     /// Enable alarm
     pub fn enable_alarm(&mut self, alarm: Alarm) -> Result<(), Error<E>> {
@@ -29,6 +38,10 @@ where
     /// Note that this clears the alarm has matched flag and the alarm needs to be
     /// enabled separately.
     /// Note that the output pin polarity will be set to the same value for both alarms.
+    ///
+    /// The alarm 1 of the MCP795xx devices does not have a month register.
+    /// The month is ignored in that case and `Error::InvalidInputData` is
+    /// returned for the `AllMatch` trigger rate without writing anything.
     pub fn set_alarm(
         &mut self,
         alarm: Alarm,
@@ -36,79 +49,284 @@ where
         matching: AlarmMatching,
         polarity: AlarmOutputPinPolarity,
     ) -> Result<(), Error<E>> {
-        if when.month < 1
-            || when.month > 12
-            || when.day < 1
-            || when.day > 31
-            || when.weekday < 1
-            || when.weekday > 7
-            || when.minute > 59
-            || when.second > 59
-        {
-            return Err(Error::InvalidInputData);
-        }
-        let hours = convert_hours_to_format(self.is_running_in_24h_mode, when.hour)?;
-        let mut weekday = decimal_to_packed_bcd(when.weekday);
+        let payload = alarm_to_registers(
+            alarm,
+            when,
+            matching,
+            polarity,
+            self.is_running_in_24h_mode,
+            IC::HAS_ALARM1_MONTH,
+        )?;
         if polarity != self.alarm_output_pin_polarity && alarm == Alarm::One {
             let data = self.iface.read_register(Register::ALM0WKDAY)?;
-            let data = match polarity {
-                AlarmOutputPinPolarity::Low => data & !BitFlags::ALMPOL,
-                AlarmOutputPinPolarity::High => data | BitFlags::ALMPOL,
-            };
-            self.iface.write_register(Register::ALM0WKDAY, data)?;
+            self.iface.write_register(
+                Register::ALM0WKDAY,
+                alarm_polarity_to_register(data, polarity),
+            )?;
             self.alarm_output_pin_polarity = polarity;
         }
-
-        if polarity == AlarmOutputPinPolarity::High {
-            weekday |= BitFlags::ALMPOL;
-        }
-        let mask = match matching {
-            AlarmMatching::SecondsMatch => 0,
-            AlarmMatching::MinutesMatch => 1 << 4,
-            AlarmMatching::HoursMatch => 2 << 4,
-            AlarmMatching::WeekdayMatches => 3 << 4,
-            AlarmMatching::DayMatches => 4 << 4,
-            AlarmMatching::AllMatch => 7 << 4,
-        };
-        weekday |= mask;
-        let payload = [
-            if alarm == Alarm::Zero {
-                Register::ALM0SEC
-            } else {
-                Register::ALM1SEC
-            },
-            decimal_to_packed_bcd(when.second),
-            decimal_to_packed_bcd(when.minute),
-            hours_to_register(hours)?,
-            weekday,
-            decimal_to_packed_bcd(when.day),
-            decimal_to_packed_bcd(when.month),
-        ];
         self.iface.write_data(&payload)?;
         self.alarm_output_pin_polarity = polarity;
         Ok(())
     }
 
+    /// Set alarm for a date/time with a trigger rate and an output pin polarity.
+    ///
+    /// The weekday is calculated from the date as in
+    /// [`set_datetime()`](#method.set_datetime) and the hour is stored in the
+    /// format the RTC is running in.
+    /// Returns `Error::InvalidInputData` if the year is not in the range
+    /// 2000-2099 since the weekday would not correspond to the device calendar.
+    ///
+    /// See [`set_alarm()`](#method.set_alarm) for further notes.
+    #[cfg(feature = "rtcc")]
+    pub fn set_alarm_at(
+        &mut self,
+        alarm: Alarm,
+        datetime: &NaiveDateTime,
+        matching: AlarmMatching,
+        polarity: AlarmOutputPinPolarity,
+    ) -> Result<(), Error<E>> {
+        let when = alarm_datetime(&datetime_from_naive(datetime)?);
+        self.set_alarm(alarm, when, matching, polarity)
+    }
+
+    /// Set alarm for a weekday with an output pin polarity.
+    ///
+    /// This uses the `WeekdayMatches` trigger rate. Note that in this mode
+    /// the device only compares the weekday, so the alarm matches as soon
+    /// as the weekday starts, at midnight.
+    ///
+    /// See [`set_alarm()`](#method.set_alarm) for further notes.
+    #[cfg(feature = "rtcc")]
+    pub fn set_weekly_alarm(
+        &mut self,
+        alarm: Alarm,
+        weekday: Weekday,
+        polarity: AlarmOutputPinPolarity,
+    ) -> Result<(), Error<E>> {
+        let when = weekly_alarm_datetime(weekday);
+        self.set_alarm(alarm, when, AlarmMatching::WeekdayMatches, polarity)
+    }
+
+    /// Set and enable an alarm to match once after a duration from the
+    /// current date/time and return the date/time at which it will match.
+    ///
+    /// The duration is rounded down to whole seconds. The alarm uses the
+    /// `AllMatch` trigger rate so that it matches only once.
+    ///
+    /// Returns `Error::InvalidInputData` if the duration is shorter than a
+    /// second, ends after 2099 or is so long that the alarm would match
+    /// earlier, since the alarm registers do not include the year.
+    ///
+    /// Since the alarm 1 of the MCP795xx devices does not have a month
+    /// register, only the alarm 0 can be used with them. `Error::InvalidInputData`
+    /// is returned for the alarm 1 without setting it.
+    ///
+    /// See [`set_alarm()`](#method.set_alarm) for further notes.
+    pub fn set_alarm_in(
+        &mut self,
+        alarm: Alarm,
+        duration: Duration,
+        polarity: AlarmOutputPinPolarity,
+    ) -> Result<DateTime, Error<E>> {
+        let now = self.read_datetime()?;
+        let (when, target) = alarm_after(&now, duration)?;
+        self.set_alarm(alarm, when, AlarmMatching::AllMatch, polarity)?;
+        self.enable_alarm(alarm)?;
+        Ok(target)
+    }
+
+    /// Read the configuration of an alarm.
+    ///
+    /// The hour is returned in the format it was stored in.
+    /// The output pin polarity is shared by both alarms and is always read
+    /// from the alarm 0 registers.
+    ///
+    /// Note that the alarm 1 of the MCP795xx devices does not have a month
+    /// register. The month will be returned as 0 in that case.
+    pub fn get_alarm(&mut self, alarm: Alarm) -> Result<AlarmConfig, Error<E>> {
+        let mut data = [0; 6];
+        self.iface
+            .read_data(alarm_seconds_register(alarm), &mut data)?;
+        let alm0_weekday = match alarm {
+            Alarm::Zero => data[3],
+            Alarm::One => self.iface.read_register(Register::ALM0WKDAY)?,
+        };
+        let control = self.iface.read_register(Register::CONTROL)?;
+        alarm_from_registers(alarm, &data, alm0_weekday, control)
+    }
+
     /// Returns whether the alarm has matched.
     ///
     /// Once this is true, it will stay as such until cleared. e.g. with
     /// [`clear_alarm_matched_flag()`](#method.clear_alarm_matched_flag)
     pub fn has_alarm_matched(&mut self, alarm: Alarm) -> Result<bool, Error<E>> {
-        let reg = match alarm {
-            Alarm::Zero => Register::ALM0WKDAY,
-            Alarm::One => Register::ALM1WKDAY,
-        };
+        let reg = alarm_weekday_register(alarm);
         let data = self.iface.read_register(reg)?;
         Ok((data & BitFlags::ALMIF) != 0)
     }
 
     /// Clears the alarm matched flag.
     pub fn clear_alarm_matched_flag(&mut self, alarm: Alarm) -> Result<(), Error<E>> {
-        let reg = match alarm {
-            Alarm::Zero => Register::ALM0WKDAY,
-            Alarm::One => Register::ALM1WKDAY,
-        };
+        let reg = alarm_weekday_register(alarm);
         let data = self.iface.read_register(reg)?;
         self.iface.write_register(reg, data & !BitFlags::ALMIF)
     }
 }
+
+pub(crate) fn alarm_seconds_register(alarm: Alarm) -> u8 {
+    match alarm {
+        Alarm::Zero => Register::ALM0SEC,
+        Alarm::One => Register::ALM1SEC,
+    }
+}
+
+pub(crate) fn alarm_weekday_register(alarm: Alarm) -> u8 {
+    match alarm {
+        Alarm::Zero => Register::ALM0WKDAY,
+        Alarm::One => Register::ALM1WKDAY,
+    }
+}
+
+// Sets the ALMPOL bit in an ALMxWKDAY register value.
+pub(crate) fn alarm_polarity_to_register(data: u8, polarity: AlarmOutputPinPolarity) -> u8 {
+    match polarity {
+        AlarmOutputPinPolarity::Low => data & !BitFlags::ALMPOL,
+        AlarmOutputPinPolarity::High => data | BitFlags::ALMPOL,
+    }
+}
+
+pub(crate) fn alarm_datetime(datetime: &DateTime) -> AlarmDateTime {
+    AlarmDateTime {
+        month: datetime.month(),
+        day: datetime.day(),
+        weekday: datetime.weekday(),
+        hour: Hours::H24(datetime.hour()),
+        minute: datetime.minute(),
+        second: datetime.second(),
+    }
+}
+
+// Alarm date/time matching first after a duration from `now` with the
+// `AllMatch` trigger rate together with the date/time at which it matches.
+pub(crate) fn alarm_after<E>(
+    now: &DateTime,
+    duration: Duration,
+) -> Result<(AlarmDateTime, DateTime), Error<E>> {
+    let target = i64::try_from(duration.as_secs())
+        .ok()
+        .filter(|seconds| *seconds > 0)
+        .and_then(|seconds| now.timestamp().checked_add(seconds))
+        .and_then(DateTime::from_timestamp)
+        .ok_or(Error::InvalidInputData)?;
+    let when = alarm_datetime(&target);
+    if when.next_match(AlarmMatching::AllMatch, now) != Some(target) {
+        return Err(Error::InvalidInputData);
+    }
+    Ok((when, target))
+}
+
+// The month and day are set to 1 and the time to midnight since they are
+// not compared.
+#[cfg(feature = "rtcc")]
+pub(crate) fn weekly_alarm_datetime(weekday: Weekday) -> AlarmDateTime {
+    AlarmDateTime {
+        month: 1,
+        day: 1,
+        weekday: weekday.number_from_sunday() as u8,
+        hour: Hours::H24(0),
+        minute: 0,
+        second: 0,
+    }
+}
+
+// Encodes a write payload for the ALMxSEC..ALMxMTH registers.
+// The month cannot be compared if the alarm 1 has no month register.
+pub(crate) fn alarm_to_registers<E>(
+    alarm: Alarm,
+    when: AlarmDateTime,
+    matching: AlarmMatching,
+    polarity: AlarmOutputPinPolarity,
+    is_running_in_24h_mode: bool,
+    has_alarm1_month: bool,
+) -> Result<[u8; 7], Error<E>> {
+    if (alarm == Alarm::One && matching == AlarmMatching::AllMatch && !has_alarm1_month)
+        || when.month < 1
+        || when.month > 12
+        || when.day < 1
+        || when.day > 31
+        || when.weekday < 1
+        || when.weekday > 7
+        || when.minute > 59
+        || when.second > 59
+    {
+        return Err(Error::InvalidInputData);
+    }
+    let hours = convert_hours_to_format(is_running_in_24h_mode, when.hour)?;
+    let mut weekday = decimal_to_packed_bcd(when.weekday);
+    if polarity == AlarmOutputPinPolarity::High {
+        weekday |= BitFlags::ALMPOL;
+    }
+    let mask = match matching {
+        AlarmMatching::SecondsMatch => 0,
+        AlarmMatching::MinutesMatch => 1 << 4,
+        AlarmMatching::HoursMatch => 2 << 4,
+        AlarmMatching::WeekdayMatches => 3 << 4,
+        AlarmMatching::DayMatches => 4 << 4,
+        AlarmMatching::AllMatch => 7 << 4,
+    };
+    weekday |= mask;
+    Ok([
+        alarm_seconds_register(alarm),
+        decimal_to_packed_bcd(when.second),
+        decimal_to_packed_bcd(when.minute),
+        hours_to_register(hours)?,
+        weekday,
+        decimal_to_packed_bcd(when.day),
+        decimal_to_packed_bcd(when.month),
+    ])
+}
+
+// Decodes the ALMxSEC..ALMxMTH registers together with the ALM0WKDAY
+// register (for the polarity) and the CONTROL register.
+pub(crate) fn alarm_from_registers<E>(
+    alarm: Alarm,
+    data: &[u8; 6],
+    alm0_weekday: u8,
+    control: u8,
+) -> Result<AlarmConfig, Error<E>> {
+    let mask = (data[3] >> 4) & 0b0000_0111;
+    let matching = match mask {
+        0 => AlarmMatching::SecondsMatch,
+        1 => AlarmMatching::MinutesMatch,
+        2 => AlarmMatching::HoursMatch,
+        3 => AlarmMatching::WeekdayMatches,
+        4 => AlarmMatching::DayMatches,
+        7 => AlarmMatching::AllMatch,
+        _ => return Err(Error::InvalidAlarmData(mask)),
+    };
+    let polarity = if (alm0_weekday & BitFlags::ALMPOL) != 0 {
+        AlarmOutputPinPolarity::High
+    } else {
+        AlarmOutputPinPolarity::Low
+    };
+    let enable_flag = match alarm {
+        Alarm::Zero => BitFlags::ALM0EN,
+        Alarm::One => BitFlags::ALM1EN,
+    };
+    let when = AlarmDateTime {
+        month: packed_bcd_to_decimal(data[5] & 0b0001_1111),
+        day: packed_bcd_to_decimal(data[4] & 0b0011_1111),
+        weekday: data[3] & 0b0000_0111,
+        hour: hours_from_register(data[2] & 0b0111_1111),
+        minute: packed_bcd_to_decimal(data[1] & 0b0111_1111),
+        second: packed_bcd_to_decimal(data[0] & 0b0111_1111),
+    };
+    Ok(AlarmConfig {
+        when,
+        matching,
+        polarity,
+        is_enabled: (control & enable_flag) != 0,
+    })
+}
@@ -1,13 +1,18 @@
 //! Common date/time function
 
-use super::conversion::{
-    decimal_to_packed_bcd, hours_from_register, hours_to_register, packed_bcd_to_decimal,
-};
+#[cfg(feature = "rtcc")]
+use super::conversion::hours_to_register;
+use super::conversion::{decimal_to_packed_bcd, hours_from_register, packed_bcd_to_decimal};
+use crate::calendar::Y2K_TIMESTAMP;
 use crate::{
-    interface, BitFlags, DateTimeAccess, Datelike, Error, Hours, Mcp794xx, NaiveDate,
-    NaiveDateTime, NaiveTime, Register, Rtcc, Timelike,
+    interface::{self, SpiInterface},
+    BitFlags, DateTime, Error, Hours, Mcp794xx, Register,
 };
+#[cfg(feature = "rtcc")]
+use crate::{DateTimeAccess, Datelike, NaiveDate, NaiveDateTime, NaiveTime, Rtcc, Timelike};
+use embedded_hal::{delay::DelayNs, spi};
 
+#[cfg(feature = "rtcc")]
 impl<DI, E, IC> DateTimeAccess for Mcp794xx<DI, IC>
 where
     DI: interface::WriteData<Error = Error<E>> + interface::ReadData<Error = Error<E>>,
@@ -17,35 +22,12 @@ where
     /// This device can compensate for leap years up to 2399
     /// but only the two last year digits are stored so we will return
     /// the year as in the range 2000-2099.
+    ///
+    /// If the seconds read are 0, the registers are read again until
+    /// they are stable in case a carry was in progress.
+    /// See [`consistent_datetime()`](#method.consistent_datetime).
     fn datetime(&mut self) -> Result<NaiveDateTime, Self::Error> {
-        let mut data = [0; 7];
-        self.iface.read_data(0, &mut data)?;
-        let year = 2000 + u16::from(packed_bcd_to_decimal(data[Register::YEAR as usize]));
-        let month = packed_bcd_to_decimal(data[Register::MONTH as usize] & !BitFlags::LEAPYEAR);
-        let day = packed_bcd_to_decimal(data[Register::DAY as usize]);
-        let hour = hours_from_register(data[Register::HOURS as usize]);
-        let minute = packed_bcd_to_decimal(data[Register::MINUTES as usize]);
-        let second = packed_bcd_to_decimal(data[Register::SECONDS as usize] & !BitFlags::ST);
-        let h24 = match hour {
-            Hours::H24(h) => h,
-            Hours::AM(h) => h,
-            Hours::PM(h) => h + 12,
-        };
-
-        if let Some(date) = NaiveDate::from_ymd_opt(year.into(), month.into(), day.into()) {
-            date.and_hms_opt(h24.into(), minute.into(), second.into())
-                .ok_or(Error::InvalidTimeData(
-                    h24.into(),
-                    minute.into(),
-                    second.into(),
-                ))
-        } else {
-            Err(Error::InvalidDateData(
-                year.into(),
-                month.into(),
-                day.into(),
-            ))
-        }
+        self.read_datetime().map(NaiveDateTime::from)
     }
 
     /// Note that this clears the power failed flag.
@@ -53,31 +35,11 @@ where
     /// but only the two last year digits are stored so we only
     /// support the range 2000-2099.
     fn set_datetime(&mut self, datetime: &NaiveDateTime) -> Result<(), Self::Error> {
-        if datetime.year() < 2000 || datetime.year() > 2099 {
-            return Err(Error::InvalidInputData);
-        }
-        let second = decimal_to_packed_bcd(datetime.second() as u8);
-        let second = if self.is_enabled {
-            second | BitFlags::ST
-        } else {
-            second
-        };
-        let payload = [
-            Register::SECONDS,
-            second,
-            decimal_to_packed_bcd(datetime.minute() as u8),
-            hours_to_register(Hours::H24(datetime.hour() as u8))?,
-            datetime.weekday().number_from_sunday() as u8,
-            decimal_to_packed_bcd(datetime.day() as u8),
-            decimal_to_packed_bcd(datetime.month() as u8),
-            decimal_to_packed_bcd((datetime.year() - 2000) as u8),
-        ];
-        self.iface.write_data(&payload)?;
-        self.is_running_in_24h_mode = true;
-        Ok(())
+        self.write_datetime(&datetime_from_naive(datetime)?)
     }
 }
 
+#[cfg(feature = "rtcc")]
 impl<DI, E, IC> Rtcc for Mcp794xx<DI, IC>
 where
     DI: interface::WriteData<Error = Error<E>> + interface::ReadData<Error = Error<E>>,
@@ -98,20 +60,11 @@ where
         Ok(hours_from_register(data))
     }
 
+    /// If the seconds read are 0, the registers are read again until
+    /// they are stable in case a carry was in progress.
     fn time(&mut self) -> Result<NaiveTime, Self::Error> {
-        let mut data = [0; 3];
-        self.iface.read_data(0, &mut data)?;
-        let hour = hours_from_register(data[Register::HOURS as usize]);
-        let minute = packed_bcd_to_decimal(data[Register::MINUTES as usize]);
-        let second = packed_bcd_to_decimal(data[Register::SECONDS as usize] & !BitFlags::ST);
-        let h24 = match hour {
-            Hours::H24(h) => h,
-            Hours::AM(h) => h,
-            Hours::PM(h) => h + 12,
-        };
-        NaiveTime::from_hms_opt(h24.into(), minute.into(), second.into()).ok_or(
-            Error::InvalidTimeData(h24.into(), minute.into(), second.into()),
-        )
+        let data = self.read_time_keeping_registers(false)?;
+        time_from_registers(&data)
     }
 
     fn weekday(&mut self) -> Result<u8, Self::Error> {
@@ -138,25 +91,18 @@ where
         Ok(2000 + u16::from(packed_bcd_to_decimal(value)))
     }
 
+    /// The time is read as well so that if the seconds are 0, the registers
+    /// are read again until they are stable in case a carry was in progress.
     fn date(&mut self) -> Result<NaiveDate, Self::Error> {
-        let mut data = [0; 3];
-        self.iface.read_data(Register::DAY, &mut data)?;
-        let year = 2000 + u16::from(packed_bcd_to_decimal(data[2]));
-        let month = packed_bcd_to_decimal(data[1] & !BitFlags::LEAPYEAR);
-        let day = packed_bcd_to_decimal(data[0]);
-        NaiveDate::from_ymd_opt(year.into(), month.into(), day.into()).ok_or(
-            Error::InvalidDateData(year.into(), month.into(), day.into()),
-        )
+        let data: [u8; 7] = self.read_time_keeping_registers(false)?;
+        let mut date = [0; 3];
+        date.copy_from_slice(&data[Register::DAY as usize..]);
+        date_from_registers(&date)
     }
 
     fn set_seconds(&mut self, seconds: u8) -> Result<(), Self::Error> {
         Self::check_lt(seconds, 60)?;
-        let seconds = decimal_to_packed_bcd(seconds);
-        let value = if self.is_enabled {
-            seconds | BitFlags::ST
-        } else {
-            seconds
-        };
+        let value = seconds_to_register(seconds, self.is_enabled);
         self.iface.write_register(Register::SECONDS, value)
     }
 
@@ -178,21 +124,7 @@ where
     }
 
     fn set_time(&mut self, time: &NaiveTime) -> Result<(), Self::Error> {
-        if time.minute() > 59 || time.second() > 59 {
-            return Err(Error::InvalidInputData);
-        }
-        let second = decimal_to_packed_bcd(time.second() as u8);
-        let second = if self.is_enabled {
-            second | BitFlags::ST
-        } else {
-            second
-        };
-        let payload = [
-            Register::SECONDS,
-            second,
-            decimal_to_packed_bcd(time.minute() as u8),
-            hours_to_register(Hours::H24(time.hour() as u8))?,
-        ];
+        let payload = time_to_registers(time, self.is_enabled)?;
         self.iface.write_data(&payload)?;
         self.is_running_in_24h_mode = true;
         Ok(())
@@ -235,17 +167,163 @@ where
     }
 
     fn set_date(&mut self, date: &NaiveDate) -> Result<(), Self::Error> {
-        if date.year() < 2000 || date.year() > 2099 {
+        let payload = date_to_registers(date)?;
+        self.iface.write_data(&payload)
+    }
+}
+
+impl<DI, E, IC> Mcp794xx<DI, IC>
+where
+    DI: interface::WriteData<Error = Error<E>> + interface::ReadData<Error = Error<E>>,
+{
+    /// Read the date and time.
+    ///
+    /// This device can compensate for leap years up to 2399
+    /// but only the two last year digits are stored so we will return
+    /// the year as in the range 2000-2099.
+    ///
+    /// If the seconds read are 0, the registers are read again until
+    /// they are stable in case a carry was in progress.
+    /// See [`consistent_datetime()`](#method.consistent_datetime).
+    pub fn read_datetime(&mut self) -> Result<DateTime, Error<E>> {
+        let data = self.read_time_keeping_registers(false)?;
+        datetime_from_registers(&data)
+    }
+
+    /// Set the date and time.
+    ///
+    /// Note that this clears the power failed flag.
+    pub fn write_datetime(&mut self, datetime: &DateTime) -> Result<(), Error<E>> {
+        let payload = datetime_to_registers(datetime, self.is_enabled);
+        self.iface.write_data(&payload)?;
+        self.is_running_in_24h_mode = true;
+        Ok(())
+    }
+
+    /// Read the date and time as a Unix timestamp (seconds since
+    /// 1970-01-01 00:00:00).
+    ///
+    /// The date and time stored in the device are interpreted as UTC.
+    pub fn timestamp(&mut self) -> Result<i64, Error<E>> {
+        Ok(self.read_datetime()?.timestamp())
+    }
+
+    /// Set the date and time from a Unix timestamp (seconds since
+    /// 1970-01-01 00:00:00).
+    ///
+    /// The date and time are stored as UTC.
+    /// Only the range 2000-2099 is supported (timestamps
+    /// `[946_684_800-4_102_444_799]`).
+    /// `Error::InvalidInputData` will be returned for other timestamps.
+    ///
+    /// Note that this clears the power failed flag.
+    pub fn set_timestamp(&mut self, timestamp: i64) -> Result<(), Error<E>> {
+        let datetime = datetime_from_timestamp(timestamp)?;
+        self.write_datetime(&datetime)
+    }
+
+    /// Set the date and time stopping the oscillator during the write.
+    ///
+    /// As recommended in the datasheet, the oscillator is stopped and
+    /// the driver waits until it is no longer running before the time-keeping
+    /// registers are written. This prevents a rollover from corrupting the
+    /// registers mid-write. Afterwards the oscillator is started again if it
+    /// was enabled and the driver waits until it is running.
+    ///
+    /// If the oscillator does not stop or start, `Error::OscillatorStopTimeout`
+    /// or `Error::OscillatorStartTimeout` will be returned.
+    /// See [`set_oscillator_poll_attempts()`](#method.set_oscillator_poll_attempts).
+    ///
+    /// Note that this clears the power failed flag.
+    pub fn set_datetime_safely<D: DelayNs>(
+        &mut self,
+        datetime: &DateTime,
+        delay: &mut D,
+    ) -> Result<(), Error<E>> {
+        let payload = datetime_to_registers(datetime, false);
+        self.write_stopped(&payload, delay)?;
+        self.is_running_in_24h_mode = true;
+        Ok(())
+    }
+
+    /// Set the time stopping the oscillator during the write.
+    ///
+    /// See [`set_datetime_safely()`](#method.set_datetime_safely).
+    #[cfg(feature = "rtcc")]
+    pub fn set_time_safely<D: DelayNs>(
+        &mut self,
+        time: &NaiveTime,
+        delay: &mut D,
+    ) -> Result<(), Error<E>> {
+        let payload = time_to_registers(time, false)?;
+        self.write_stopped(&payload, delay)?;
+        self.is_running_in_24h_mode = true;
+        Ok(())
+    }
+
+    /// Set the maximum amount of times the oscillator is polled while
+    /// waiting for it to stop or start (default: 1000).
+    ///
+    /// The driver waits 1ms between attempts.
+    pub fn set_oscillator_poll_attempts(&mut self, attempts: u32) {
+        self.oscillator_poll_attempts = attempts;
+    }
+
+    // Writes a payload starting at the SECONDS register with the ST bit
+    // cleared while the oscillator is stopped.
+    fn write_stopped<D: DelayNs>(&mut self, payload: &[u8], delay: &mut D) -> Result<(), Error<E>> {
+        let seconds = payload[1];
+        self.iface.write_register(Register::SECONDS, seconds)?;
+        self.wait_oscillator(false, delay)?;
+        self.iface.write_data(payload)?;
+        if self.is_enabled {
+            self.iface
+                .write_register(Register::SECONDS, seconds | BitFlags::ST)?;
+            self.wait_oscillator(true, delay)?;
+        }
+        Ok(())
+    }
+
+    fn wait_oscillator<D: DelayNs>(
+        &mut self,
+        running: bool,
+        delay: &mut D,
+    ) -> Result<(), Error<E>> {
+        for _ in 0..self.oscillator_poll_attempts {
+            if self.is_oscillator_running()? == running {
+                return Ok(());
+            }
+            delay.delay_ms(1);
+        }
+        if running {
+            Err(Error::OscillatorStartTimeout)
+        } else {
+            Err(Error::OscillatorStopTimeout)
+        }
+    }
+}
+
+impl<SPI, E, IC> Mcp794xx<SpiInterface<SPI>, IC>
+where
+    SPI: spi::SpiDevice<u8, Error = E>,
+{
+    /// Read the hundredths of second [0-99].
+    ///
+    /// Only available on the MCP795xx devices.
+    pub fn hundredths(&mut self) -> Result<u8, Error<E>> {
+        let data = self.iface.read_hundredths()?;
+        Ok(packed_bcd_to_decimal(data))
+    }
+
+    /// Set the hundredths of second [0-99].
+    ///
+    /// Only available on the MCP795xx devices.
+    pub fn set_hundredths(&mut self, hundredths: u8) -> Result<(), Error<E>> {
+        if hundredths > 99 {
             return Err(Error::InvalidInputData);
         }
-        let payload = [
-            Register::WEEKDAY,
-            date.weekday().number_from_sunday() as u8,
-            decimal_to_packed_bcd(date.day() as u8),
-            decimal_to_packed_bcd(date.month() as u8),
-            decimal_to_packed_bcd((date.year() - 2000) as u8),
-        ];
-        self.iface.write_data(&payload)
+        self.iface
+            .write_hundredths(decimal_to_packed_bcd(hundredths))
     }
 }
 
@@ -258,4 +336,194 @@ where
         let data = self.iface.read_register(Register::MONTH)?;
         Ok((data & BitFlags::LEAPYEAR) != 0)
     }
+
+    /// Read the date and time making sure no rollover happened during the read.
+    ///
+    /// The registers are read repeatedly until two consecutive reads are
+    /// equal. Use this instead of composing the individual elements
+    /// like [`seconds()`](#method.seconds) and [`minutes()`](#method.minutes),
+    /// which can mix values across a rollover.
+    ///
+    /// The registers are read at most 4 times. If no two consecutive reads
+    /// are equal, the last read is returned, which may still mix values
+    /// across a rollover. This can only happen if each read takes around a
+    /// second, e.g. on a heavily contended bus.
+    pub fn consistent_datetime(&mut self) -> Result<DateTime, Error<E>> {
+        let data = self.read_time_keeping_registers(true)?;
+        datetime_from_registers(&data)
+    }
+
+    // Reads the first N registers starting at SECONDS. If `always_check` is
+    // set or the seconds are 0, the registers are read again until two
+    // consecutive reads are equal or the attempts are exhausted, in which
+    // case the last read is returned.
+    fn read_time_keeping_registers<const N: usize>(
+        &mut self,
+        always_check: bool,
+    ) -> Result<[u8; N], Error<E>> {
+        let mut data = [0; N];
+        self.iface.read_data(Register::SECONDS, &mut data)?;
+        if !always_check && !is_possible_carry(&data) {
+            return Ok(data);
+        }
+        for _ in 0..CONSISTENT_READ_ATTEMPTS {
+            let mut next = [0; N];
+            self.iface.read_data(Register::SECONDS, &mut next)?;
+            if next == data {
+                break;
+            }
+            data = next;
+        }
+        Ok(data)
+    }
+}
+
+// Maximum amount of times the time-keeping registers are read again
+// while waiting for them to be stable.
+pub(crate) const CONSISTENT_READ_ATTEMPTS: u32 = 3;
+
+// Returns whether a carry into the other registers may be in progress.
+pub(crate) fn is_possible_carry(data: &[u8]) -> bool {
+    data[Register::SECONDS as usize] & !BitFlags::ST == 0
+}
+
+// Seconds from 1970-01-01 to 2100-01-01
+const Y2100_TIMESTAMP: i64 = 4_102_444_800;
+
+// Decodes a Unix timestamp in the range 2000-2099.
+pub(crate) fn datetime_from_timestamp<E>(timestamp: i64) -> Result<DateTime, Error<E>> {
+    if !(Y2K_TIMESTAMP..Y2100_TIMESTAMP).contains(&timestamp) {
+        return Err(Error::InvalidInputData);
+    }
+    DateTime::from_timestamp(timestamp).ok_or(Error::InvalidInputData)
+}
+
+// Encodes a date/time as seconds since 2000-01-01.
+pub(crate) fn to_seconds_since_2000(datetime: &DateTime) -> u32 {
+    (datetime.timestamp() - Y2K_TIMESTAMP) as u32
+}
+
+// Decodes a date/time encoded as seconds since 2000-01-01.
+// Returns `None` if it is after 2099, which means the data is corrupt.
+pub(crate) fn from_seconds_since_2000(seconds: u32) -> Option<DateTime> {
+    DateTime::from_timestamp(Y2K_TIMESTAMP + i64::from(seconds))
+}
+
+// Converts a date/time out of the range 2000-2099 into an error.
+#[cfg(feature = "rtcc")]
+pub(crate) fn datetime_from_naive<E>(datetime: &NaiveDateTime) -> Result<DateTime, Error<E>> {
+    DateTime::try_from(*datetime).map_err(|_| Error::InvalidInputData)
+}
+
+pub(crate) fn hours_to_h24(hour: Hours) -> u8 {
+    match hour {
+        Hours::H24(h) => h,
+        Hours::AM(h) => h,
+        Hours::PM(h) => h + 12,
+    }
+}
+
+// Decodes the SECONDS..YEAR registers.
+pub(crate) fn datetime_from_registers<E>(data: &[u8; 7]) -> Result<DateTime, Error<E>> {
+    let year = 2000 + u16::from(packed_bcd_to_decimal(data[Register::YEAR as usize]));
+    let month = packed_bcd_to_decimal(data[Register::MONTH as usize] & !BitFlags::LEAPYEAR);
+    let day = packed_bcd_to_decimal(data[Register::DAY as usize]);
+    let h24 = hours_to_h24(hours_from_register(data[Register::HOURS as usize]));
+    let minute = packed_bcd_to_decimal(data[Register::MINUTES as usize]);
+    let second = packed_bcd_to_decimal(data[Register::SECONDS as usize] & !BitFlags::ST);
+
+    if DateTime::new(year, month, day, 0, 0, 0).is_none() {
+        return Err(Error::InvalidDateData(
+            year.into(),
+            month.into(),
+            day.into(),
+        ));
+    }
+    DateTime::new(year, month, day, h24, minute, second).ok_or(Error::InvalidTimeData(
+        h24.into(),
+        minute.into(),
+        second.into(),
+    ))
+}
+
+// Decodes the SECONDS..HOURS registers.
+#[cfg(feature = "rtcc")]
+pub(crate) fn time_from_registers<E>(data: &[u8; 3]) -> Result<NaiveTime, Error<E>> {
+    let h24 = hours_to_h24(hours_from_register(data[Register::HOURS as usize]));
+    let minute = packed_bcd_to_decimal(data[Register::MINUTES as usize]);
+    let second = packed_bcd_to_decimal(data[Register::SECONDS as usize] & !BitFlags::ST);
+    NaiveTime::from_hms_opt(h24.into(), minute.into(), second.into()).ok_or(Error::InvalidTimeData(
+        h24.into(),
+        minute.into(),
+        second.into(),
+    ))
+}
+
+// Decodes the DAY..YEAR registers.
+#[cfg(feature = "rtcc")]
+pub(crate) fn date_from_registers<E>(data: &[u8; 3]) -> Result<NaiveDate, Error<E>> {
+    let year = 2000 + u16::from(packed_bcd_to_decimal(data[2]));
+    let month = packed_bcd_to_decimal(data[1] & !BitFlags::LEAPYEAR);
+    let day = packed_bcd_to_decimal(data[0]);
+    NaiveDate::from_ymd_opt(year.into(), month.into(), day.into()).ok_or(Error::InvalidDateData(
+        year.into(),
+        month.into(),
+        day.into(),
+    ))
+}
+
+// Encodes a write payload for the SECONDS..YEAR registers.
+pub(crate) fn datetime_to_registers(datetime: &DateTime, is_enabled: bool) -> [u8; 8] {
+    [
+        Register::SECONDS,
+        seconds_to_register(datetime.second(), is_enabled),
+        decimal_to_packed_bcd(datetime.minute()),
+        decimal_to_packed_bcd(datetime.hour()),
+        datetime.weekday(),
+        decimal_to_packed_bcd(datetime.day()),
+        decimal_to_packed_bcd(datetime.month()),
+        decimal_to_packed_bcd((datetime.year() - 2000) as u8),
+    ]
+}
+
+// Encodes a write payload for the SECONDS..HOURS registers.
+#[cfg(feature = "rtcc")]
+pub(crate) fn time_to_registers<E>(
+    time: &NaiveTime,
+    is_enabled: bool,
+) -> Result<[u8; 4], Error<E>> {
+    if time.minute() > 59 || time.second() > 59 {
+        return Err(Error::InvalidInputData);
+    }
+    Ok([
+        Register::SECONDS,
+        seconds_to_register(time.second() as u8, is_enabled),
+        decimal_to_packed_bcd(time.minute() as u8),
+        hours_to_register(Hours::H24(time.hour() as u8))?,
+    ])
+}
+
+// Encodes a write payload for the WEEKDAY..YEAR registers.
+#[cfg(feature = "rtcc")]
+pub(crate) fn date_to_registers<E>(date: &NaiveDate) -> Result<[u8; 5], Error<E>> {
+    if date.year() < 2000 || date.year() > 2099 {
+        return Err(Error::InvalidInputData);
+    }
+    Ok([
+        Register::WEEKDAY,
+        date.weekday().number_from_sunday() as u8,
+        decimal_to_packed_bcd(date.day() as u8),
+        decimal_to_packed_bcd(date.month() as u8),
+        decimal_to_packed_bcd((date.year() - 2000) as u8),
+    ])
+}
+
+// Encodes the SECONDS register keeping the oscillator running if enabled.
+pub(crate) fn seconds_to_register(seconds: u8, is_enabled: bool) -> u8 {
+    let seconds = decimal_to_packed_bcd(seconds);
+    if is_enabled {
+        seconds | BitFlags::ST
+    } else {
+        seconds
+    }
 }
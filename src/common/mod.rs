@@ -1,7 +1,11 @@
-use crate::{interface, BitFlags, Config, Error, Mcp794xx, OutputPinLevel, Register, SqWFreq};
+use crate::{
+    interface, AlarmOutputPinPolarity, BitFlags, Config, Error, Mcp794xx, OutputPinLevel, Register,
+    SqWFreq, Status,
+};
 pub mod alarm;
 pub mod conversion;
 pub mod datetime;
+pub mod ring;
 pub mod sram;
 
 impl Config {
@@ -15,6 +19,65 @@ impl Config {
             bits: self.bits & !mask,
         }
     }
+    pub(crate) fn with_square_wave_frequency(self, frequency: SqWFreq) -> Self {
+        let bits = match frequency {
+            SqWFreq::Hz1 => 0,
+            SqWFreq::Hz4_096 => 1,
+            SqWFreq::Hz8_192 => 2,
+            SqWFreq::Hz32_768 => 3,
+        };
+        Config {
+            bits: (self.bits & 0b1111_1100) | bits,
+        }
+    }
+}
+
+// Encodes the OSCTRIM register in sign-magnitude format.
+pub(crate) fn trimming_to_register(value: i8) -> u8 {
+    if value < 0 && value != -128 {
+        let rest = !(value - 1) as u8;
+        0b1000_0000 | rest
+    } else {
+        value as u8
+    }
+}
+
+// Decodes the OSCTRIM register from sign-magnitude format.
+pub(crate) fn trimming_from_register(data: u8) -> i8 {
+    let magnitude = (data & 0b0111_1111) as i8;
+    if data & 0b1000_0000 != 0 {
+        -magnitude
+    } else {
+        magnitude
+    }
+}
+
+// Amount of registers from SECONDS up to and including ALM1WKDAY.
+pub(crate) const STATUS_REGISTER_COUNT: usize = Register::ALM1WKDAY as usize + 1;
+
+// Decodes the status bits from the SECONDS..ALM1WKDAY registers.
+pub(crate) fn status_from_registers(data: &[u8; STATUS_REGISTER_COUNT]) -> Status {
+    let is_set = |register: u8, mask: u8| data[usize::from(register)] & mask != 0;
+    Status {
+        is_enabled: is_set(Register::SECONDS, BitFlags::ST),
+        is_oscillator_running: is_set(Register::WEEKDAY, BitFlags::OSCRUN),
+        has_power_failed: is_set(Register::WEEKDAY, BitFlags::PWRFAIL),
+        is_battery_power_enabled: is_set(Register::WEEKDAY, BitFlags::VBATEN),
+        is_leap_year: is_set(Register::MONTH, BitFlags::LEAPYEAR),
+        has_alarm0_matched: is_set(Register::ALM0WKDAY, BitFlags::ALMIF),
+        has_alarm1_matched: is_set(Register::ALM1WKDAY, BitFlags::ALMIF),
+        is_alarm0_enabled: is_set(Register::CONTROL, BitFlags::ALM0EN),
+        is_alarm1_enabled: is_set(Register::CONTROL, BitFlags::ALM1EN),
+        is_square_wave_enabled: is_set(Register::CONTROL, BitFlags::SQWEN),
+        is_external_oscillator_enabled: is_set(Register::CONTROL, BitFlags::EXTOSC),
+        is_coarse_trim_enabled: is_set(Register::CONTROL, BitFlags::CRSTRIM),
+        output_pin_level: if is_set(Register::CONTROL, BitFlags::OUT) {
+            OutputPinLevel::High
+        } else {
+            OutputPinLevel::Low
+        },
+        trimming: trimming_from_register(data[usize::from(Register::OSCTRIM)]),
+    }
 }
 
 impl<DI, E, IC> Mcp794xx<DI, IC>
@@ -73,16 +136,7 @@ where
     /// Note that this setting will be ignored if the square-wave output is not
     /// enabled or digital trimming is enabled.
     pub fn set_square_wave_frequency(&mut self, frequency: SqWFreq) -> Result<(), Error<E>> {
-        let bits = match frequency {
-            SqWFreq::Hz1 => 0,
-            SqWFreq::Hz4_096 => 1,
-            SqWFreq::Hz8_192 => 2,
-            SqWFreq::Hz32_768 => 3,
-        };
-        let control = Config {
-            bits: (self.control.bits & 0b1111_1100) | bits,
-        };
-        self.write_control(control)
+        self.write_control(self.control.with_square_wave_frequency(frequency))
     }
 
     /// Set output pin logic level.
@@ -117,13 +171,44 @@ where
     /// either once per minute or 128 times per second.
     /// Set to 0 or -128 to disable digital trimming.
     pub fn set_trimming(&mut self, value: i8) -> Result<(), Error<E>> {
-        if value < 0 && value != -128 {
-            let rest = !(value - 1) as u8;
-            self.iface
-                .write_register(Register::OSCTRIM, 0b1000_0000 | rest)
+        self.iface
+            .write_register(Register::OSCTRIM, trimming_to_register(value))
+    }
+
+    /// Read the device state and update the driver state accordingly.
+    ///
+    /// The driver keeps track of some settings (whether the oscillator is
+    /// enabled, the 12h/24h mode, backup battery power usage, the CONTROL
+    /// register and the alarm output pin polarity) to avoid having to read
+    /// them every time. Call this after creating an instance for a device
+    /// that may already be running (e.g. after a reset of the MCU while the
+    /// RTC was kept running on backup battery power).
+    pub fn sync(&mut self) -> Result<(), Error<E>> {
+        let mut data = [0; 8];
+        self.iface.read_data(Register::SECONDS, &mut data)?;
+        let alm0_weekday = self.iface.read_register(Register::ALM0WKDAY)?;
+        self.is_enabled = data[Register::SECONDS as usize] & BitFlags::ST != 0;
+        self.is_running_in_24h_mode = data[Register::HOURS as usize] & BitFlags::H24_H12 == 0;
+        self.is_battery_power_enabled = data[Register::WEEKDAY as usize] & BitFlags::VBATEN != 0;
+        self.control = Config {
+            bits: data[Register::CONTROL as usize],
+        };
+        self.alarm_output_pin_polarity = if alm0_weekday & BitFlags::ALMPOL != 0 {
+            AlarmOutputPinPolarity::High
         } else {
-            self.iface.write_register(Register::OSCTRIM, value as u8)
-        }
+            AlarmOutputPinPolarity::Low
+        };
+        Ok(())
+    }
+
+    /// Read the complete device status in a single transfer.
+    ///
+    /// This reads the registers from seconds up to the alarm 1 weekday at
+    /// once and decodes the status and configuration bits.
+    pub fn status(&mut self) -> Result<Status, Error<E>> {
+        let mut data = [0; STATUS_REGISTER_COUNT];
+        self.iface.read_data(Register::SECONDS, &mut data)?;
+        Ok(status_from_registers(&data))
     }
 
     fn write_control(&mut self, control: Config) -> Result<(), Error<E>> {
@@ -132,6 +217,7 @@ where
         Ok(())
     }
 
+    #[cfg(feature = "rtcc")]
     #[allow(clippy::needless_pass_by_value)]
     fn check_lt<T: PartialOrd>(value: T, reference: T) -> Result<(), Error<E>> {
         if value < reference {
@@ -141,6 +227,7 @@ where
         }
     }
 
+    #[cfg(feature = "rtcc")]
     #[allow(clippy::needless_pass_by_value)]
     fn check_gt<T: PartialOrd>(value: T, reference: T) -> Result<(), Error<E>> {
         if value > reference {
@@ -150,3 +237,21 @@ where
         }
     }
 }
+
+// CRC-16/CCITT-FALSE
+pub(crate) fn crc16(data: &[u8]) -> u16 {
+    crc16_update(0xFFFF, data)
+}
+
+// Continues a CRC-16/CCITT-FALSE with more data.
+pub(crate) fn crc16_update(crc: u16, data: &[u8]) -> u16 {
+    data.iter().fold(crc, |crc, byte| {
+        (0..8).fold(crc ^ (u16::from(*byte) << 8), |crc, _| {
+            if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x1021
+            } else {
+                crc << 1
+            }
+        })
+    })
+}
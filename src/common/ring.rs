@@ -0,0 +1,95 @@
+//! Ring buffer bookkeeping shared by the event log and the outage history
+
+use super::{crc16, crc16_update};
+
+// Size of the head, the length and the CRC-16 in a header
+pub(crate) const HEADER_OVERHEAD: usize = 4;
+
+// Position of the next entry, amount of entries and capacity of a ring
+// buffer. Its header holds the head, the length, any extra data and a CRC-16
+// (CCITT) of all of them followed by the capacity, so that changing the
+// region size invalidates the header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Ring {
+    pub(crate) head: u8,
+    pub(crate) len: u8,
+    pub(crate) capacity: u8,
+}
+
+impl Ring {
+    pub(crate) fn empty(capacity: u8) -> Self {
+        Ring {
+            head: 0,
+            len: 0,
+            capacity,
+        }
+    }
+
+    // Returns `None` if the header is not valid.
+    pub(crate) fn from_header(header: &[u8], capacity: u8) -> Option<Self> {
+        let ring = Ring {
+            head: header[0],
+            len: header[1],
+            capacity,
+        };
+        let crc_start = header.len() - 2;
+        let is_valid = ring.head < capacity
+            && ring.len <= capacity
+            && ring.header_crc(header).to_be_bytes() == header[crc_start..];
+        if is_valid {
+            Some(ring)
+        } else {
+            None
+        }
+    }
+
+    // Sets the head, the length and the CRC-16 keeping the extra data.
+    pub(crate) fn write_header(&self, header: &mut [u8]) {
+        header[0] = self.head;
+        header[1] = self.len;
+        let crc_start = header.len() - 2;
+        let crc = self.header_crc(header).to_be_bytes();
+        header[crc_start..].copy_from_slice(&crc);
+    }
+
+    fn header_crc(&self, header: &[u8]) -> u16 {
+        let crc = crc16(&header[..header.len() - 2]);
+        crc16_update(crc, &[self.capacity])
+    }
+
+    // Ring after an entry has been written at the head, overwriting the
+    // oldest entry if full.
+    pub(crate) fn pushed(&self) -> Self {
+        Ring {
+            head: (self.head + 1) % self.capacity,
+            len: (self.len + 1).min(self.capacity),
+            capacity: self.capacity,
+        }
+    }
+
+    // Position of the entry at `index`, where 0 is the newest.
+    pub(crate) fn position(&self, index: usize) -> Option<u8> {
+        let capacity = usize::from(self.capacity);
+        if index >= usize::from(self.len) {
+            return None;
+        }
+        Some(((usize::from(self.head) + capacity - 1 - index) % capacity) as u8)
+    }
+}
+
+// Returns the entry at `index` or the following ones, skipping the corrupt
+// ones for which `get` returns `None`, and advances `index` past it.
+pub(crate) fn next_entry<T, E>(
+    index: &mut usize,
+    len: usize,
+    mut get: impl FnMut(usize) -> Result<Option<T>, E>,
+) -> Option<Result<T, E>> {
+    while *index < len {
+        let entry = get(*index).transpose();
+        *index += 1;
+        if entry.is_some() {
+            return entry;
+        }
+    }
+    None
+}
@@ -0,0 +1,292 @@
+//! Five-field cron expressions for alarm schedules
+
+use crate::calendar::days_in_month;
+use crate::common::alarm::alarm_datetime;
+use crate::{
+    interface, marker, Alarm, AlarmMatching, AlarmOutputPinPolarity, DateTime, Error, Mcp794xx,
+};
+use core::str::FromStr;
+
+const MONTH_NAMES: [&str; 12] = [
+    "JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC",
+];
+const WEEKDAY_NAMES: [&str; 7] = ["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"];
+
+/// Field of a cron expression
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CronField {
+    /// Minute [0-59]
+    Minute,
+    /// Hour [0-23]
+    Hour,
+    /// Day of the month [1-31]
+    DayOfMonth,
+    /// Month [1-12] or `JAN`-`DEC`
+    Month,
+    /// Day of the week [0-7] or `SUN`-`SAT` where both 0 and 7 are Sunday
+    DayOfWeek,
+}
+
+impl CronField {
+    fn range(self) -> (u8, u8) {
+        match self {
+            CronField::Minute => (0, 59),
+            CronField::Hour => (0, 23),
+            CronField::DayOfMonth => (1, 31),
+            CronField::Month => (1, 12),
+            CronField::DayOfWeek => (0, 7),
+        }
+    }
+
+    fn names(self) -> &'static [&'static str] {
+        match self {
+            CronField::Month => &MONTH_NAMES,
+            CronField::DayOfWeek => &WEEKDAY_NAMES,
+            _ => &[],
+        }
+    }
+}
+
+/// Cron expression parsing error
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CronError {
+    /// The expression does not have five fields separated by whitespace.
+    FieldCount,
+    /// Nicknames such as `@daily` are not supported.
+    Nickname,
+    /// A value is not a number or name or it is out of the range of the field.
+    InvalidValue(CronField),
+    /// A range or step is malformed, e.g. `5-2` or `*/0`.
+    InvalidRange(CronField),
+    /// Special characters such as `L`, `W`, `#` or `?` are not supported.
+    UnsupportedSyntax(CronField),
+}
+
+/// Five-field cron expression: minute, hour, day of month, month and
+/// day of week
+///
+/// Each field supports `*`, values, ranges (`1-5`), steps (`*/15`, `0-30/10`
+/// or `5/15`) and comma-separated lists of them. Months and days of the week
+/// can also be given as three-letter English names (`JAN`, `MON`).
+///
+/// As in the usual cron implementations, if both the day of the month and the
+/// day of the week are restricted (not starting with `*`), a day matches if
+/// either of them matches.
+///
+/// The parser does not allocate.
+///
+/// ```
+/// use mcp794xx::Cron;
+///
+/// let workdays = Cron::parse("30 7 * * MON-FRI").unwrap();
+/// let quarter_hours: Cron = "*/15 * * * *".parse().unwrap();
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cron {
+    minutes: u64,
+    hours: u32,
+    days: u32,
+    months: u16,
+    weekdays: u8,
+    is_day_restricted: bool,
+    is_weekday_restricted: bool,
+}
+
+impl Cron {
+    /// Parse a five-field cron expression.
+    pub fn parse(expression: &str) -> Result<Self, CronError> {
+        if expression.trim_start().starts_with('@') {
+            return Err(CronError::Nickname);
+        }
+        let mut fields = expression.split_whitespace();
+        let mut next = || fields.next().ok_or(CronError::FieldCount);
+        let (minute, hour, day, month, weekday) = (next()?, next()?, next()?, next()?, next()?);
+        if fields.next().is_some() {
+            return Err(CronError::FieldCount);
+        }
+        let weekdays = parse_field(weekday, CronField::DayOfWeek)?;
+        Ok(Cron {
+            minutes: parse_field(minute, CronField::Minute)?,
+            hours: parse_field(hour, CronField::Hour)? as u32,
+            days: parse_field(day, CronField::DayOfMonth)? as u32,
+            months: parse_field(month, CronField::Month)? as u16,
+            // Sunday is both 0 and 7
+            weekdays: ((weekdays | weekdays >> 7) & 0x7F) as u8,
+            is_day_restricted: !day.starts_with('*'),
+            is_weekday_restricted: !weekday.starts_with('*'),
+        })
+    }
+
+    /// Returns the first date/time after the given one matching the
+    /// expression.
+    ///
+    /// The occurrences happen at second 0.
+    /// Returns `None` if there is no occurrence until the end of 2099, e.g.
+    /// for `0 0 30 2 *`.
+    ///
+    /// With the `rtcc` feature, a `NaiveDateTime` can be converted into a
+    /// [`DateTime`] with `DateTime::try_from()`.
+    pub fn next_after(&self, datetime: &DateTime) -> Option<DateTime> {
+        let (mut year, mut month, mut day) = (datetime.year(), datetime.month(), datetime.day());
+        let (mut hour, mut minute) = (datetime.hour(), datetime.minute() + 1);
+        loop {
+            // Fails after 2099-12-31
+            let date = DateTime::new(year, month, day, 0, 0, 0)?;
+            if self.matches_date(&date) {
+                if let Some((hour, minute)) = self.first_time_from(hour, minute) {
+                    return DateTime::new(year, month, day, hour, minute, 0);
+                }
+            }
+            hour = 0;
+            minute = 0;
+            day += 1;
+            if day > days_in_month(year, month) {
+                day = 1;
+                month += 1;
+                if month > 12 {
+                    month = 1;
+                    year += 1;
+                }
+            }
+        }
+    }
+
+    /// Returns whether the date/time matches the expression, ignoring the
+    /// seconds.
+    pub fn matches(&self, datetime: &DateTime) -> bool {
+        self.matches_date(datetime)
+            && self.hours & (1 << datetime.hour()) != 0
+            && self.minutes & (1 << datetime.minute()) != 0
+    }
+
+    fn matches_date(&self, date: &DateTime) -> bool {
+        let day = self.days & (1 << date.day()) != 0;
+        let weekday = self.weekdays & (1 << (date.weekday() - 1)) != 0;
+        let day_matches = match (self.is_day_restricted, self.is_weekday_restricted) {
+            (true, true) => day || weekday,
+            _ => day && weekday,
+        };
+        day_matches && self.months & (1 << date.month()) != 0
+    }
+
+    // First hour and minute matching at or after the given ones in a day
+    fn first_time_from(&self, hour: u8, minute: u8) -> Option<(u8, u8)> {
+        (hour..24)
+            .filter(|h| self.hours & (1 << h) != 0)
+            .find_map(|h| {
+                let from = if h == hour { minute } else { 0 };
+                (from..60)
+                    .find(|m| self.minutes & (1 << m) != 0)
+                    .map(|m| (h, m))
+            })
+    }
+}
+
+impl FromStr for Cron {
+    type Err = CronError;
+
+    fn from_str(expression: &str) -> Result<Self, Self::Err> {
+        Cron::parse(expression)
+    }
+}
+
+// Parses a field into a bit set where bit `n` is set if value `n` matches.
+fn parse_field(field: &str, kind: CronField) -> Result<u64, CronError> {
+    let (min, max) = kind.range();
+    let mut bits = 0;
+    for element in field.split(',') {
+        let (range, step) = match element.split_once('/') {
+            Some((range, step)) => match parse_number(step) {
+                Some(0) => return Err(CronError::InvalidRange(kind)),
+                Some(step) => (range, Some(step)),
+                None => return Err(value_error(step, kind)),
+            },
+            None => (element, None),
+        };
+        let (first, last) = if range == "*" {
+            (min, max)
+        } else if let Some((first, last)) = range.split_once('-') {
+            (parse_value(first, kind)?, parse_value(last, kind)?)
+        } else {
+            let first = parse_value(range, kind)?;
+            (first, if step.is_some() { max } else { first })
+        };
+        if first > last {
+            return Err(CronError::InvalidRange(kind));
+        }
+        for value in (first..=last).step_by(step.unwrap_or(1).into()) {
+            bits |= 1 << value;
+        }
+    }
+    Ok(bits)
+}
+
+fn parse_value(value: &str, kind: CronField) -> Result<u8, CronError> {
+    let (min, max) = kind.range();
+    if let Some(index) = kind
+        .names()
+        .iter()
+        .position(|name| name.eq_ignore_ascii_case(value))
+    {
+        return Ok(index as u8 + min);
+    }
+    match parse_number(value) {
+        Some(value) if (min..=max).contains(&value) => Ok(value),
+        Some(_) => Err(CronError::InvalidValue(kind)),
+        None => Err(value_error(value, kind)),
+    }
+}
+
+fn parse_number(value: &str) -> Option<u8> {
+    if value.is_empty() || !value.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+    value.parse().ok()
+}
+
+fn value_error(value: &str, kind: CronField) -> CronError {
+    if value.contains(['L', 'W', '#', '?']) {
+        CronError::UnsupportedSyntax(kind)
+    } else {
+        CronError::InvalidValue(kind)
+    }
+}
+
+impl<DI, E, IC> Mcp794xx<DI, IC>
+where
+    DI: interface::WriteData<Error = Error<E>> + interface::ReadData<Error = Error<E>>,
+    IC: marker::WithAlarms,
+{
+    /// Set and enable an alarm to match at the next occurrence of a cron
+    /// expression after the current date/time and return the date/time at
+    /// which it will match.
+    ///
+    /// The alarm uses the `AllMatch` trigger rate. Call this again after
+    /// the alarm has matched to set the following occurrence.
+    ///
+    /// Returns `Error::InvalidInputData` if there is no occurrence until the
+    /// end of 2099 or the alarm would match earlier since the alarm registers
+    /// do not include the year.
+    ///
+    /// Since the alarm 1 of the MCP795xx devices does not have a month
+    /// register, only the alarm 0 can be used with them. `Error::InvalidInputData`
+    /// is returned for the alarm 1 without setting it.
+    ///
+    /// See [`set_alarm()`](#method.set_alarm) for further notes.
+    pub fn set_cron_alarm(
+        &mut self,
+        alarm: Alarm,
+        cron: &Cron,
+        polarity: AlarmOutputPinPolarity,
+    ) -> Result<DateTime, Error<E>> {
+        let now = self.read_datetime()?;
+        let next = cron.next_after(&now).ok_or(Error::InvalidInputData)?;
+        let when = alarm_datetime(&next);
+        if when.next_match(AlarmMatching::AllMatch, &now) != Some(next) {
+            return Err(Error::InvalidInputData);
+        }
+        self.set_alarm(alarm, when, AlarmMatching::AllMatch, polarity)?;
+        self.enable_alarm(alarm)?;
+        Ok(next)
+    }
+}
@@ -1,7 +1,6 @@
 //! EEPROM methods
 use crate::{interface, marker, EepromWriteProtection, Error, Mcp794xx};
-const EEUNLOCK: u8 = 0b0000_1001;
-const EEPROM_STATUS: u8 = 0xFF;
+pub(crate) const EEPROM_PAGE_SIZE: usize = 8;
 
 impl<DI, E, IC> Mcp794xx<DI, IC>
 where
@@ -16,7 +15,10 @@ where
         if !is_protected_eeprom_address(address) {
             return Err(Error::InvalidInputData);
         }
-        self.iface.read_eeprom_byte(address)
+        let mut data = [0];
+        self.iface
+            .read_protected_eeprom_data(address, &mut data)
+            .and(Ok(data[0]))
     }
 
     /// Read protected EEPROM starting in an address as many bytes as
@@ -30,13 +32,10 @@ where
         address: u8,
         data: &mut [u8],
     ) -> Result<(), Error<E>> {
-        if !is_protected_eeprom_address(address)
-            || data.len() > 8
-            || (data.len() as u8 + address) > 0xF8
-        {
+        if !is_protected_eeprom_data_range(address, data.len()) {
             return Err(Error::InvalidInputData);
         }
-        self.iface.read_eeprom_data(address, data)
+        self.iface.read_protected_eeprom_data(address, data)
     }
 
     /// Unlock protected EEPROM and write a single byte to an address.
@@ -47,9 +46,8 @@ where
         if !is_protected_eeprom_address(address) {
             return Err(Error::InvalidInputData);
         }
-        self.iface.write_register(EEUNLOCK, 0x55)?;
-        self.iface.write_register(EEUNLOCK, 0xAA)?;
-        self.iface.write_eeprom_byte(address, data)
+        self.iface.write_protected_eeprom_data(&[address, data])?;
+        self.wait_eeprom_write()
     }
 
     /// Write data array starting in an address in the protected EEPROM.
@@ -62,26 +60,36 @@ where
         address: u8,
         data: &[u8],
     ) -> Result<(), Error<E>> {
-        if !is_protected_eeprom_address(address)
-            || data.len() > 8
-            || (data.len() as u8 + address) > 0xF8
-        {
+        if !is_protected_eeprom_data_range(address, data.len()) {
             return Err(Error::InvalidInputData);
         }
         let mut payload = [0; 9]; // max size
         payload[0] = address;
         payload[1..=data.len()].copy_from_slice(data);
-        self.iface.write_register(EEUNLOCK, 0x55)?;
-        self.iface.write_register(EEUNLOCK, 0xAA)?;
-        self.iface.write_eeprom_data(&payload[..=data.len()])
+        self.iface
+            .write_protected_eeprom_data(&payload[..=data.len()])?;
+        self.wait_eeprom_write()
     }
 }
 
 #[allow(clippy::manual_range_contains)]
-fn is_protected_eeprom_address(address: u8) -> bool {
+pub(crate) fn is_protected_eeprom_address(address: u8) -> bool {
     address >= 0xF0 && address <= 0xF7
 }
 
+pub(crate) fn is_protected_eeprom_data_range(address: u8, len: usize) -> bool {
+    is_protected_eeprom_address(address) && len <= 8 && (len as u8 + address) <= 0xF8
+}
+
+pub(crate) fn write_protection_to_register(protection: EepromWriteProtection) -> u8 {
+    match protection {
+        EepromWriteProtection::None => 0,
+        EepromWriteProtection::UpperQuarter => 0b0000_0100,
+        EepromWriteProtection::UpperHalf => 0b0000_1000,
+        EepromWriteProtection::All => 0b0000_1100,
+    }
+}
+
 impl<DI, E, IC> Mcp794xx<DI, IC>
 where
     DI: interface::WriteData<Error = Error<E>> + interface::ReadData<Error = Error<E>>,
@@ -92,21 +100,18 @@ where
         &mut self,
         protection: EepromWriteProtection,
     ) -> Result<(), Error<E>> {
-        let value = match protection {
-            EepromWriteProtection::None => 0,
-            EepromWriteProtection::UpperQuarter => 0b0000_0100,
-            EepromWriteProtection::UpperHalf => 0b0000_1000,
-            EepromWriteProtection::All => 0b0000_1100,
-        };
-        self.iface.write_eeprom_byte(EEPROM_STATUS, value)
+        self.iface
+            .write_eeprom_status(write_protection_to_register(protection))?;
+        self.wait_eeprom_write()
     }
 
     /// Read a single byte from an address in EEPROM.
     ///
-    /// Valid addresses are in the range `[0x00-0x7F]`.
+    /// Valid addresses are in the range `[0x00-0x7F]` (`[0x00-0xFF]` for
+    /// devices with 2 Kbit EEPROM).
     /// `Error::InvalidInputData` will be returned for invalid addresses.
     pub fn read_eeprom_byte(&mut self, address: u8) -> Result<u8, Error<E>> {
-        if is_eeprom_address(address) {
+        if is_eeprom_address(address, IC::EEPROM_SIZE) {
             self.iface.read_eeprom_byte(address)
         } else {
             Err(Error::InvalidInputData)
@@ -116,11 +121,12 @@ where
     /// Read EEPROM starting in an address as many bytes as necessary to fill
     /// the data array provided.
     ///
-    /// Valid addresses are in the range `[0x00-0x7F]`.
+    /// Valid addresses are in the range `[0x00-0x7F]` (`[0x00-0xFF]` for
+    /// devices with 2 Kbit EEPROM).
     /// `Error::InvalidInputData` will be returned for invalid addresses or
     /// if the reading would overflow the size of the EEPROM.
     pub fn read_eeprom_data(&mut self, address: u8, data: &mut [u8]) -> Result<(), Error<E>> {
-        if is_eeprom_address(address) && data.len() <= 128 && (address + data.len() as u8) < 0x80 {
+        if is_eeprom_data_range(address, data.len(), IC::EEPROM_SIZE) {
             self.iface.read_eeprom_data(address, data)
         } else {
             Err(Error::InvalidInputData)
@@ -129,11 +135,13 @@ where
 
     /// Write a single byte to an address in EEPROM.
     ///
-    /// Valid addresses are in the range `[0x00-0x7F]`.
+    /// Valid addresses are in the range `[0x00-0x7F]` (`[0x00-0xFF]` for
+    /// devices with 2 Kbit EEPROM).
     /// `Error::InvalidInputData` will be returned for invalid addresses.
     pub fn write_eeprom_byte(&mut self, address: u8, data: u8) -> Result<(), Error<E>> {
-        if is_eeprom_address(address) {
-            self.iface.write_eeprom_byte(address, data)
+        if is_eeprom_address(address, IC::EEPROM_SIZE) {
+            self.iface.write_eeprom_byte(address, data)?;
+            self.wait_eeprom_write()
         } else {
             Err(Error::InvalidInputData)
         }
@@ -141,23 +149,102 @@ where
 
     /// Write data array starting in an address in EEPROM.
     ///
-    /// Valid addresses are in the range `[0x00-0x7F]`.
+    /// The data is split into several writes at the 8-byte EEPROM page
+    /// boundaries and the completion of each write cycle is waited for.
+    ///
+    /// Valid addresses are in the range `[0x00-0x7F]` (`[0x00-0xFF]` for
+    /// devices with 2 Kbit EEPROM).
     /// `Error::InvalidInputData` will be returned for invalid addresses or
     /// if the writing would overflow the size of the EEPROM.
     pub fn write_eeprom_data(&mut self, address: u8, data: &[u8]) -> Result<(), Error<E>> {
-        if is_eeprom_address(address) && data.len() <= 128 && (address + data.len() as u8) < 0x80 {
-            let mut payload = [0; 128]; // max size
-            payload[0] = address;
-            payload[1..=data.len()].copy_from_slice(data);
-            self.iface.write_eeprom_data(&payload[..=data.len()])
-        } else {
-            Err(Error::InvalidInputData)
+        if !is_eeprom_data_range(address, data.len(), IC::EEPROM_SIZE) {
+            return Err(Error::InvalidInputData);
         }
+        self.write_eeprom_pages(address, data)
+    }
+}
+
+pub(crate) fn is_eeprom_address(address: u8, size: usize) -> bool {
+    usize::from(address) < size
+}
+
+// Amount of bytes that can be written starting at an address without
+// crossing an EEPROM page boundary.
+fn page_write_len(address: u8, len: usize) -> usize {
+    let page_remaining = EEPROM_PAGE_SIZE - usize::from(address) % EEPROM_PAGE_SIZE;
+    len.min(page_remaining)
+}
+
+// Write payload of the data for one EEPROM page prefixed with its address.
+pub(crate) struct PageWrite {
+    buffer: [u8; EEPROM_PAGE_SIZE + 1],
+    len: usize,
+}
+
+impl PageWrite {
+    pub(crate) fn payload(&self) -> &[u8] {
+        &self.buffer[..=self.len]
     }
 }
 
-fn is_eeprom_address(address: u8) -> bool {
-    address < 0x80
+// Splits data to be written starting at an address at the EEPROM page
+// boundaries.
+pub(crate) fn page_writes(address: u8, data: &[u8]) -> impl Iterator<Item = PageWrite> + '_ {
+    let mut address = address;
+    let mut data = data;
+    core::iter::from_fn(move || {
+        if data.is_empty() {
+            return None;
+        }
+        let len = page_write_len(address, data.len());
+        let mut buffer = [0; EEPROM_PAGE_SIZE + 1];
+        buffer[0] = address;
+        buffer[1..=len].copy_from_slice(&data[..len]);
+        address = address.wrapping_add(len as u8);
+        data = &data[len..];
+        Some(PageWrite { buffer, len })
+    })
+}
+
+pub(crate) fn is_eeprom_data_range(address: u8, len: usize, size: usize) -> bool {
+    is_eeprom_address(address, size) && usize::from(address) + len <= size
+}
+
+impl<DI, E, IC> Mcp794xx<DI, IC>
+where
+    DI: interface::ReadData<Error = Error<E>>,
+{
+    /// Set the maximum amount of times the device is polled while waiting
+    /// for an EEPROM write cycle to complete (default: 1000).
+    ///
+    /// Each attempt takes roughly the time needed to transfer one byte
+    /// on the bus. A write cycle can take up to 5ms.
+    /// If the write cycle has not completed after this amount of attempts,
+    /// `Error::EepromWriteTimeout` will be returned.
+    pub fn set_eeprom_write_poll_attempts(&mut self, attempts: u32) {
+        self.eeprom_poll_attempts = attempts;
+    }
+
+    // The range must have been checked against the EEPROM size.
+    pub(crate) fn write_eeprom_pages(&mut self, address: u8, data: &[u8]) -> Result<(), Error<E>>
+    where
+        DI: interface::WriteData<Error = Error<E>>,
+    {
+        for page in page_writes(address, data) {
+            self.iface.write_eeprom_data(page.payload())?;
+            self.wait_eeprom_write()?;
+        }
+        Ok(())
+    }
+
+    fn wait_eeprom_write(&mut self) -> Result<(), Error<E>> {
+        for _ in 0..self.eeprom_poll_attempts {
+            if !self.iface.is_eeprom_write_in_progress()? {
+                return Ok(());
+            }
+        }
+        Err(Error::EepromWriteTimeout)
+    }
 }
 
 impl<DI, E, IC> Mcp794xx<DI, IC>
@@ -181,7 +268,9 @@ where
     /// Read pre-programmed EUI-48 node address from EEPROM.
     pub fn read_eui48(&mut self) -> Result<[u8; 6], Error<E>> {
         let mut data = [0; 6];
-        self.iface.read_eeprom_data(0xF2, &mut data).and(Ok(data))
+        self.iface
+            .read_protected_eeprom_data(0xF2, &mut data)
+            .and(Ok(data))
     }
 }
 
@@ -193,6 +282,8 @@ where
     /// Read pre-programmed EUI-64 node address from EEPROM.
     pub fn read_eui64(&mut self) -> Result<[u8; 8], Error<E>> {
         let mut data = [0; 8];
-        self.iface.read_eeprom_data(0xF0, &mut data).and(Ok(data))
+        self.iface
+            .read_protected_eeprom_data(0xF0, &mut data)
+            .and(Ok(data))
     }
 }
@@ -0,0 +1,161 @@
+//! Event log ring buffer in the battery-backed SRAM
+
+use crate::common::datetime::{from_seconds_since_2000, to_seconds_since_2000};
+use crate::common::ring::{next_entry, Ring, HEADER_OVERHEAD};
+use crate::common::sram::is_sram_data_range;
+use crate::{interface, DateTime, Error, Mcp794xx};
+
+// Head, count and CRC-16
+const HEADER_SIZE: usize = HEADER_OVERHEAD;
+// Seconds since 2000-01-01 and code
+const ENTRY_SIZE: usize = 5;
+
+/// Event recorded in the [`EventLog`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Event {
+    /// Date/time when the event was recorded
+    pub datetime: DateTime,
+    /// User-defined event code
+    pub code: u8,
+}
+
+/// Ring buffer of timestamped events in the battery-backed SRAM.
+///
+/// Created with [`Mcp794xx::event_log()`].
+///
+/// The region starts with a 4-byte header holding the position of the next
+/// entry, the amount of entries and a CRC-16 (CCITT) of both. Each entry takes
+/// 5 bytes: the date/time as seconds since 2000-01-01 and the event code.
+/// The whole SRAM can hold 12 entries. When the log is full, the oldest
+/// entry is overwritten.
+///
+/// If the header is not valid (e.g. the SRAM contents were lost) the log
+/// is considered empty.
+#[derive(Debug)]
+pub struct EventLog<'a, DI, IC> {
+    rtc: &'a mut Mcp794xx<DI, IC>,
+    address: u8,
+    ring: Ring,
+}
+
+impl<DI, E, IC> Mcp794xx<DI, IC>
+where
+    DI: interface::WriteData<Error = Error<E>> + interface::ReadData<Error = Error<E>>,
+{
+    /// Use an event log in the SRAM region of `size` bytes starting at `address`.
+    ///
+    /// `Error::InvalidInputData` will be returned if the region is not
+    /// in the SRAM `[0x20-0x5F]` or cannot hold at least one entry.
+    pub fn event_log(
+        &mut self,
+        address: u8,
+        size: usize,
+    ) -> Result<EventLog<'_, DI, IC>, Error<E>> {
+        if !is_sram_data_range(address, size) || size < HEADER_SIZE + ENTRY_SIZE {
+            return Err(Error::InvalidInputData);
+        }
+        let capacity = ((size - HEADER_SIZE) / ENTRY_SIZE) as u8;
+        let mut header = [0; HEADER_SIZE];
+        self.read_sram_data(address, &mut header)?;
+        let ring = Ring::from_header(&header, capacity).unwrap_or(Ring::empty(capacity));
+        Ok(EventLog {
+            rtc: self,
+            address,
+            ring,
+        })
+    }
+}
+
+impl<'a, DI, E, IC> EventLog<'a, DI, IC>
+where
+    DI: interface::WriteData<Error = Error<E>> + interface::ReadData<Error = Error<E>>,
+{
+    /// Maximum amount of events before the oldest one is overwritten
+    pub fn capacity(&self) -> usize {
+        usize::from(self.ring.capacity)
+    }
+
+    /// Amount of events in the log
+    pub fn len(&self) -> usize {
+        usize::from(self.ring.len)
+    }
+
+    /// Whether the log has no events
+    pub fn is_empty(&self) -> bool {
+        self.ring.len == 0
+    }
+
+    /// Append an event with the current date/time read from the device.
+    pub fn append(&mut self, code: u8) -> Result<(), Error<E>> {
+        let datetime = self.rtc.read_datetime()?;
+        let seconds = to_seconds_since_2000(&datetime).to_le_bytes();
+        let entry = [seconds[0], seconds[1], seconds[2], seconds[3], code];
+        self.rtc
+            .write_sram_data(self.entry_address(self.ring.head), &entry)?;
+        self.write_header(self.ring.pushed())
+    }
+
+    /// Remove all events.
+    pub fn clear(&mut self) -> Result<(), Error<E>> {
+        self.write_header(Ring::empty(self.ring.capacity))
+    }
+
+    /// Iterate over the events, newest first.
+    pub fn iter(&mut self) -> EventLogIter<'_, 'a, DI, IC> {
+        EventLogIter {
+            log: self,
+            index: 0,
+        }
+    }
+
+    /// Read the event at `index`, where 0 is the most recent one.
+    ///
+    /// Returns `None` if there is no event at `index` or its entry is corrupt
+    /// (the date/time is after 2099).
+    pub fn get(&mut self, index: usize) -> Result<Option<Event>, Error<E>> {
+        let Some(position) = self.ring.position(index) else {
+            return Ok(None);
+        };
+        let mut entry = [0; ENTRY_SIZE];
+        self.rtc
+            .read_sram_data(self.entry_address(position), &mut entry)?;
+        let seconds = u32::from_le_bytes([entry[0], entry[1], entry[2], entry[3]]);
+        Ok(from_seconds_since_2000(seconds).map(|datetime| Event {
+            datetime,
+            code: entry[4],
+        }))
+    }
+
+    fn entry_address(&self, position: u8) -> u8 {
+        self.address + HEADER_SIZE as u8 + position * ENTRY_SIZE as u8
+    }
+
+    fn write_header(&mut self, ring: Ring) -> Result<(), Error<E>> {
+        let mut header = [0; HEADER_SIZE];
+        ring.write_header(&mut header);
+        self.rtc.write_sram_data(self.address, &header)?;
+        self.ring = ring;
+        Ok(())
+    }
+}
+
+/// Iterator over the entries of an [`EventLog`], newest first.
+///
+/// Created with [`EventLog::iter()`]. Corrupt entries are skipped.
+#[derive(Debug)]
+pub struct EventLogIter<'b, 'a, DI, IC> {
+    log: &'b mut EventLog<'a, DI, IC>,
+    index: usize,
+}
+
+impl<DI, E, IC> Iterator for EventLogIter<'_, '_, DI, IC>
+where
+    DI: interface::WriteData<Error = Error<E>> + interface::ReadData<Error = Error<E>>,
+{
+    type Item = Result<Event, Error<E>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let len = self.log.len();
+        next_entry(&mut self.index, len, |index| self.log.get(index))
+    }
+}
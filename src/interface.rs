@@ -1,6 +1,10 @@
 //! Communication interface
 use crate::{private, Error, DEVICE_ADDRESS, EEPROM_ADDRESS};
 use embedded_hal::i2c;
+use embedded_hal::spi;
+
+const EEUNLOCK: u8 = 0b0000_1001;
+const EEPROM_STATUS: u8 = 0xFF;
 
 /// I2C interface
 #[derive(Debug, Default)]
@@ -66,6 +70,11 @@ pub trait WriteData: private::Sealed {
     fn write_eeprom_byte(&mut self, address: u8, data: u8) -> Result<(), Self::Error>;
     /// Write data to EEPROM. The first element corresponds to the starting address.
     fn write_eeprom_data(&mut self, payload: &[u8]) -> Result<(), Self::Error>;
+    /// Write the EEPROM status register
+    fn write_eeprom_status(&mut self, data: u8) -> Result<(), Self::Error>;
+    /// Unlock and write data to protected EEPROM. The first element corresponds
+    /// to the starting address.
+    fn write_protected_eeprom_data(&mut self, payload: &[u8]) -> Result<(), Self::Error>;
 }
 
 impl<I2C, E> WriteData for I2cInterface<I2C>
@@ -89,6 +98,16 @@ where
     fn write_eeprom_data(&mut self, payload: &[u8]) -> Result<(), Self::Error> {
         self.write_data(EEPROM_ADDRESS, payload)
     }
+
+    fn write_eeprom_status(&mut self, data: u8) -> Result<(), Self::Error> {
+        self.write_data(EEPROM_ADDRESS, &[EEPROM_STATUS, data])
+    }
+
+    fn write_protected_eeprom_data(&mut self, payload: &[u8]) -> Result<(), Self::Error> {
+        self.write_data(DEVICE_ADDRESS, &[EEUNLOCK, 0x55])?;
+        self.write_data(DEVICE_ADDRESS, &[EEUNLOCK, 0xAA])?;
+        self.write_data(EEPROM_ADDRESS, payload)
+    }
 }
 
 /// Read data
@@ -103,6 +122,16 @@ pub trait ReadData: private::Sealed {
     fn read_eeprom_byte(&mut self, address: u8) -> Result<u8, Self::Error>;
     /// Read some data from EEPROM.
     fn read_eeprom_data(&mut self, address: u8, payload: &mut [u8]) -> Result<(), Self::Error>;
+    /// Read some data from protected EEPROM.
+    fn read_protected_eeprom_data(
+        &mut self,
+        address: u8,
+        payload: &mut [u8],
+    ) -> Result<(), Self::Error>;
+    /// Read the EEPROM status register
+    fn read_eeprom_status(&mut self) -> Result<u8, Self::Error>;
+    /// Returns whether an EEPROM write cycle is in progress.
+    fn is_eeprom_write_in_progress(&mut self) -> Result<bool, Self::Error>;
 }
 
 /// Read current data
@@ -118,6 +147,7 @@ pub trait ReadCurrent: private::Sealed {
 impl<I2C, E> ReadData for I2cInterface<I2C>
 where
     I2C: i2c::I2c<Error = E>,
+    E: i2c::Error,
 {
     type Error = Error<E>;
 
@@ -136,6 +166,28 @@ where
     fn read_eeprom_data(&mut self, address: u8, payload: &mut [u8]) -> Result<(), Self::Error> {
         self.read_data(EEPROM_ADDRESS, address, payload)
     }
+
+    fn read_protected_eeprom_data(
+        &mut self,
+        address: u8,
+        payload: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        self.read_data(EEPROM_ADDRESS, address, payload)
+    }
+
+    fn read_eeprom_status(&mut self) -> Result<u8, Self::Error> {
+        self.read_byte(EEPROM_ADDRESS, EEPROM_STATUS)
+    }
+
+    // Acknowledge polling: the device does not acknowledge its address
+    // while the write cycle is in progress.
+    fn is_eeprom_write_in_progress(&mut self) -> Result<bool, Self::Error> {
+        match self.i2c.write(EEPROM_ADDRESS, &[]) {
+            Ok(()) => Ok(false),
+            Err(e) if matches!(e.kind(), i2c::ErrorKind::NoAcknowledge(_)) => Ok(true),
+            Err(e) => Err(Error::Comm(e)),
+        }
+    }
 }
 
 impl<I2C, E> ReadCurrent for I2cInterface<I2C>
@@ -152,3 +204,426 @@ where
         self.read(EEPROM_ADDRESS)
     }
 }
+
+/// SPI interface for the MCP795xx devices
+///
+/// The register addresses used by the driver correspond to the MCP794xx
+/// register map and are translated to the MCP795xx register map here.
+#[derive(Debug, Default)]
+pub struct SpiInterface<SPI> {
+    pub(crate) spi: SPI,
+}
+
+struct Instruction;
+impl Instruction {
+    const EEREAD: u8 = 0x03;
+    const EEWRITE: u8 = 0x02;
+    const EEWREN: u8 = 0x06;
+    const SRREAD: u8 = 0x05;
+    const SRWRITE: u8 = 0x01;
+    const READ: u8 = 0x13;
+    const WRITE: u8 = 0x12;
+    const UNLOCK: u8 = 0x14;
+    const IDWRITE: u8 = 0x32;
+    const IDREAD: u8 = 0x33;
+}
+
+const HUNDREDTHS: u8 = 0x00;
+const WIP: u8 = 0b0000_0001;
+const PROTECTED_EEPROM_START: u8 = 0xF0;
+
+// Translates an MCP794xx RTCC address into the MCP795xx register map.
+// Returns the translated address (if the register exists) and how many
+// consecutive addresses starting at it are translated the same way.
+fn spi_rtcc_address(address: u8) -> (Option<u8>, usize) {
+    match address {
+        // SECONDS..OSCTRIM, shifted by the hundredths of seconds register
+        0x00..=0x08 => (Some(address + 1), usize::from(0x09 - address)),
+        // ALM0SEC..ALM0MTH
+        0x0A..=0x0F => (Some(address + 2), usize::from(0x10 - address)),
+        // ALM1SEC..ALM1DATE. Alarm 1 has no month register on the MCP795xx.
+        0x11..=0x15 => (Some(address + 2), usize::from(0x16 - address)),
+        // Power-fail time-stamps and SRAM
+        0x18..=0xFF => (Some(address), 0x100 - usize::from(address)),
+        _ => (None, 1),
+    }
+}
+
+impl<SPI, E> SpiInterface<SPI>
+where
+    SPI: spi::SpiDevice<u8, Error = E>,
+{
+    fn read_raw(
+        &mut self,
+        instruction: u8,
+        address: u8,
+        payload: &mut [u8],
+    ) -> Result<(), Error<E>> {
+        self.spi
+            .transaction(&mut [
+                spi::Operation::Write(&[instruction, address]),
+                spi::Operation::Read(payload),
+            ])
+            .map_err(Error::Comm)
+    }
+
+    fn write_raw(&mut self, instruction: u8, address: u8, payload: &[u8]) -> Result<(), Error<E>> {
+        self.spi
+            .transaction(&mut [
+                spi::Operation::Write(&[instruction, address]),
+                spi::Operation::Write(payload),
+            ])
+            .map_err(Error::Comm)
+    }
+
+    pub(crate) fn read_hundredths(&mut self) -> Result<u8, Error<E>> {
+        let mut data = [0];
+        self.read_raw(Instruction::READ, HUNDREDTHS, &mut data)
+            .and(Ok(data[0]))
+    }
+
+    pub(crate) fn write_hundredths(&mut self, data: u8) -> Result<(), Error<E>> {
+        self.write_raw(Instruction::WRITE, HUNDREDTHS, &[data])
+    }
+
+    fn enable_eeprom_write(&mut self) -> Result<(), Error<E>> {
+        self.spi.write(&[Instruction::EEWREN]).map_err(Error::Comm)
+    }
+}
+
+impl<SPI, E> WriteData for SpiInterface<SPI>
+where
+    SPI: spi::SpiDevice<u8, Error = E>,
+{
+    type Error = Error<E>;
+
+    fn write_register(&mut self, register: u8, data: u8) -> Result<(), Self::Error> {
+        self.write_data(&[register, data])
+    }
+
+    fn write_data(&mut self, payload: &[u8]) -> Result<(), Self::Error> {
+        let mut address = payload[0];
+        let mut data = &payload[1..];
+        while !data.is_empty() {
+            let (translated, count) = spi_rtcc_address(address);
+            let (chunk, rest) = data.split_at(count.min(data.len()));
+            if let Some(translated) = translated {
+                self.write_raw(Instruction::WRITE, translated, chunk)?;
+            }
+            address = address.wrapping_add(chunk.len() as u8);
+            data = rest;
+        }
+        Ok(())
+    }
+
+    fn write_eeprom_byte(&mut self, address: u8, data: u8) -> Result<(), Self::Error> {
+        self.write_eeprom_data(&[address, data])
+    }
+
+    fn write_eeprom_data(&mut self, payload: &[u8]) -> Result<(), Self::Error> {
+        self.enable_eeprom_write()?;
+        self.write_raw(Instruction::EEWRITE, payload[0], &payload[1..])
+    }
+
+    fn write_eeprom_status(&mut self, data: u8) -> Result<(), Self::Error> {
+        self.enable_eeprom_write()?;
+        self.spi
+            .write(&[Instruction::SRWRITE, data])
+            .map_err(Error::Comm)
+    }
+
+    fn write_protected_eeprom_data(&mut self, payload: &[u8]) -> Result<(), Self::Error> {
+        self.spi
+            .write(&[Instruction::UNLOCK, 0x55])
+            .map_err(Error::Comm)?;
+        self.spi
+            .write(&[Instruction::UNLOCK, 0xAA])
+            .map_err(Error::Comm)?;
+        self.enable_eeprom_write()?;
+        self.write_raw(
+            Instruction::IDWRITE,
+            payload[0] - PROTECTED_EEPROM_START,
+            &payload[1..],
+        )
+    }
+}
+
+impl<SPI, E> ReadData for SpiInterface<SPI>
+where
+    SPI: spi::SpiDevice<u8, Error = E>,
+{
+    type Error = Error<E>;
+
+    fn read_register(&mut self, register: u8) -> Result<u8, Self::Error> {
+        let mut data = [0];
+        self.read_data(register, &mut data).and(Ok(data[0]))
+    }
+
+    fn read_data(&mut self, address: u8, payload: &mut [u8]) -> Result<(), Self::Error> {
+        let mut address = address;
+        let mut data = payload;
+        while !data.is_empty() {
+            let (translated, count) = spi_rtcc_address(address);
+            let (chunk, rest) = data.split_at_mut(count.min(data.len()));
+            match translated {
+                Some(translated) => self.read_raw(Instruction::READ, translated, chunk)?,
+                None => chunk.fill(0),
+            }
+            address = address.wrapping_add(chunk.len() as u8);
+            data = rest;
+        }
+        Ok(())
+    }
+
+    fn read_eeprom_byte(&mut self, address: u8) -> Result<u8, Self::Error> {
+        let mut data = [0];
+        self.read_raw(Instruction::EEREAD, address, &mut data)
+            .and(Ok(data[0]))
+    }
+
+    fn read_eeprom_data(&mut self, address: u8, payload: &mut [u8]) -> Result<(), Self::Error> {
+        self.read_raw(Instruction::EEREAD, address, payload)
+    }
+
+    fn read_protected_eeprom_data(
+        &mut self,
+        address: u8,
+        payload: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        self.read_raw(
+            Instruction::IDREAD,
+            address - PROTECTED_EEPROM_START,
+            payload,
+        )
+    }
+
+    fn read_eeprom_status(&mut self) -> Result<u8, Self::Error> {
+        let mut data = [0];
+        self.spi
+            .transaction(&mut [
+                spi::Operation::Write(&[Instruction::SRREAD]),
+                spi::Operation::Read(&mut data),
+            ])
+            .map_err(Error::Comm)?;
+        Ok(data[0])
+    }
+
+    fn is_eeprom_write_in_progress(&mut self) -> Result<bool, Self::Error> {
+        Ok((self.read_eeprom_status()? & WIP) != 0)
+    }
+}
+
+/// Asynchronous I2C interface
+#[cfg(feature = "async")]
+#[derive(Debug, Default)]
+pub struct AsyncI2cInterface<I2C> {
+    pub(crate) i2c: I2C,
+}
+
+#[cfg(feature = "async")]
+impl<I2C, E> AsyncI2cInterface<I2C>
+where
+    I2C: embedded_hal_async::i2c::I2c<Error = E>,
+{
+    async fn read_byte(&mut self, device_address: u8, address: u8) -> Result<u8, Error<E>> {
+        let mut data = [0];
+        self.i2c
+            .write_read(device_address, &[address], &mut data)
+            .await
+            .map_err(Error::Comm)
+            .and(Ok(data[0]))
+    }
+
+    async fn read_data(
+        &mut self,
+        device_address: u8,
+        address: u8,
+        payload: &mut [u8],
+    ) -> Result<(), Error<E>> {
+        self.i2c
+            .write_read(device_address, &[address], payload)
+            .await
+            .map_err(Error::Comm)
+    }
+
+    async fn write_data(&mut self, device_address: u8, payload: &[u8]) -> Result<(), Error<E>> {
+        self.i2c
+            .write(device_address, payload)
+            .await
+            .map_err(Error::Comm)
+    }
+
+    async fn read(&mut self, device_address: u8) -> Result<u8, Error<E>> {
+        let mut data = [0];
+        self.i2c
+            .read(device_address, &mut data)
+            .await
+            .map_err(Error::Comm)
+            .and(Ok(data[0]))
+    }
+}
+
+/// Write data asynchronously
+#[cfg(feature = "async")]
+#[allow(async_fn_in_trait)]
+pub trait AsyncWriteData: private::Sealed {
+    /// Error type
+    type Error;
+    /// Write to an u8 register
+    async fn write_register(&mut self, register: u8, data: u8) -> Result<(), Self::Error>;
+    /// Write data. The first element corresponds to the starting address.
+    async fn write_data(&mut self, payload: &[u8]) -> Result<(), Self::Error>;
+    /// Write byte to EEPROM
+    async fn write_eeprom_byte(&mut self, address: u8, data: u8) -> Result<(), Self::Error>;
+    /// Write data to EEPROM. The first element corresponds to the starting address.
+    async fn write_eeprom_data(&mut self, payload: &[u8]) -> Result<(), Self::Error>;
+    /// Write the EEPROM status register
+    async fn write_eeprom_status(&mut self, data: u8) -> Result<(), Self::Error>;
+    /// Unlock and write data to protected EEPROM. The first element corresponds
+    /// to the starting address.
+    async fn write_protected_eeprom_data(&mut self, payload: &[u8]) -> Result<(), Self::Error>;
+}
+
+#[cfg(feature = "async")]
+impl<I2C, E> AsyncWriteData for AsyncI2cInterface<I2C>
+where
+    I2C: embedded_hal_async::i2c::I2c<Error = E>,
+{
+    type Error = Error<E>;
+
+    async fn write_register(&mut self, register: u8, data: u8) -> Result<(), Self::Error> {
+        self.write_data(DEVICE_ADDRESS, &[register, data]).await
+    }
+
+    async fn write_data(&mut self, payload: &[u8]) -> Result<(), Self::Error> {
+        self.write_data(DEVICE_ADDRESS, payload).await
+    }
+
+    async fn write_eeprom_byte(&mut self, address: u8, data: u8) -> Result<(), Self::Error> {
+        self.write_data(EEPROM_ADDRESS, &[address, data]).await
+    }
+
+    async fn write_eeprom_data(&mut self, payload: &[u8]) -> Result<(), Self::Error> {
+        self.write_data(EEPROM_ADDRESS, payload).await
+    }
+
+    async fn write_eeprom_status(&mut self, data: u8) -> Result<(), Self::Error> {
+        self.write_data(EEPROM_ADDRESS, &[EEPROM_STATUS, data])
+            .await
+    }
+
+    async fn write_protected_eeprom_data(&mut self, payload: &[u8]) -> Result<(), Self::Error> {
+        self.write_data(DEVICE_ADDRESS, &[EEUNLOCK, 0x55]).await?;
+        self.write_data(DEVICE_ADDRESS, &[EEUNLOCK, 0xAA]).await?;
+        self.write_data(EEPROM_ADDRESS, payload).await
+    }
+}
+
+/// Read data asynchronously
+#[cfg(feature = "async")]
+#[allow(async_fn_in_trait)]
+pub trait AsyncReadData: private::Sealed {
+    /// Error type
+    type Error;
+    /// Read an u8 register
+    async fn read_register(&mut self, register: u8) -> Result<u8, Self::Error>;
+    /// Read some data.
+    async fn read_data(&mut self, address: u8, payload: &mut [u8]) -> Result<(), Self::Error>;
+    /// Read byte from EEPROM
+    async fn read_eeprom_byte(&mut self, address: u8) -> Result<u8, Self::Error>;
+    /// Read some data from EEPROM.
+    async fn read_eeprom_data(
+        &mut self,
+        address: u8,
+        payload: &mut [u8],
+    ) -> Result<(), Self::Error>;
+    /// Read some data from protected EEPROM.
+    async fn read_protected_eeprom_data(
+        &mut self,
+        address: u8,
+        payload: &mut [u8],
+    ) -> Result<(), Self::Error>;
+    /// Read the EEPROM status register
+    async fn read_eeprom_status(&mut self) -> Result<u8, Self::Error>;
+    /// Returns whether an EEPROM write cycle is in progress.
+    async fn is_eeprom_write_in_progress(&mut self) -> Result<bool, Self::Error>;
+}
+
+/// Read current data asynchronously
+#[cfg(feature = "async")]
+#[allow(async_fn_in_trait)]
+pub trait AsyncReadCurrent: private::Sealed {
+    /// Error type
+    type Error;
+    /// Read current address
+    async fn read(&mut self) -> Result<u8, Self::Error>;
+    /// Read current address from EEPROM
+    async fn read_eeprom(&mut self) -> Result<u8, Self::Error>;
+}
+
+#[cfg(feature = "async")]
+impl<I2C, E> AsyncReadData for AsyncI2cInterface<I2C>
+where
+    I2C: embedded_hal_async::i2c::I2c<Error = E>,
+    E: i2c::Error,
+{
+    type Error = Error<E>;
+
+    async fn read_register(&mut self, register: u8) -> Result<u8, Self::Error> {
+        self.read_byte(DEVICE_ADDRESS, register).await
+    }
+
+    async fn read_data(&mut self, address: u8, payload: &mut [u8]) -> Result<(), Self::Error> {
+        self.read_data(DEVICE_ADDRESS, address, payload).await
+    }
+
+    async fn read_eeprom_byte(&mut self, register: u8) -> Result<u8, Self::Error> {
+        self.read_byte(EEPROM_ADDRESS, register).await
+    }
+
+    async fn read_eeprom_data(
+        &mut self,
+        address: u8,
+        payload: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        self.read_data(EEPROM_ADDRESS, address, payload).await
+    }
+
+    async fn read_protected_eeprom_data(
+        &mut self,
+        address: u8,
+        payload: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        self.read_data(EEPROM_ADDRESS, address, payload).await
+    }
+
+    async fn read_eeprom_status(&mut self) -> Result<u8, Self::Error> {
+        self.read_byte(EEPROM_ADDRESS, EEPROM_STATUS).await
+    }
+
+    // Acknowledge polling: the device does not acknowledge its address
+    // while the write cycle is in progress.
+    async fn is_eeprom_write_in_progress(&mut self) -> Result<bool, Self::Error> {
+        match self.i2c.write(EEPROM_ADDRESS, &[]).await {
+            Ok(()) => Ok(false),
+            Err(e) if matches!(e.kind(), i2c::ErrorKind::NoAcknowledge(_)) => Ok(true),
+            Err(e) => Err(Error::Comm(e)),
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl<I2C, E> AsyncReadCurrent for AsyncI2cInterface<I2C>
+where
+    I2C: embedded_hal_async::i2c::I2c<Error = E>,
+{
+    type Error = Error<E>;
+
+    async fn read(&mut self) -> Result<u8, Self::Error> {
+        self.read(DEVICE_ADDRESS).await
+    }
+
+    async fn read_eeprom(&mut self) -> Result<u8, Self::Error> {
+        self.read(EEPROM_ADDRESS).await
+    }
+}
@@ -0,0 +1,235 @@
+//! `embedded-io` traits implementation for the SRAM and EEPROM
+
+use crate::{interface, marker, Error, Mcp794xx};
+use embedded_io::{ErrorKind, ErrorType, Read, Seek, SeekFrom, Write};
+
+const SRAM_START: u8 = 0x20;
+const SRAM_SIZE: usize = 64;
+
+/// Seekable byte stream over the SRAM implementing the `embedded-io` traits.
+///
+/// Created with [`Mcp794xx::sram_cursor()`].
+///
+/// Position 0 corresponds to the SRAM address `0x20`. Reading at the end of
+/// the SRAM returns 0 bytes (EOF) and writing returns `IoError::EndOfRegion`.
+#[derive(Debug)]
+pub struct SramCursor<'a, DI, IC> {
+    rtc: &'a mut Mcp794xx<DI, IC>,
+    position: usize,
+}
+
+/// Seekable byte stream over the EEPROM implementing the `embedded-io` traits.
+///
+/// Created with [`Mcp794xx::eeprom_cursor()`].
+///
+/// Position 0 corresponds to the EEPROM address `0x00`. Reading at the end of
+/// the EEPROM returns 0 bytes (EOF) and writing returns `IoError::EndOfRegion`.
+#[derive(Debug)]
+pub struct EepromCursor<'a, DI, IC> {
+    rtc: &'a mut Mcp794xx<DI, IC>,
+    position: usize,
+}
+
+/// Errors of the [`SramCursor`] and [`EepromCursor`] byte streams
+#[derive(Debug)]
+pub enum IoError<E> {
+    /// Device error
+    Device(Error<E>),
+    /// The end of the SRAM or EEPROM was reached while writing
+    EndOfRegion,
+}
+
+impl<E> From<Error<E>> for IoError<E> {
+    fn from(error: Error<E>) -> Self {
+        IoError::Device(error)
+    }
+}
+
+impl<DI, E, IC> Mcp794xx<DI, IC>
+where
+    DI: interface::WriteData<Error = Error<E>> + interface::ReadData<Error = Error<E>>,
+{
+    /// Access the SRAM as a byte stream through the `embedded-io` traits.
+    pub fn sram_cursor(&mut self) -> SramCursor<'_, DI, IC> {
+        SramCursor {
+            rtc: self,
+            position: 0,
+        }
+    }
+}
+
+impl<DI, E, IC> Mcp794xx<DI, IC>
+where
+    DI: interface::WriteData<Error = Error<E>> + interface::ReadData<Error = Error<E>>,
+    IC: marker::WithEeprom,
+{
+    /// Access the EEPROM as a byte stream through the `embedded-io` traits.
+    pub fn eeprom_cursor(&mut self) -> EepromCursor<'_, DI, IC> {
+        EepromCursor {
+            rtc: self,
+            position: 0,
+        }
+    }
+}
+
+impl<E: core::fmt::Debug> embedded_io::Error for IoError<E> {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            IoError::Device(Error::InvalidInputData) => ErrorKind::InvalidInput,
+            IoError::Device(
+                Error::InvalidTimeData(..)
+                | Error::InvalidDateData(..)
+                | Error::InvalidAlarmData(_),
+            ) => ErrorKind::InvalidData,
+            IoError::Device(
+                Error::EepromWriteTimeout
+                | Error::OscillatorStopTimeout
+                | Error::OscillatorStartTimeout,
+            ) => ErrorKind::TimedOut,
+            IoError::Device(Error::Comm(_)) => ErrorKind::Other,
+            IoError::EndOfRegion => ErrorKind::WriteZero,
+        }
+    }
+}
+
+// Returns the new position within a region of the given size.
+fn seek_position<E>(position: usize, size: usize, pos: SeekFrom) -> Result<usize, Error<E>> {
+    let (base, offset) = match pos {
+        SeekFrom::Start(offset) => (0, i64::try_from(offset).unwrap_or(i64::MAX)),
+        SeekFrom::End(offset) => (size, offset),
+        SeekFrom::Current(offset) => (position, offset),
+    };
+    let position = (base as i64).checked_add(offset).unwrap_or(-1);
+    if position < 0 || position > size as i64 {
+        Err(Error::InvalidInputData)
+    } else {
+        Ok(position as usize)
+    }
+}
+
+// Amount of bytes to transfer from the position within a region of the given size.
+fn transfer_len(position: usize, size: usize, len: usize) -> usize {
+    len.min(size - position)
+}
+
+impl<DI, E, IC> ErrorType for SramCursor<'_, DI, IC>
+where
+    DI: interface::WriteData<Error = Error<E>> + interface::ReadData<Error = Error<E>>,
+    E: core::fmt::Debug,
+{
+    type Error = IoError<E>;
+}
+
+impl<DI, E, IC> Read for SramCursor<'_, DI, IC>
+where
+    DI: interface::WriteData<Error = Error<E>> + interface::ReadData<Error = Error<E>>,
+    E: core::fmt::Debug,
+{
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        let len = transfer_len(self.position, SRAM_SIZE, buf.len());
+        if len > 0 {
+            let address = SRAM_START + self.position as u8;
+            self.rtc.read_sram_data(address, &mut buf[..len])?;
+            self.position += len;
+        }
+        Ok(len)
+    }
+}
+
+impl<DI, E, IC> Write for SramCursor<'_, DI, IC>
+where
+    DI: interface::WriteData<Error = Error<E>> + interface::ReadData<Error = Error<E>>,
+    E: core::fmt::Debug,
+{
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        let len = transfer_len(self.position, SRAM_SIZE, buf.len());
+        if len == 0 {
+            return Err(IoError::EndOfRegion);
+        }
+        let address = SRAM_START + self.position as u8;
+        self.rtc.write_sram_data(address, &buf[..len])?;
+        self.position += len;
+        Ok(len)
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+impl<DI, E, IC> Seek for SramCursor<'_, DI, IC>
+where
+    DI: interface::WriteData<Error = Error<E>> + interface::ReadData<Error = Error<E>>,
+    E: core::fmt::Debug,
+{
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64, Self::Error> {
+        self.position = seek_position(self.position, SRAM_SIZE, pos)?;
+        Ok(self.position as u64)
+    }
+}
+
+impl<DI, E, IC> ErrorType for EepromCursor<'_, DI, IC>
+where
+    DI: interface::WriteData<Error = Error<E>> + interface::ReadData<Error = Error<E>>,
+    IC: marker::WithEeprom,
+    E: core::fmt::Debug,
+{
+    type Error = IoError<E>;
+}
+
+impl<DI, E, IC> Read for EepromCursor<'_, DI, IC>
+where
+    DI: interface::WriteData<Error = Error<E>> + interface::ReadData<Error = Error<E>>,
+    IC: marker::WithEeprom,
+    E: core::fmt::Debug,
+{
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        let len = transfer_len(self.position, IC::EEPROM_SIZE, buf.len());
+        if len > 0 {
+            self.rtc
+                .read_eeprom_data(self.position as u8, &mut buf[..len])?;
+            self.position += len;
+        }
+        Ok(len)
+    }
+}
+
+impl<DI, E, IC> Write for EepromCursor<'_, DI, IC>
+where
+    DI: interface::WriteData<Error = Error<E>> + interface::ReadData<Error = Error<E>>,
+    IC: marker::WithEeprom,
+    E: core::fmt::Debug,
+{
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        let len = transfer_len(self.position, IC::EEPROM_SIZE, buf.len());
+        if len == 0 {
+            return Err(IoError::EndOfRegion);
+        }
+        self.rtc
+            .write_eeprom_data(self.position as u8, &buf[..len])?;
+        self.position += len;
+        Ok(len)
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+impl<DI, E, IC> Seek for EepromCursor<'_, DI, IC>
+where
+    DI: interface::WriteData<Error = Error<E>> + interface::ReadData<Error = Error<E>>,
+    IC: marker::WithEeprom,
+    E: core::fmt::Debug,
+{
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64, Self::Error> {
+        self.position = seek_position(self.position, IC::EEPROM_SIZE, pos)?;
+        Ok(self.position as u64)
+    }
+}
@@ -5,10 +5,17 @@
 //!
 //! This driver allows you to:
 //! - Read and set date and time. See: [`datetime()`].
+//! - Read and set date and time without `chrono` using the [`DateTime`] type. See: [`read_datetime()`].
+//! - Set date and time while the oscillator is stopped. See: [`set_datetime_safely()`].
+//! - Read date and time consistently across rollovers. See: [`consistent_datetime()`].
+//! - Read and set date and time as a Unix timestamp. See: [`timestamp()`].
 //! - Read and set date. See: [`date()`].
 //! - Read and set time. See: [`time()`].
 //! - Read and set date and time individual elements. For example, see: [`year()`].
 //! - Enable and disable the real-time clock. See: [`enable()`].
+//! - Synchronize the driver state with an already running device. See: [`sync()`].
+//! - Read the complete device status at once. See: [`status()`].
+//! - Dump and decode the device registers for diagnostics. See: [`dump_registers()`].
 //! - Read whether the oscillator is running. See: [`is_oscillator_running()`].
 //! - Read whether the current year is a leap year. See: [`is_leap_year()`].
 //! - Enable and disable the usage of an external oscillator source. See: [`enable_external_oscillator()`].
@@ -20,16 +27,27 @@
 //!     - Clear the has-power-failed flag. See: [`clear_power_failed()`].
 //!     - Read the date/time when power went down. See: [`get_power_down_datetime()`].
 //!     - Read the date/time when power went back up. See: [`get_power_up_datetime()`].
+//!     - Read the full date/time and duration of a power outage. See: [`get_power_outage()`].
+//!     - Keep a history of power outages with statistics. See: [`outage_history()`].
 //!     - Enable and disable usage of backup battery power. See: [`enable_backup_battery_power()`].
 //! - SRAM:
 //!     - Read and write byte to SRAM. See: [`read_sram_byte()`].
 //!     - Read and write byte array to SRAM. See: [`read_sram_data()`].
 //!     - Read current position from SRAM. See: [`read_sram_current_byte()`].
+//!     - Use the SRAM as a byte stream with the `embedded-io` feature. See: [`sram_cursor()`].
+//!     - Record timestamped events in a ring buffer. See: [`event_log()`].
+//!     - Count boots and accumulate the uptime. See: [`init_boot_counter()`].
 //! - Alarms:
 //!     - Enable and disable alarms. See: [`enable_alarm()`].
 //!     - Set alarms with several matching policies and output pin polarities. See: [`set_alarm()`].
+//!     - Set alarms from a `NaiveDateTime` or a `Weekday`. See: [`set_alarm_at()`] and [`set_weekly_alarm()`].
+//!     - Set alarms to match once after a duration. See: [`set_alarm_in()`].
+//!     - Run a recurring schedule on both alarms. See: [`scheduler()`].
+//!     - Set alarms from cron expressions. See: [`Cron`] and [`set_cron_alarm()`].
+//!     - Read the configuration of alarms. See: [`get_alarm()`].
 //!     - Read whether alarms have matched. See: [`has_alarm_matched()`].
 //!     - Clear flag indicating that alarms have matched. See: [`clear_alarm_matched_flag()`].
+//!     - Compute the next time an alarm configuration will match. See: [`AlarmDateTime::next_match()`].
 //! - Wave generation:
 //!     - Enable and disable the square-wave generation. See: [`enable_square_wave()`].
 //!     - Select the square-wave frequency. See: [`set_square_wave_frequency()`].
@@ -41,14 +59,29 @@
 //! - EEPROM:
 //!     - Read and write byte to the EEPROM. See: [`read_eeprom_byte()`].
 //!     - Read and write byte array to the EEPROM. See: [`read_eeprom_data()`].
+//!     - Wait for EEPROM write cycles to complete. See: [`set_eeprom_write_poll_attempts()`].
 //!     - Set EEPROM block write protection. See: [`set_eeprom_write_protection()`].
 //!     - Read current position from the EEPROM. See: [`read_eeprom_current_byte()`].
+//!     - Use the EEPROM through the `embedded-storage` traits with the `embedded-storage` feature. See: [`eeprom_storage()`].
+//!     - Use the EEPROM as a byte stream with the `embedded-io` feature. See: [`eeprom_cursor()`].
+//!     - Store versioned settings protected against power loss. See: [`settings_store()`].
+//! - Read and set the hundredths of second on the MCP795xx devices. See: [`hundredths()`].
+//! - Use all of the above asynchronously with the `async` feature. See: [`Mcp794xxAsync`].
+//! - Run the driver against a simulated device with the `sim` feature. See: [`sim`].
+//! - Build without `chrono` by disabling the default `rtcc` feature. See: [`DateTime`].
+//! - Convert [`DateTime`] from and into `time` or `jiff` types with the `time` and `jiff` features.
 //!
 //! [`datetime()`]: struct.Mcp794xx.html#method.datetime
+//! [`set_datetime_safely()`]: struct.Mcp794xx.html#method.set_datetime_safely
+//! [`consistent_datetime()`]: struct.Mcp794xx.html#method.consistent_datetime
+//! [`timestamp()`]: struct.Mcp794xx.html#method.timestamp
 //! [`date()`]: struct.Mcp794xx.html#method.date
 //! [`time()`]: struct.Mcp794xx.html#method.time
 //! [`year()`]: struct.Mcp794xx.html#method.year
 //! [`enable()`]: struct.Mcp794xx.html#method.enable
+//! [`sync()`]: struct.Mcp794xx.html#method.sync
+//! [`status()`]: struct.Mcp794xx.html#method.status
+//! [`dump_registers()`]: struct.Mcp794xx.html#method.dump_registers
 //! [`is_oscillator_running()`]: struct.Mcp794xx.html#method.is_oscillator_running
 //! [`is_leap_year()`]: struct.Mcp794xx.html#method.is_leap_year
 //! [`enable_external_oscillator()`]: struct.Mcp794xx.html#method.enable_external_oscillator
@@ -59,14 +92,26 @@
 //! [`clear_power_failed()`]: struct.Mcp794xx.html#method.clear_power_failed
 //! [`get_power_down_datetime()`]: struct.Mcp794xx.html#method.get_power_down_datetime
 //! [`get_power_up_datetime()`]: struct.Mcp794xx.html#method.get_power_up_datetime
+//! [`get_power_outage()`]: struct.Mcp794xx.html#method.get_power_outage
+//! [`outage_history()`]: struct.Mcp794xx.html#method.outage_history
 //! [`enable_backup_battery_power()`]: struct.Mcp794xx.html#method.enable_backup_battery_power
 //! [`read_sram_byte()`]: struct.Mcp794xx.html#method.read_sram_byte
 //! [`read_sram_data()`]: struct.Mcp794xx.html#method.read_sram_data
 //! [`read_sram_current_byte()`]: struct.Mcp794xx.html#method.read_sram_current_byte
+//! [`sram_cursor()`]: struct.Mcp794xx.html#method.sram_cursor
+//! [`event_log()`]: struct.Mcp794xx.html#method.event_log
+//! [`init_boot_counter()`]: struct.Mcp794xx.html#method.init_boot_counter
 //! [`enable_alarm()`]: struct.Mcp794xx.html#method.enable_alarm
 //! [`set_alarm()`]: struct.Mcp794xx.html#method.set_alarm
+//! [`set_alarm_at()`]: struct.Mcp794xx.html#method.set_alarm_at
+//! [`set_weekly_alarm()`]: struct.Mcp794xx.html#method.set_weekly_alarm
+//! [`set_alarm_in()`]: struct.Mcp794xx.html#method.set_alarm_in
+//! [`scheduler()`]: struct.Mcp794xx.html#method.scheduler
+//! [`set_cron_alarm()`]: struct.Mcp794xx.html#method.set_cron_alarm
+//! [`get_alarm()`]: struct.Mcp794xx.html#method.get_alarm
 //! [`has_alarm_matched()`]: struct.Mcp794xx.html#method.has_alarm_matched
 //! [`clear_alarm_matched_flag()`]: struct.Mcp794xx.html#method.clear_alarm_matched_flag
+//! [`AlarmDateTime::next_match()`]: struct.AlarmDateTime.html#method.next_match
 //! [`enable_square_wave()`]: struct.Mcp794xx.html#method.enable_square_wave
 //! [`set_square_wave_frequency()`]: struct.Mcp794xx.html#method.set_square_wave_frequency
 //! [`read_protected_eeprom_byte()`]: struct.Mcp794xx.html#method.read_protected_eeprom_byte
@@ -75,15 +120,24 @@
 //! [`read_eui64()`]: struct.Mcp794xx.html#method.read_eui64
 //! [`read_eeprom_byte()`]: struct.Mcp794xx.html#method.read_eeprom_byte
 //! [`read_eeprom_data()`]: struct.Mcp794xx.html#method.read_eeprom_data
+//! [`set_eeprom_write_poll_attempts()`]: struct.Mcp794xx.html#method.set_eeprom_write_poll_attempts
 //! [`set_eeprom_write_protection()`]: struct.Mcp794xx.html#method.set_eeprom_write_protection
 //! [`read_eeprom_current_byte()`]: struct.Mcp794xx.html#method.read_eeprom_current_byte
+//! [`eeprom_storage()`]: struct.Mcp794xx.html#method.eeprom_storage
+//! [`eeprom_cursor()`]: struct.Mcp794xx.html#method.eeprom_cursor
+//! [`settings_store()`]: struct.Mcp794xx.html#method.settings_store
+//! [`Mcp794xxAsync`]: struct.Mcp794xxAsync.html
+//! [`sim`]: sim/index.html
+//! [`read_datetime()`]: struct.Mcp794xx.html#method.read_datetime
+//! [`hundredths()`]: struct.Mcp794xx.html#method.hundredths
 //!
 //! [Introductory blog post](https://blog.eldruin.com/mcp794xx-real-time-clock-rtc-driver-in-rust/)
 //!
 //! ## The devices
 //!
 //! This driver is compatible with the devices: MCP7940N, MCP7940M, MCP79400,
-//! MCP79401, MCP79402, MCP79410, MCP79411 and MCP79412.
+//! MCP79401, MCP79402, MCP79410, MCP79411 and MCP79412 over I2C and with
+//! the devices MCP79510, MCP79511, MCP79520 and MCP79521 over SPI.
 //!
 //! The Real-Time Clock/Calendar (RTCC) tracks time using internal counters for
 //! hours, minutes, seconds, days, months, years, and day of week. Alarms can
@@ -118,6 +172,13 @@
 //! - [MCP79400/MCP79401/MCP79402](http://ww1.microchip.com/downloads/en/DeviceDoc/MCP79400-MCP79401-MCP79402-Data-Sheet-20005009G.pdf)
 //! - [MCP79410/MCP79411/MCP79412](http://ww1.microchip.com/downloads/en/DeviceDoc/20002266H.pdf)
 //!
+//! The MCP795xx devices share the register map of the MCP794xx devices
+//! with some differences. They additionally offer a hundredths of seconds
+//! register. See: [`hundredths()`]. On the other hand, the alarm 1 does
+//! not feature a month register so the month is ignored when setting it
+//! and the `AllMatch` trigger rate is not available for it.
+//! The MCP79520 and MCP79521 feature 2 Kbit of EEPROM.
+//!
 //! ## Usage examples (see also examples folder)
 //!
 //! To use this driver, import this crate and an `embedded_hal` implementation,
@@ -143,9 +204,38 @@
 //! let dev = rtc.destroy();
 //! ```
 //!
+//! ### Continue using a device that was already running
+//!
+//! ```no_run
+//! use linux_embedded_hal::I2cdev;
+//! use mcp794xx::Mcp794xx;
+//!
+//! let dev = I2cdev::new("/dev/i2c-1").unwrap();
+//! let mut rtc = Mcp794xx::new_mcp7940n(dev);
+//! // read the oscillator, 12h/24h mode, battery and CONTROL settings
+//! rtc.sync().unwrap();
+//! rtc.enable_square_wave().unwrap();
+//! ```
+//!
+//! ### Create a driver instance for the MCP79510
+//!
+//! ```no_run
+//! use linux_embedded_hal::SpidevDevice;
+//! use mcp794xx::Mcp794xx;
+//!
+//! let dev = SpidevDevice::open("/dev/spidev0.0").unwrap();
+//! let rtc = Mcp794xx::new_mcp79510(dev);
+//! // do something...
+//!
+//! // get the SPI device back
+//! let dev = rtc.destroy();
+//! ```
+//!
 //! ### Set the current date and time at once
 //!
 //! ```no_run
+//! # #[cfg(feature = "rtcc")]
+//! # {
 //! use linux_embedded_hal::I2cdev;
 //! use mcp794xx::{Mcp794xx, NaiveDate, Hours, DateTimeAccess};
 //!
@@ -154,6 +244,7 @@
 //! let datetime = NaiveDate::from_ymd(2018, 8, 20).and_hms(19, 59, 58);
 //! rtc.set_datetime(&datetime).unwrap();
 //! rtc.enable().unwrap();
+//! # }
 //! ```
 //!
 //! ### Change the date and time at once
@@ -162,6 +253,8 @@
 //! and you must be wait unter the oscillator reports not to be running anymore.
 //!
 //! ```no_run
+//! # #[cfg(feature = "rtcc")]
+//! # {
 //! use linux_embedded_hal::I2cdev;
 //! use mcp794xx::{Mcp794xx, NaiveDate, Hours, DateTimeAccess};
 //!
@@ -180,11 +273,14 @@
 //! // now you can change the date/time
 //! rtc.set_datetime(&datetime).unwrap();
 //! rtc.enable().unwrap();
+//! # }
 //! ```
 //!
 //! ### Get the current date and time at once
 //!
 //! ```no_run
+//! # #[cfg(feature = "rtcc")]
+//! # {
 //! use linux_embedded_hal::I2cdev;
 //! use mcp794xx::{Mcp794xx, DateTimeAccess, Datelike, Timelike};
 //!
@@ -196,11 +292,14 @@
 //!          dt.month(), dt.day(), dt.weekday().number_from_sunday(),
 //!          dt.hour(), dt.minute(), dt.second());
 //! // This will print something like: 2018-08-15, 4 19:59:58
+//! # }
 //! ```
 //!
 //! ### Set / Get the year
 //!
 //! ```no_run
+//! # #[cfg(feature = "rtcc")]
+//! # {
 //! use linux_embedded_hal::I2cdev;
 //! use mcp794xx::{ Mcp794xx, Hours, Rtcc };
 //!
@@ -209,6 +308,7 @@
 //! rtc.set_year(2019).unwrap();
 //! let year = rtc.year().unwrap();
 //! println!("Year: {}", year);
+//! # }
 //! ```
 //! Similar methods exist for month, day, weekday, hours, minutes and seconds.
 //!
@@ -330,12 +430,29 @@
 //! let value = rtc.read_eui64().unwrap();
 //! ```
 //!
+//! ### Asynchronous usage
+//!
+//! Enable the `async` feature and create an [`Mcp794xxAsync`] instance from
+//! an I2C bus implementing the `embedded-hal-async` traits. The methods are
+//! the same as in the blocking driver but must be awaited.
+//!
+//! ```ignore
+//! use mcp794xx::Mcp794xxAsync;
+//!
+//! let mut rtc = Mcp794xxAsync::new_mcp7940n(i2c);
+//! rtc.enable().await.unwrap();
+//! let datetime = rtc.datetime().await.unwrap();
+//! ```
+//!
 
 #![deny(unsafe_code, missing_docs)]
 #![no_std]
 
+#[cfg(feature = "rtcc")]
+pub use chrono::Weekday;
 use core::marker::PhantomData;
-use embedded_hal::i2c;
+use embedded_hal::{i2c, spi};
+#[cfg(feature = "rtcc")]
 pub use rtcc::{
     DateTimeAccess, Datelike, Hours, NaiveDate, NaiveDateTime, NaiveTime, Rtcc, Timelike,
 };
@@ -348,11 +465,19 @@ pub mod marker {
     /// Supports protected EEPROM
     pub trait WithProtectedEeprom: private::Sealed {}
     /// Supports EEPROM and protected EEPROM
-    pub trait WithEeprom: private::Sealed {}
+    pub trait WithEeprom: private::Sealed {
+        /// EEPROM size in bytes
+        const EEPROM_SIZE: usize;
+    }
     /// Contains EUI-48
     pub trait WithEui48: private::Sealed {}
     /// Contains EUI-64
     pub trait WithEui64: private::Sealed {}
+    /// Supports alarms
+    pub trait WithAlarms: private::Sealed {
+        /// Whether the alarm 1 has a month register
+        const HAS_ALARM1_MONTH: bool;
+    }
 }
 
 /// IC markers
@@ -373,6 +498,14 @@ pub mod ic {
     pub struct Mcp79411(());
     /// MCP79412 IC marker
     pub struct Mcp79412(());
+    /// MCP79510 IC marker
+    pub struct Mcp79510(());
+    /// MCP79511 IC marker
+    pub struct Mcp79511(());
+    /// MCP79520 IC marker
+    pub struct Mcp79520(());
+    /// MCP79521 IC marker
+    pub struct Mcp79521(());
 }
 
 impl marker::WithBatteryPower for ic::Mcp7940n {}
@@ -382,27 +515,98 @@ impl marker::WithBatteryPower for ic::Mcp79402 {}
 impl marker::WithBatteryPower for ic::Mcp79410 {}
 impl marker::WithBatteryPower for ic::Mcp79411 {}
 impl marker::WithBatteryPower for ic::Mcp79412 {}
+impl marker::WithBatteryPower for ic::Mcp79510 {}
+impl marker::WithBatteryPower for ic::Mcp79511 {}
+impl marker::WithBatteryPower for ic::Mcp79520 {}
+impl marker::WithBatteryPower for ic::Mcp79521 {}
 impl marker::WithProtectedEeprom for ic::Mcp79400 {}
 impl marker::WithProtectedEeprom for ic::Mcp79401 {}
 impl marker::WithProtectedEeprom for ic::Mcp79402 {}
 impl marker::WithProtectedEeprom for ic::Mcp79410 {}
 impl marker::WithProtectedEeprom for ic::Mcp79411 {}
 impl marker::WithProtectedEeprom for ic::Mcp79412 {}
+impl marker::WithProtectedEeprom for ic::Mcp79510 {}
+impl marker::WithProtectedEeprom for ic::Mcp79511 {}
+impl marker::WithProtectedEeprom for ic::Mcp79520 {}
+impl marker::WithProtectedEeprom for ic::Mcp79521 {}
 impl marker::WithEui48 for ic::Mcp79401 {}
 impl marker::WithEui48 for ic::Mcp79411 {}
+impl marker::WithEui48 for ic::Mcp79511 {}
+impl marker::WithEui48 for ic::Mcp79521 {}
 impl marker::WithEui64 for ic::Mcp79402 {}
 impl marker::WithEui64 for ic::Mcp79412 {}
-impl marker::WithEeprom for ic::Mcp79410 {}
-impl marker::WithEeprom for ic::Mcp79411 {}
-impl marker::WithEeprom for ic::Mcp79412 {}
+impl marker::WithEeprom for ic::Mcp79410 {
+    const EEPROM_SIZE: usize = 128;
+}
+impl marker::WithEeprom for ic::Mcp79411 {
+    const EEPROM_SIZE: usize = 128;
+}
+impl marker::WithEeprom for ic::Mcp79412 {
+    const EEPROM_SIZE: usize = 128;
+}
+impl marker::WithEeprom for ic::Mcp79510 {
+    const EEPROM_SIZE: usize = 128;
+}
+impl marker::WithEeprom for ic::Mcp79511 {
+    const EEPROM_SIZE: usize = 128;
+}
+impl marker::WithEeprom for ic::Mcp79520 {
+    const EEPROM_SIZE: usize = 256;
+}
+impl marker::WithEeprom for ic::Mcp79521 {
+    const EEPROM_SIZE: usize = 256;
+}
+impl marker::WithAlarms for ic::Mcp7940n {
+    const HAS_ALARM1_MONTH: bool = true;
+}
+impl marker::WithAlarms for ic::Mcp7940m {
+    const HAS_ALARM1_MONTH: bool = true;
+}
+impl marker::WithAlarms for ic::Mcp79400 {
+    const HAS_ALARM1_MONTH: bool = true;
+}
+impl marker::WithAlarms for ic::Mcp79401 {
+    const HAS_ALARM1_MONTH: bool = true;
+}
+impl marker::WithAlarms for ic::Mcp79402 {
+    const HAS_ALARM1_MONTH: bool = true;
+}
+impl marker::WithAlarms for ic::Mcp79410 {
+    const HAS_ALARM1_MONTH: bool = true;
+}
+impl marker::WithAlarms for ic::Mcp79411 {
+    const HAS_ALARM1_MONTH: bool = true;
+}
+impl marker::WithAlarms for ic::Mcp79412 {
+    const HAS_ALARM1_MONTH: bool = true;
+}
+impl marker::WithAlarms for ic::Mcp79510 {
+    const HAS_ALARM1_MONTH: bool = false;
+}
+impl marker::WithAlarms for ic::Mcp79511 {
+    const HAS_ALARM1_MONTH: bool = false;
+}
+impl marker::WithAlarms for ic::Mcp79520 {
+    const HAS_ALARM1_MONTH: bool = false;
+}
+impl marker::WithAlarms for ic::Mcp79521 {
+    const HAS_ALARM1_MONTH: bool = false;
+}
 
+mod calendar;
+pub use crate::calendar::{DateTime, OutOfRange};
 mod types;
+#[cfg(not(feature = "rtcc"))]
+pub use crate::types::Hours;
 pub use crate::types::{
-    Alarm, AlarmDateTime, AlarmMatching, AlarmOutputPinPolarity, EepromWriteProtection, Error,
-    OutputPinLevel, PowerFailDateTime, SqWFreq,
+    Alarm, AlarmConfig, AlarmDateTime, AlarmMatching, AlarmOutputPinPolarity,
+    EepromWriteProtection, Error, OutputPinLevel, PowerFailDateTime, PowerOutage, SqWFreq, Status,
 };
+mod alarm_schedule;
 
 const DEVICE_ADDRESS: u8 = 0b110_1111;
+pub(crate) const DEFAULT_EEPROM_POLL_ATTEMPTS: u32 = 1000;
+pub(crate) const DEFAULT_OSCILLATOR_POLL_ATTEMPTS: u32 = 1000;
 const EEPROM_ADDRESS: u8 = 0b101_0111;
 
 struct Register;
@@ -452,6 +656,8 @@ pub struct Mcp794xx<DI, IC> {
     is_running_in_24h_mode: bool,
     control: Config,
     alarm_output_pin_polarity: AlarmOutputPinPolarity,
+    eeprom_poll_attempts: u32,
+    oscillator_poll_attempts: u32,
     _ic: PhantomData<IC>,
 }
 
@@ -461,10 +667,38 @@ pub(crate) struct Config {
 }
 
 pub mod interface;
-use crate::interface::I2cInterface;
+use crate::interface::{I2cInterface, SpiInterface};
+#[cfg(feature = "async")]
+mod asynch;
+#[cfg(feature = "async")]
+pub use crate::asynch::Mcp794xxAsync;
 mod battery_power;
 mod common;
 mod eeprom;
+mod register_dump;
+pub use crate::register_dump::RegisterDump;
+mod boot_counter;
+pub use crate::boot_counter::{BootCounter, BOOT_COUNTER_SIZE};
+mod event_log;
+pub use crate::event_log::{Event, EventLog, EventLogIter};
+mod outage_history;
+pub use crate::outage_history::{OutageHistory, OutageHistoryIter, OutageStats};
+mod settings;
+pub use crate::settings::{Settings, SettingsError, SettingsStore};
+mod cron;
+pub use crate::cron::{Cron, CronError, CronField};
+mod scheduler;
+pub use crate::scheduler::{DueRules, Recurrence, Scheduler, SCHEDULER_SIZE};
+#[cfg(feature = "embedded-io")]
+mod io;
+#[cfg(feature = "embedded-io")]
+pub use crate::io::{EepromCursor, IoError, SramCursor};
+#[cfg(feature = "embedded-storage")]
+mod storage;
+#[cfg(feature = "embedded-storage")]
+pub use crate::storage::{EepromStorage, StorageError};
+#[cfg(feature = "sim")]
+pub mod sim;
 
 macro_rules! create_destroy_i2c {
     ($ic:ident, $create:ident) => {
@@ -483,6 +717,8 @@ macro_rules! create_destroy_i2c {
                         bits: BitFlags::OUT,
                     },
                     alarm_output_pin_polarity: AlarmOutputPinPolarity::Low,
+                    eeprom_poll_attempts: DEFAULT_EEPROM_POLL_ATTEMPTS,
+                    oscillator_poll_attempts: DEFAULT_OSCILLATOR_POLL_ATTEMPTS,
                     _ic: PhantomData,
                 }
             }
@@ -503,11 +739,49 @@ create_destroy_i2c!(Mcp79410, new_mcp79410);
 create_destroy_i2c!(Mcp79411, new_mcp79411);
 create_destroy_i2c!(Mcp79412, new_mcp79412);
 
+macro_rules! create_destroy_spi {
+    ($ic:ident, $create:ident) => {
+        impl<SPI, E> Mcp794xx<SpiInterface<SPI>, ic::$ic>
+        where
+            SPI: spi::SpiDevice<u8, Error = E>,
+        {
+            /// Create a new instance of the device.
+            pub fn $create(spi: SPI) -> Self {
+                Mcp794xx {
+                    iface: SpiInterface { spi },
+                    is_enabled: false,
+                    is_battery_power_enabled: false,
+                    is_running_in_24h_mode: false,
+                    control: Config {
+                        bits: BitFlags::OUT,
+                    },
+                    alarm_output_pin_polarity: AlarmOutputPinPolarity::Low,
+                    eeprom_poll_attempts: DEFAULT_EEPROM_POLL_ATTEMPTS,
+                    oscillator_poll_attempts: DEFAULT_OSCILLATOR_POLL_ATTEMPTS,
+                    _ic: PhantomData,
+                }
+            }
+
+            /// Destroy driver instance, return SPI device instance.
+            pub fn destroy(self) -> SPI {
+                self.iface.spi
+            }
+        }
+    };
+}
+create_destroy_spi!(Mcp79510, new_mcp79510);
+create_destroy_spi!(Mcp79511, new_mcp79511);
+create_destroy_spi!(Mcp79520, new_mcp79520);
+create_destroy_spi!(Mcp79521, new_mcp79521);
+
 mod private {
     use super::{ic, interface};
     pub trait Sealed {}
 
     impl<E> Sealed for interface::I2cInterface<E> {}
+    impl<E> Sealed for interface::SpiInterface<E> {}
+    #[cfg(feature = "async")]
+    impl<E> Sealed for interface::AsyncI2cInterface<E> {}
     impl Sealed for ic::Mcp7940n {}
     impl Sealed for ic::Mcp7940m {}
     impl Sealed for ic::Mcp79400 {}
@@ -516,4 +790,8 @@ mod private {
     impl Sealed for ic::Mcp79410 {}
     impl Sealed for ic::Mcp79411 {}
     impl Sealed for ic::Mcp79412 {}
+    impl Sealed for ic::Mcp79510 {}
+    impl Sealed for ic::Mcp79511 {}
+    impl Sealed for ic::Mcp79520 {}
+    impl Sealed for ic::Mcp79521 {}
 }
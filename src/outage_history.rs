@@ -0,0 +1,279 @@
+//! Power outage history in the battery-backed SRAM or the EEPROM
+
+use crate::common::datetime::{from_seconds_since_2000, to_seconds_since_2000};
+use crate::common::ring::{next_entry, Ring, HEADER_OVERHEAD};
+use crate::common::sram::is_sram_data_range;
+use crate::eeprom::is_eeprom_data_range;
+use crate::{interface, marker, Error, Mcp794xx, PowerOutage};
+use core::time::Duration;
+
+// Head, length, outage count, total and longest outage seconds and CRC-16
+const HEADER_SIZE: usize = HEADER_OVERHEAD + 12;
+// Power-down and power-up as seconds since 2000-01-01
+const ENTRY_SIZE: usize = 8;
+
+/// Aggregate statistics of the outages recorded in an [`OutageHistory`].
+///
+/// These include the outages that have already been overwritten.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct OutageStats {
+    /// Amount of outages recorded
+    pub count: u32,
+    /// Total time without primary power
+    pub total: Duration,
+    /// Longest time without primary power
+    pub longest: Duration,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Memory {
+    Sram,
+    Eeprom,
+}
+
+/// History of power outages together with aggregate statistics.
+///
+/// Created with [`Mcp794xx::outage_history()`] in the battery-backed SRAM or
+/// with [`Mcp794xx::eeprom_outage_history()`] in the EEPROM, which keeps the
+/// history even if the backup battery is depleted.
+///
+/// The region starts with a 16-byte header holding the position of the next
+/// entry, the amount of entries, the statistics and a CRC-16 (CCITT). Each
+/// entry takes 8 bytes: the power-down and power-up date/times as seconds
+/// since 2000-01-01. When the history is full, the oldest entry is overwritten.
+///
+/// A history whose header does not match its CRC or the region size starts
+/// empty, with its statistics reset.
+#[derive(Debug)]
+pub struct OutageHistory<'a, DI, IC> {
+    rtc: &'a mut Mcp794xx<DI, IC>,
+    memory: Memory,
+    address: u8,
+    ring: Ring,
+    stats: OutageStats,
+}
+
+impl<DI, E, IC> Mcp794xx<DI, IC>
+where
+    DI: interface::WriteData<Error = Error<E>> + interface::ReadData<Error = Error<E>>,
+    IC: marker::WithBatteryPower,
+{
+    /// Use a power outage history in the SRAM region of `size` bytes starting
+    /// at `address`.
+    ///
+    /// `Error::InvalidInputData` will be returned if the region is not
+    /// in the SRAM `[0x20-0x5F]` or cannot hold at least one entry.
+    pub fn outage_history(
+        &mut self,
+        address: u8,
+        size: usize,
+    ) -> Result<OutageHistory<'_, DI, IC>, Error<E>> {
+        if !is_sram_data_range(address, size) {
+            return Err(Error::InvalidInputData);
+        }
+        OutageHistory::new(self, Memory::Sram, address, size)
+    }
+
+    /// Use a power outage history in the EEPROM region of `size` bytes
+    /// starting at `address`.
+    ///
+    /// `Error::InvalidInputData` will be returned if the region is not
+    /// in the EEPROM or cannot hold at least one entry.
+    pub fn eeprom_outage_history(
+        &mut self,
+        address: u8,
+        size: usize,
+    ) -> Result<OutageHistory<'_, DI, IC>, Error<E>>
+    where
+        IC: marker::WithEeprom,
+    {
+        if !is_eeprom_data_range(address, size, IC::EEPROM_SIZE) {
+            return Err(Error::InvalidInputData);
+        }
+        OutageHistory::new(self, Memory::Eeprom, address, size)
+    }
+}
+
+fn stats_to_bytes(stats: &OutageStats, header: &mut [u8; HEADER_SIZE]) {
+    header[2..6].copy_from_slice(&stats.count.to_le_bytes());
+    header[6..10].copy_from_slice(&(stats.total.as_secs() as u32).to_le_bytes());
+    header[10..14].copy_from_slice(&(stats.longest.as_secs() as u32).to_le_bytes());
+}
+
+fn stats_from_bytes(header: &[u8; HEADER_SIZE]) -> OutageStats {
+    let seconds = |i: usize| {
+        let value = u32::from_le_bytes([header[i], header[i + 1], header[i + 2], header[i + 3]]);
+        Duration::from_secs(value.into())
+    };
+    OutageStats {
+        count: u32::from_le_bytes([header[2], header[3], header[4], header[5]]),
+        total: seconds(6),
+        longest: seconds(10),
+    }
+}
+
+impl<'a, DI, E, IC> OutageHistory<'a, DI, IC>
+where
+    DI: interface::WriteData<Error = Error<E>> + interface::ReadData<Error = Error<E>>,
+    IC: marker::WithBatteryPower,
+{
+    fn new(
+        rtc: &'a mut Mcp794xx<DI, IC>,
+        memory: Memory,
+        address: u8,
+        size: usize,
+    ) -> Result<Self, Error<E>> {
+        if size < HEADER_SIZE + ENTRY_SIZE {
+            return Err(Error::InvalidInputData);
+        }
+        let capacity = ((size - HEADER_SIZE) / ENTRY_SIZE).min(usize::from(u8::MAX)) as u8;
+        let mut history = OutageHistory {
+            rtc,
+            memory,
+            address,
+            ring: Ring::empty(capacity),
+            stats: OutageStats::default(),
+        };
+        let mut header = [0; HEADER_SIZE];
+        history.read(address, &mut header)?;
+        if let Some(ring) = Ring::from_header(&header, capacity) {
+            history.ring = ring;
+            history.stats = stats_from_bytes(&header);
+        }
+        Ok(history)
+    }
+
+    /// Record the pending power outage, if any.
+    ///
+    /// This should be called at boot. If the power failed flag is set, the
+    /// outage is read with
+    /// [`get_power_outage()`](struct.Mcp794xx.html#method.get_power_outage),
+    /// appended to the history and the power failed flag and time-stamps
+    /// are cleared. The recorded outage is returned.
+    pub fn record(&mut self) -> Result<Option<PowerOutage>, Error<E>> {
+        if !self.rtc.has_power_failed()? {
+            return Ok(None);
+        }
+        let outage = self.rtc.get_power_outage()?;
+        let mut entry = [0; ENTRY_SIZE];
+        entry[..4].copy_from_slice(&to_seconds_since_2000(&outage.power_down).to_le_bytes());
+        entry[4..].copy_from_slice(&to_seconds_since_2000(&outage.power_up).to_le_bytes());
+        self.write(self.entry_address(self.ring.head), &entry)?;
+        let duration = Duration::from_secs(outage.duration().as_secs().min(u32::MAX.into()));
+        let total = (self.stats.total + duration).min(Duration::from_secs(u32::MAX.into()));
+        let stats = OutageStats {
+            count: self.stats.count.saturating_add(1),
+            total,
+            longest: self.stats.longest.max(duration),
+        };
+        self.write_header(self.ring.pushed(), stats)?;
+        self.rtc.clear_power_failed()?;
+        Ok(Some(outage))
+    }
+
+    /// Maximum amount of outages kept before the oldest one is overwritten
+    pub fn capacity(&self) -> usize {
+        usize::from(self.ring.capacity)
+    }
+
+    /// Amount of outages kept in the history
+    pub fn len(&self) -> usize {
+        usize::from(self.ring.len)
+    }
+
+    /// Whether the history has no outages
+    pub fn is_empty(&self) -> bool {
+        self.ring.len == 0
+    }
+
+    /// Aggregate statistics of all recorded outages
+    pub fn stats(&self) -> OutageStats {
+        self.stats
+    }
+
+    /// Remove all outages and reset the statistics.
+    pub fn clear(&mut self) -> Result<(), Error<E>> {
+        self.write_header(Ring::empty(self.ring.capacity), OutageStats::default())
+    }
+
+    /// Iterate over the outages, most recent first.
+    pub fn iter(&mut self) -> OutageHistoryIter<'_, 'a, DI, IC> {
+        OutageHistoryIter {
+            history: self,
+            index: 0,
+        }
+    }
+
+    /// Read the outage at `index`, where 0 is the most recent one.
+    ///
+    /// Returns `None` if there is no outage at `index` or its entry is
+    /// corrupt (any of the date/times is after 2099).
+    pub fn get(&mut self, index: usize) -> Result<Option<PowerOutage>, Error<E>> {
+        let Some(position) = self.ring.position(index) else {
+            return Ok(None);
+        };
+        let mut entry = [0; ENTRY_SIZE];
+        self.read(self.entry_address(position), &mut entry)?;
+        let power_down = u32::from_le_bytes([entry[0], entry[1], entry[2], entry[3]]);
+        let power_up = u32::from_le_bytes([entry[4], entry[5], entry[6], entry[7]]);
+        let outage = from_seconds_since_2000(power_down)
+            .zip(from_seconds_since_2000(power_up))
+            .map(|(power_down, power_up)| PowerOutage {
+                power_down,
+                power_up,
+            });
+        Ok(outage)
+    }
+
+    fn entry_address(&self, position: u8) -> u8 {
+        self.address + HEADER_SIZE as u8 + position * ENTRY_SIZE as u8
+    }
+
+    fn write_header(&mut self, ring: Ring, stats: OutageStats) -> Result<(), Error<E>> {
+        let mut header = [0; HEADER_SIZE];
+        stats_to_bytes(&stats, &mut header);
+        ring.write_header(&mut header);
+        self.write(self.address, &header)?;
+        self.ring = ring;
+        self.stats = stats;
+        Ok(())
+    }
+
+    // The region has been checked on creation.
+    fn read(&mut self, address: u8, data: &mut [u8]) -> Result<(), Error<E>> {
+        match self.memory {
+            Memory::Sram => self.rtc.read_sram_data(address, data),
+            Memory::Eeprom => self.rtc.iface.read_eeprom_data(address, data),
+        }
+    }
+
+    fn write(&mut self, address: u8, data: &[u8]) -> Result<(), Error<E>> {
+        match self.memory {
+            Memory::Sram => self.rtc.write_sram_data(address, data),
+            Memory::Eeprom => self.rtc.write_eeprom_pages(address, data),
+        }
+    }
+}
+
+/// Iterator over the outages of an [`OutageHistory`], most recent first.
+///
+/// Created with [`OutageHistory::iter()`]. Outages whose entry is corrupt
+/// are skipped.
+#[derive(Debug)]
+pub struct OutageHistoryIter<'b, 'a, DI, IC> {
+    history: &'b mut OutageHistory<'a, DI, IC>,
+    index: usize,
+}
+
+impl<DI, E, IC> Iterator for OutageHistoryIter<'_, '_, DI, IC>
+where
+    DI: interface::WriteData<Error = Error<E>> + interface::ReadData<Error = Error<E>>,
+    IC: marker::WithBatteryPower,
+{
+    type Item = Result<PowerOutage, Error<E>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let len = self.history.len();
+        next_entry(&mut self.index, len, |index| self.history.get(index))
+    }
+}
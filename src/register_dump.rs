@@ -0,0 +1,294 @@
+//! Register dump for diagnostics
+
+use crate::common::conversion::packed_bcd_to_decimal;
+use crate::{interface, marker, Error, Mcp794xx, Register};
+use core::fmt;
+
+pub(crate) const REGISTER_COUNT: usize = 0x20;
+const SRAM_SIZE: usize = 64;
+
+/// Contents of the RTCC registers, optionally together with the SRAM and
+/// the EEPROM status register.
+///
+/// The `Display` and `Debug` implementations decode every register, naming
+/// its fields and flags.
+/// A dump can be created from raw bytes (e.g. captured in a log) with
+/// [`from_registers()`](#method.from_registers).
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct RegisterDump {
+    registers: [u8; REGISTER_COUNT],
+    sram: Option<[u8; SRAM_SIZE]>,
+    eeprom_status: Option<u8>,
+}
+
+impl RegisterDump {
+    /// Create a dump from the contents of the registers `[0x00-0x1F]`.
+    pub fn from_registers(registers: [u8; REGISTER_COUNT]) -> Self {
+        RegisterDump {
+            registers,
+            sram: None,
+            eeprom_status: None,
+        }
+    }
+
+    /// Add the contents of the SRAM `[0x20-0x5F]`.
+    pub fn with_sram(self, sram: [u8; SRAM_SIZE]) -> Self {
+        RegisterDump {
+            sram: Some(sram),
+            ..self
+        }
+    }
+
+    /// Add the contents of the EEPROM status register.
+    pub fn with_eeprom_status(self, status: u8) -> Self {
+        RegisterDump {
+            eeprom_status: Some(status),
+            ..self
+        }
+    }
+
+    /// Contents of the registers `[0x00-0x1F]`
+    pub fn registers(&self) -> &[u8; REGISTER_COUNT] {
+        &self.registers
+    }
+
+    /// Contents of the SRAM `[0x20-0x5F]`, if included.
+    pub fn sram(&self) -> Option<&[u8; SRAM_SIZE]> {
+        self.sram.as_ref()
+    }
+
+    /// Contents of the EEPROM status register, if included.
+    pub fn eeprom_status(&self) -> Option<u8> {
+        self.eeprom_status
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Field {
+    Flag(&'static str, u8),
+    Bcd(&'static str, u8),
+    Value(&'static str, u8),
+    Hours,
+}
+
+use Field::{Bcd, Flag, Hours, Value};
+
+const SECONDS: &[Field] = &[Bcd("SECONDS", 0b0111_1111)];
+const MINUTES: &[Field] = &[Bcd("MINUTES", 0b0111_1111)];
+const DATE: &[Field] = &[Bcd("DATE", 0b0011_1111)];
+const MONTH: &[Field] = &[Bcd("MONTH", 0b0001_1111)];
+const ALARM_WEEKDAY: &[Field] = &[
+    Flag("ALMPOL", 0b1000_0000),
+    Value("ALMxMSK", 0b0111_0000),
+    Flag("ALMxIF", 0b0000_1000),
+    Value("WKDAY", 0b0000_0111),
+];
+const POWER_FAIL_MONTH: &[Field] = &[Value("WKDAY", 0b1110_0000), Bcd("MONTH", 0b0001_1111)];
+
+const REGISTERS: [(&str, &[Field]); REGISTER_COUNT] = [
+    (
+        "RTCSEC",
+        &[Flag("ST", 0b1000_0000), Bcd("SECONDS", 0b0111_1111)],
+    ),
+    ("RTCMIN", MINUTES),
+    ("RTCHOUR", &[Hours]),
+    (
+        "RTCWKDAY",
+        &[
+            Flag("OSCRUN", 0b0010_0000),
+            Flag("PWRFAIL", 0b0001_0000),
+            Flag("VBATEN", 0b0000_1000),
+            Value("WKDAY", 0b0000_0111),
+        ],
+    ),
+    ("RTCDATE", DATE),
+    (
+        "RTCMTH",
+        &[Flag("LPYR", 0b0010_0000), Bcd("MONTH", 0b0001_1111)],
+    ),
+    ("RTCYEAR", &[Bcd("YEAR", 0b1111_1111)]),
+    (
+        "CONTROL",
+        &[
+            Flag("OUT", 0b1000_0000),
+            Flag("SQWEN", 0b0100_0000),
+            Flag("ALM1EN", 0b0010_0000),
+            Flag("ALM0EN", 0b0001_0000),
+            Flag("EXTOSC", 0b0000_1000),
+            Flag("CRSTRIM", 0b0000_0100),
+            Value("SQWFS", 0b0000_0011),
+        ],
+    ),
+    (
+        "OSCTRIM",
+        &[Flag("SIGN", 0b1000_0000), Value("TRIMVAL", 0b0111_1111)],
+    ),
+    ("EEUNLOCK", &[]),
+    ("ALM0SEC", SECONDS),
+    ("ALM0MIN", MINUTES),
+    ("ALM0HOUR", &[Hours]),
+    ("ALM0WKDAY", ALARM_WEEKDAY),
+    ("ALM0DATE", DATE),
+    ("ALM0MTH", MONTH),
+    ("RESERVED", &[]),
+    ("ALM1SEC", SECONDS),
+    ("ALM1MIN", MINUTES),
+    ("ALM1HOUR", &[Hours]),
+    ("ALM1WKDAY", ALARM_WEEKDAY),
+    ("ALM1DATE", DATE),
+    ("ALM1MTH", MONTH),
+    ("RESERVED", &[]),
+    ("PWRDNMIN", MINUTES),
+    ("PWRDNHOUR", &[Hours]),
+    ("PWRDNDATE", DATE),
+    ("PWRDNMTH", POWER_FAIL_MONTH),
+    ("PWRUPMIN", MINUTES),
+    ("PWRUPHOUR", &[Hours]),
+    ("PWRUPDATE", DATE),
+    ("PWRUPMTH", POWER_FAIL_MONTH),
+];
+
+fn fmt_field(f: &mut fmt::Formatter<'_>, field: Field, data: u8) -> fmt::Result {
+    match field {
+        Flag(name, mask) => write!(f, " {}={}", name, u8::from(data & mask != 0)),
+        Bcd(name, mask) => write!(f, " {}={}", name, packed_bcd_to_decimal(data & mask)),
+        Value(name, mask) => write!(f, " {}={}", name, (data & mask) >> mask.trailing_zeros()),
+        Hours if data & 0b0100_0000 != 0 => write!(
+            f,
+            " 12/24=1 AM/PM={} HOURS={}",
+            u8::from(data & 0b0010_0000 != 0),
+            packed_bcd_to_decimal(data & 0b0001_1111)
+        ),
+        Hours => write!(
+            f,
+            " 12/24=0 HOURS={}",
+            packed_bcd_to_decimal(data & 0b0011_1111)
+        ),
+    }
+}
+
+impl fmt::Display for RegisterDump {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (address, ((name, fields), data)) in
+            REGISTERS.iter().zip(self.registers.iter()).enumerate()
+        {
+            write!(f, "{:<9} (0x{:02X}) = 0x{:02X}:", name, address, data)?;
+            for field in fields.iter() {
+                fmt_field(f, *field, *data)?;
+            }
+            writeln!(f)?;
+        }
+        if let Some(sram) = &self.sram {
+            for (row, chunk) in sram.chunks(16).enumerate() {
+                write!(f, "SRAM      (0x{:02X}) =", REGISTER_COUNT + row * 16)?;
+                for data in chunk {
+                    write!(f, " {:02X}", data)?;
+                }
+                writeln!(f)?;
+            }
+        }
+        if let Some(status) = self.eeprom_status {
+            writeln!(
+                f,
+                "EESTATUS  (0xFF) = 0x{:02X}: BP={}",
+                status,
+                (status & 0b0000_1100) >> 2
+            )?;
+        }
+        Ok(())
+    }
+}
+
+struct Hex(u8);
+
+impl fmt::Debug for Hex {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "0x{:02X}", self.0)
+    }
+}
+
+struct Decoded<'a>(&'a [Field], u8);
+
+impl fmt::Debug for Decoded<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "0x{:02X}", self.1)?;
+        if !self.0.is_empty() {
+            write!(f, " {{")?;
+            for field in self.0.iter() {
+                fmt_field(f, *field, self.1)?;
+            }
+            write!(f, " }}")?;
+        }
+        Ok(())
+    }
+}
+
+struct Bytes<'a>(&'a [u8]);
+
+impl fmt::Debug for Bytes<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.0.iter().map(|data| Hex(*data)))
+            .finish()
+    }
+}
+
+impl fmt::Debug for RegisterDump {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut s = f.debug_struct("RegisterDump");
+        for ((name, fields), data) in REGISTERS.iter().zip(self.registers.iter()) {
+            s.field(name, &Decoded(fields, *data));
+        }
+        if let Some(sram) = &self.sram {
+            s.field("SRAM", &Bytes(sram));
+        }
+        if let Some(status) = self.eeprom_status {
+            s.field("EESTATUS", &Decoded(&[Value("BP", 0b0000_1100)], status));
+        }
+        s.finish()
+    }
+}
+
+impl<DI, E, IC> Mcp794xx<DI, IC>
+where
+    DI: interface::ReadData<Error = Error<E>>,
+{
+    /// Read the registers `[0x00-0x1F]` for diagnostics.
+    pub fn dump_registers(&mut self) -> Result<RegisterDump, Error<E>> {
+        let mut registers = [0; REGISTER_COUNT];
+        self.iface.read_data(Register::SECONDS, &mut registers)?;
+        Ok(RegisterDump::from_registers(registers))
+    }
+
+    /// Read the registers `[0x00-0x1F]` and the SRAM `[0x20-0x5F]` for
+    /// diagnostics in a single transfer.
+    pub fn dump_registers_and_sram(&mut self) -> Result<RegisterDump, Error<E>> {
+        let mut data = [0; DUMP_WITH_SRAM_SIZE];
+        self.iface.read_data(Register::SECONDS, &mut data)?;
+        Ok(dump_from_registers_and_sram(&data))
+    }
+}
+
+impl<DI, E, IC> Mcp794xx<DI, IC>
+where
+    DI: interface::ReadData<Error = Error<E>>,
+    IC: marker::WithEeprom,
+{
+    /// Read the EEPROM status register.
+    ///
+    /// This can be added to a register dump with
+    /// [`RegisterDump::with_eeprom_status()`](struct.RegisterDump.html#method.with_eeprom_status).
+    pub fn read_eeprom_status(&mut self) -> Result<u8, Error<E>> {
+        self.iface.read_eeprom_status()
+    }
+}
+
+pub(crate) const DUMP_WITH_SRAM_SIZE: usize = REGISTER_COUNT + SRAM_SIZE;
+
+pub(crate) fn dump_from_registers_and_sram(data: &[u8; DUMP_WITH_SRAM_SIZE]) -> RegisterDump {
+    let mut registers = [0; REGISTER_COUNT];
+    let mut sram = [0; SRAM_SIZE];
+    registers.copy_from_slice(&data[..REGISTER_COUNT]);
+    sram.copy_from_slice(&data[REGISTER_COUNT..]);
+    RegisterDump::from_registers(registers).with_sram(sram)
+}
@@ -0,0 +1,287 @@
+//! Recurring schedule re-arming the hardware alarms
+
+use crate::common::alarm::alarm_datetime;
+use crate::common::crc16;
+use crate::common::datetime::{from_seconds_since_2000, to_seconds_since_2000};
+use crate::common::sram::is_sram_data_range;
+use crate::{
+    interface, marker, Alarm, AlarmMatching, AlarmOutputPinPolarity, DateTime, Error, Mcp794xx,
+};
+
+const SECONDS_PER_DAY: i64 = 86_400;
+const MAX_RULES: usize = 32;
+
+/// Recurrence rule of a [`Scheduler`]
+///
+/// All occurrences happen at second 0.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Recurrence {
+    /// Every amount of minutes [1-1440] counted from midnight.
+    ///
+    /// e.g. 15 occurs at 00:00, 00:15, 00:30 and so on. If the amount does
+    /// not divide a day, the last interval of the day is shorter.
+    Interval {
+        /// Minutes between occurrences
+        minutes: u16,
+    },
+    /// At a time on a set of weekdays.
+    Weekly {
+        /// Weekdays as a bit mask where bit 0 is Sunday and bit 6 is Saturday.
+        /// See [`Recurrence::WORKDAYS`] and [`Recurrence::EVERY_DAY`].
+        weekdays: u8,
+        /// Hour [0-23]
+        hour: u8,
+        /// Minute [0-59]
+        minute: u8,
+    },
+    /// At a time on a day of each month.
+    ///
+    /// Months that do not have the day are skipped.
+    Monthly {
+        /// Day of the month [1-31]
+        day: u8,
+        /// Hour [0-23]
+        hour: u8,
+        /// Minute [0-59]
+        minute: u8,
+    },
+}
+
+impl Recurrence {
+    /// Monday to Friday weekday mask
+    pub const WORKDAYS: u8 = 0b0011_1110;
+    /// Weekday mask of the whole week
+    pub const EVERY_DAY: u8 = 0b0111_1111;
+
+    /// Returns the first occurrence after the given date/time.
+    ///
+    /// Returns `None` if the rule is not valid or there are no occurrences
+    /// until the end of 2099.
+    pub fn next_after(&self, datetime: &DateTime) -> Option<DateTime> {
+        if !self.is_valid() {
+            return None;
+        }
+        match *self {
+            Recurrence::Interval { minutes } => {
+                let minutes = i64::from(minutes);
+                let elapsed = i64::from(datetime.hour()) * 60 + i64::from(datetime.minute());
+                let next = (elapsed / minutes + 1) * minutes;
+                let midnight = start_of_day(datetime);
+                if next < 24 * 60 {
+                    DateTime::from_timestamp(midnight + next * 60)
+                } else {
+                    DateTime::from_timestamp(midnight + SECONDS_PER_DAY)
+                }
+            }
+            Recurrence::Weekly {
+                weekdays,
+                hour,
+                minute,
+            } => {
+                let midnight = start_of_day(datetime);
+                (0..8)
+                    .filter_map(|days| DateTime::from_timestamp(midnight + days * SECONDS_PER_DAY))
+                    .filter(|date| weekdays & (1 << (date.weekday() - 1)) != 0)
+                    .filter_map(|date| {
+                        DateTime::new(date.year(), date.month(), date.day(), hour, minute, 0)
+                    })
+                    .find(|occurrence| occurrence > datetime)
+            }
+            Recurrence::Monthly { day, hour, minute } => {
+                let (mut year, mut month) = (datetime.year(), datetime.month());
+                for _ in 0..13 {
+                    match DateTime::new(year, month, day, hour, minute, 0) {
+                        Some(occurrence) if occurrence > *datetime => return Some(occurrence),
+                        _ => (),
+                    }
+                    month += 1;
+                    if month > 12 {
+                        month = 1;
+                        year += 1;
+                    }
+                }
+                None
+            }
+        }
+    }
+
+    fn is_valid(&self) -> bool {
+        match *self {
+            Recurrence::Interval { minutes } => (1..=24 * 60).contains(&minutes),
+            Recurrence::Weekly {
+                weekdays,
+                hour,
+                minute,
+            } => weekdays != 0 && weekdays <= Self::EVERY_DAY && hour < 24 && minute < 60,
+            Recurrence::Monthly { day, hour, minute } => {
+                (1..=31).contains(&day) && hour < 24 && minute < 60
+            }
+        }
+    }
+}
+
+fn start_of_day(datetime: &DateTime) -> i64 {
+    let timestamp = datetime.timestamp();
+    timestamp - timestamp % SECONDS_PER_DAY
+}
+
+/// Rules with occurrences since the previous [`Scheduler::service()`]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct DueRules {
+    due: u32,
+    missed: u32,
+}
+
+impl DueRules {
+    /// Whether no rule is due
+    pub fn is_empty(&self) -> bool {
+        self.due == 0
+    }
+
+    /// Whether the rule at `index` had at least one occurrence
+    pub fn is_due(&self, index: usize) -> bool {
+        index < MAX_RULES && self.due & (1 << index) != 0
+    }
+
+    /// Whether the rule at `index` had more than one occurrence, e.g. while
+    /// the MCU was without power.
+    pub fn was_missed(&self, index: usize) -> bool {
+        index < MAX_RULES && self.missed & (1 << index) != 0
+    }
+}
+
+/// Recurring schedule using both alarms of the device
+///
+/// Created with [`Mcp794xx::scheduler()`].
+///
+/// `Alarm::Zero` is set to the nearest occurrence of all rules and
+/// `Alarm::One` to the following one, both with the `AllMatch` trigger rate.
+/// Since the alarm 1 of the MCP795xx devices does not have a month register,
+/// only `Alarm::Zero` is used with them and `Alarm::One` stays disabled.
+/// After an alarm has matched, [`Scheduler::service()`] reports the rules
+/// that are due and sets the alarms again.
+///
+/// The date/time of the last service is stored in the battery-backed SRAM
+/// so that the occurrences passed while the MCU was off are reported as well.
+/// The region takes [`SCHEDULER_SIZE`] bytes: the date/time as seconds since
+/// 2000-01-01 followed by a CRC-16 (CCITT).
+#[derive(Debug)]
+pub struct Scheduler<'a, DI, IC> {
+    rtc: &'a mut Mcp794xx<DI, IC>,
+    address: u8,
+    rules: &'a [Recurrence],
+    polarity: AlarmOutputPinPolarity,
+}
+
+/// Size of the [`Scheduler`] SRAM region in bytes
+pub const SCHEDULER_SIZE: usize = 6;
+
+impl<DI, E, IC> Mcp794xx<DI, IC>
+where
+    DI: interface::WriteData<Error = Error<E>> + interface::ReadData<Error = Error<E>>,
+{
+    /// Use both alarms for a recurring schedule of up to 32 rules storing
+    /// its state in the SRAM at `address`.
+    ///
+    /// Call [`Scheduler::service()`] to set the alarms.
+    ///
+    /// `Error::InvalidInputData` will be returned if any of the rules is not
+    /// valid, there are more than 32 rules or the region does not fit in the
+    /// SRAM `[0x20-0x5F]`.
+    pub fn scheduler<'a>(
+        &'a mut self,
+        address: u8,
+        rules: &'a [Recurrence],
+        polarity: AlarmOutputPinPolarity,
+    ) -> Result<Scheduler<'a, DI, IC>, Error<E>> {
+        if !is_sram_data_range(address, SCHEDULER_SIZE)
+            || rules.len() > MAX_RULES
+            || !rules.iter().all(Recurrence::is_valid)
+        {
+            return Err(Error::InvalidInputData);
+        }
+        Ok(Scheduler {
+            rtc: self,
+            address,
+            rules,
+            polarity,
+        })
+    }
+}
+
+impl<DI, E, IC> Scheduler<'_, DI, IC>
+where
+    DI: interface::WriteData<Error = Error<E>> + interface::ReadData<Error = Error<E>>,
+    IC: marker::WithAlarms,
+{
+    /// Report the rules with occurrences since the previous service and set
+    /// the alarms to the next occurrences.
+    ///
+    /// This should be called once after each MCU reset and whenever an alarm
+    /// has matched. It clears the alarm matched flags.
+    ///
+    /// If the SRAM contents are not valid or the date/time was set to an
+    /// earlier value, no rule is reported.
+    pub fn service(&mut self) -> Result<DueRules, Error<E>> {
+        let now = self.rtc.read_datetime()?;
+        let last = self.read()?.filter(|last| *last <= now).unwrap_or(now);
+        let mut due = DueRules::default();
+        for (index, rule) in self.rules.iter().enumerate() {
+            if let Some(first) = rule.next_after(&last).filter(|first| *first <= now) {
+                due.due |= 1 << index;
+                if rule.next_after(&first).is_some_and(|second| second <= now) {
+                    due.missed |= 1 << index;
+                }
+            }
+        }
+        let next = self.next_after(&now);
+        let following = next
+            .filter(|_| IC::HAS_ALARM1_MONTH)
+            .and_then(|next| self.next_after(&next));
+        self.set_alarm(Alarm::Zero, next)?;
+        self.set_alarm(Alarm::One, following)?;
+        self.write(&now)?;
+        Ok(due)
+    }
+
+    // Nearest occurrence of all rules after a date/time
+    fn next_after(&self, datetime: &DateTime) -> Option<DateTime> {
+        self.rules
+            .iter()
+            .filter_map(|rule| rule.next_after(datetime))
+            .min()
+    }
+
+    fn set_alarm(&mut self, alarm: Alarm, when: Option<DateTime>) -> Result<(), Error<E>> {
+        match when {
+            Some(when) => {
+                let when = alarm_datetime(&when);
+                self.rtc
+                    .set_alarm(alarm, when, AlarmMatching::AllMatch, self.polarity)?;
+                self.rtc.enable_alarm(alarm)
+            }
+            None => {
+                self.rtc.disable_alarm(alarm)?;
+                self.rtc.clear_alarm_matched_flag(alarm)
+            }
+        }
+    }
+
+    fn read(&mut self) -> Result<Option<DateTime>, Error<E>> {
+        let mut data = [0; SCHEDULER_SIZE];
+        self.rtc.read_sram_data(self.address, &mut data)?;
+        if crc16(&data[..4]).to_be_bytes() != data[4..] {
+            return Ok(None);
+        }
+        let seconds = u32::from_le_bytes([data[0], data[1], data[2], data[3]]);
+        Ok(from_seconds_since_2000(seconds))
+    }
+
+    fn write(&mut self, datetime: &DateTime) -> Result<(), Error<E>> {
+        let mut data = [0; SCHEDULER_SIZE];
+        data[..4].copy_from_slice(&to_seconds_since_2000(datetime).to_le_bytes());
+        let crc = crc16(&data[..4]).to_be_bytes();
+        data[4..].copy_from_slice(&crc);
+        self.rtc.write_sram_data(self.address, &data)
+    }
+}
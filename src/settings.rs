@@ -0,0 +1,241 @@
+//! Persistent settings store in the EEPROM
+
+use crate::common::crc16;
+use crate::{interface, marker, Error, Mcp794xx};
+
+// Version, sequence number and payload length
+const HEADER_SIZE: usize = 3;
+const CRC_SIZE: usize = 2;
+const MAX_SLOT_SIZE: usize = 128;
+
+/// Settings record which can be stored in the EEPROM.
+///
+/// See [`SettingsStore`].
+pub trait Settings: Sized {
+    /// Version of the stored record layout
+    const VERSION: u8;
+
+    /// Serialize the settings into the buffer.
+    ///
+    /// Returns the amount of bytes written or `None` if the buffer is too small.
+    fn serialize(&self, buffer: &mut [u8]) -> Option<usize>;
+
+    /// Deserialize settings stored with the current version.
+    ///
+    /// Returns `None` if the data is invalid.
+    fn deserialize(data: &[u8]) -> Option<Self>;
+
+    /// Migrate settings stored with an older version.
+    ///
+    /// Returns `None` if the version is not supported (default).
+    fn migrate(version: u8, data: &[u8]) -> Option<Self> {
+        let _ = (version, data);
+        None
+    }
+}
+
+/// Errors of the [`SettingsStore`]
+#[derive(Debug)]
+pub enum SettingsError<E> {
+    /// Device error
+    Device(Error<E>),
+    /// No settings have been stored
+    Blank,
+    /// The stored settings are corrupt
+    Corrupt,
+    /// The stored settings have an unsupported version: (stored version)
+    VersionMismatch(u8),
+}
+
+impl<E> From<Error<E>> for SettingsError<E> {
+    fn from(error: Error<E>) -> Self {
+        SettingsError::Device(error)
+    }
+}
+
+/// Versioned settings store in the EEPROM protected with a CRC.
+///
+/// Created with [`Mcp794xx::settings_store()`].
+///
+/// The store uses two slots of the same size one after another.
+/// Every save overwrites the slot not holding the most recent record so
+/// that a power loss in the middle of a write leaves the previous record
+/// intact. Each slot contains a 3-byte header (version, sequence number and
+/// length), the serialized settings and a CRC-16 (CCITT), so the settings can
+/// take up to the slot size minus 5 bytes.
+#[derive(Debug)]
+pub struct SettingsStore<'a, DI, IC> {
+    rtc: &'a mut Mcp794xx<DI, IC>,
+    address: u8,
+    slot_size: usize,
+}
+
+impl<DI, E, IC> Mcp794xx<DI, IC>
+where
+    DI: interface::WriteData<Error = Error<E>> + interface::ReadData<Error = Error<E>>,
+    IC: marker::WithEeprom,
+{
+    /// Use a settings store in the EEPROM with two slots of `slot_size` bytes
+    /// starting at `address`.
+    ///
+    /// `Error::InvalidInputData` will be returned if the slots do not fit in
+    /// the EEPROM or cannot hold any data.
+    pub fn settings_store(
+        &mut self,
+        address: u8,
+        slot_size: usize,
+    ) -> Result<SettingsStore<'_, DI, IC>, Error<E>> {
+        if slot_size <= HEADER_SIZE + CRC_SIZE
+            || usize::from(address) + 2 * slot_size > IC::EEPROM_SIZE
+        {
+            return Err(Error::InvalidInputData);
+        }
+        Ok(SettingsStore {
+            rtc: self,
+            address,
+            slot_size,
+        })
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Slot {
+    Blank,
+    Corrupt,
+    Valid {
+        version: u8,
+        sequence: u8,
+        len: usize,
+    },
+}
+
+impl<DI, E, IC> SettingsStore<'_, DI, IC>
+where
+    DI: interface::WriteData<Error = Error<E>> + interface::ReadData<Error = Error<E>>,
+    IC: marker::WithEeprom,
+{
+    /// Load the most recent settings.
+    ///
+    /// Settings stored with an older version are passed to
+    /// [`Settings::migrate()`]. They are not stored again in the current
+    /// version until [`save()`](#method.save) is called.
+    ///
+    /// Returns `SettingsError::Blank` if no settings have been stored,
+    /// `SettingsError::Corrupt` if no valid record could be found and
+    /// `SettingsError::VersionMismatch` if the stored version is newer or
+    /// cannot be migrated.
+    pub fn load<S: Settings>(&mut self) -> Result<S, SettingsError<E>> {
+        let mut buffer = [0; MAX_SLOT_SIZE];
+        let (slot, _) = self.read_latest(&mut buffer)?;
+        let data = &buffer[..self.slot_size];
+        match slot {
+            Slot::Blank => Err(SettingsError::Blank),
+            Slot::Corrupt => Err(SettingsError::Corrupt),
+            Slot::Valid { version, len, .. } => {
+                let payload = &data[HEADER_SIZE..HEADER_SIZE + len];
+                if version == S::VERSION {
+                    S::deserialize(payload).ok_or(SettingsError::Corrupt)
+                } else if version < S::VERSION {
+                    S::migrate(version, payload).ok_or(SettingsError::VersionMismatch(version))
+                } else {
+                    Err(SettingsError::VersionMismatch(version))
+                }
+            }
+        }
+    }
+
+    /// Save the settings, keeping the previously stored record until the
+    /// write has completed.
+    ///
+    /// `SettingsError::Device(Error::InvalidInputData)` will be returned if
+    /// the serialized settings do not fit in a slot.
+    pub fn save<S: Settings>(&mut self, settings: &S) -> Result<(), SettingsError<E>> {
+        let mut buffer = [0; MAX_SLOT_SIZE];
+        let capacity = self.slot_size - HEADER_SIZE - CRC_SIZE;
+        let len = settings
+            .serialize(&mut buffer[HEADER_SIZE..HEADER_SIZE + capacity])
+            .filter(|len| *len <= capacity)
+            .ok_or(Error::InvalidInputData)?;
+        let mut scratch = [0; MAX_SLOT_SIZE];
+        let (latest, index) = self.read_latest(&mut scratch)?;
+        let (index, sequence) = match latest {
+            Slot::Valid { sequence, .. } => (1 - index, sequence.wrapping_add(1)),
+            _ => (0, 0),
+        };
+        buffer[0] = S::VERSION;
+        buffer[1] = sequence;
+        buffer[2] = len as u8;
+        let crc = crc16(&buffer[..HEADER_SIZE + len]);
+        buffer[HEADER_SIZE + len..HEADER_SIZE + len + CRC_SIZE].copy_from_slice(&crc.to_be_bytes());
+        Ok(self.rtc.write_eeprom_data(
+            self.slot_address(index),
+            &buffer[..HEADER_SIZE + len + CRC_SIZE],
+        )?)
+    }
+
+    /// Erase both slots so that the store is blank.
+    pub fn clear(&mut self) -> Result<(), SettingsError<E>> {
+        let blank = [0xFF; MAX_SLOT_SIZE];
+        for index in 0..2 {
+            self.rtc
+                .write_eeprom_data(self.slot_address(index), &blank[..self.slot_size])?;
+        }
+        Ok(())
+    }
+
+    fn slot_address(&self, index: usize) -> u8 {
+        self.address + (index * self.slot_size) as u8
+    }
+
+    // Reads both slots and leaves the most recent valid one in the buffer.
+    // If there is none, the slot is blank only if both slots are blank.
+    fn read_latest(&mut self, buffer: &mut [u8; MAX_SLOT_SIZE]) -> Result<(Slot, usize), Error<E>> {
+        let mut other = [0; MAX_SLOT_SIZE];
+        let size = self.slot_size;
+        self.rtc
+            .read_eeprom_data(self.slot_address(0), &mut buffer[..size])?;
+        self.rtc
+            .read_eeprom_data(self.slot_address(1), &mut other[..size])?;
+        let first = check_slot(&buffer[..size]);
+        let second = check_slot(&other[..size]);
+        Ok(match (first, second) {
+            (Slot::Valid { sequence: a, .. }, Slot::Valid { sequence: b, .. })
+                if is_newer(b, a) =>
+            {
+                buffer.copy_from_slice(&other);
+                (second, 1)
+            }
+            (Slot::Valid { .. }, _) => (first, 0),
+            (_, Slot::Valid { .. }) => {
+                buffer.copy_from_slice(&other);
+                (second, 1)
+            }
+            (Slot::Blank, Slot::Blank) => (Slot::Blank, 0),
+            _ => (Slot::Corrupt, 0),
+        })
+    }
+}
+
+fn check_slot(data: &[u8]) -> Slot {
+    if data[..HEADER_SIZE].iter().all(|byte| *byte == 0xFF) {
+        return Slot::Blank;
+    }
+    let len = usize::from(data[2]);
+    if HEADER_SIZE + len + CRC_SIZE > data.len() {
+        return Slot::Corrupt;
+    }
+    let crc = crc16(&data[..HEADER_SIZE + len]).to_be_bytes();
+    if crc != data[HEADER_SIZE + len..HEADER_SIZE + len + CRC_SIZE] {
+        return Slot::Corrupt;
+    }
+    Slot::Valid {
+        version: data[0],
+        sequence: data[1],
+        len,
+    }
+}
+
+// Whether sequence number `a` was written after `b`, taking wrap-around into account.
+fn is_newer(a: u8, b: u8) -> bool {
+    (a.wrapping_sub(b) as i8) > 0
+}
@@ -0,0 +1,216 @@
+//! `embedded-storage` traits implementation for the EEPROM
+
+use crate::eeprom::EEPROM_PAGE_SIZE;
+use crate::{interface, marker, Error, Mcp794xx};
+use embedded_storage::nor_flash::{
+    ErrorType, MultiwriteNorFlash, NorFlash, NorFlashError, NorFlashErrorKind, ReadNorFlash,
+};
+use embedded_storage::{ReadStorage, Storage};
+
+/// EEPROM adapter implementing the `embedded-storage` traits.
+///
+/// Created with [`Mcp794xx::eeprom_storage()`].
+///
+/// The offsets are EEPROM addresses. Accesses beyond the EEPROM size return
+/// `StorageError::Device(Error::InvalidInputData)` and writes to
+/// write-protected addresses return `StorageError::WriteProtected`. See
+/// [`set_eeprom_write_protection()`](struct.Mcp794xx.html#method.set_eeprom_write_protection).
+///
+/// The NOR-flash traits use the 8-byte EEPROM page as write and erase size.
+/// Writes do not need a previous erase and erasing fills the memory with `0xFF`.
+#[derive(Debug)]
+pub struct EepromStorage<'a, DI, IC> {
+    rtc: &'a mut Mcp794xx<DI, IC>,
+}
+
+/// Errors of the [`EepromStorage`] adapter
+#[derive(Debug)]
+pub enum StorageError<E> {
+    /// Device error
+    Device(Error<E>),
+    /// The offset or length is not aligned to the EEPROM page size
+    NotAligned,
+    /// The EEPROM addresses to be written are write-protected
+    WriteProtected,
+}
+
+impl<E> From<Error<E>> for StorageError<E> {
+    fn from(error: Error<E>) -> Self {
+        StorageError::Device(error)
+    }
+}
+
+impl<DI, E, IC> Mcp794xx<DI, IC>
+where
+    DI: interface::WriteData<Error = Error<E>> + interface::ReadData<Error = Error<E>>,
+    IC: marker::WithEeprom,
+{
+    /// Use the EEPROM through the `embedded-storage` traits.
+    pub fn eeprom_storage(&mut self) -> EepromStorage<'_, DI, IC> {
+        EepromStorage { rtc: self }
+    }
+}
+
+impl<DI, E, IC> EepromStorage<'_, DI, IC>
+where
+    DI: interface::WriteData<Error = Error<E>> + interface::ReadData<Error = Error<E>>,
+    IC: marker::WithEeprom,
+{
+    fn check_range(offset: u32, len: usize) -> Result<u8, Error<E>> {
+        let end = usize::try_from(offset)
+            .ok()
+            .and_then(|offset| offset.checked_add(len));
+        match end {
+            Some(end) if end <= IC::EEPROM_SIZE => Ok(offset as u8),
+            _ => Err(Error::InvalidInputData),
+        }
+    }
+
+    fn check_aligned(offset: u32, len: usize) -> Result<(), StorageError<E>> {
+        if offset as usize % EEPROM_PAGE_SIZE != 0 || len % EEPROM_PAGE_SIZE != 0 {
+            Err(StorageError::NotAligned)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn check_writable(&mut self, address: u8, len: usize) -> Result<(), StorageError<E>> {
+        let status = self.rtc.read_eeprom_status()?;
+        if usize::from(address) + len > writable_size(status, IC::EEPROM_SIZE) {
+            Err(StorageError::WriteProtected)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn read_data(&mut self, offset: u32, bytes: &mut [u8]) -> Result<(), StorageError<E>> {
+        if bytes.is_empty() {
+            return Ok(());
+        }
+        let address = Self::check_range(offset, bytes.len())?;
+        Ok(self.rtc.read_eeprom_data(address, bytes)?)
+    }
+
+    fn write_data(&mut self, offset: u32, bytes: &[u8]) -> Result<(), StorageError<E>> {
+        if bytes.is_empty() {
+            return Ok(());
+        }
+        let address = Self::check_range(offset, bytes.len())?;
+        self.check_writable(address, bytes.len())?;
+        Ok(self.rtc.write_eeprom_data(address, bytes)?)
+    }
+}
+
+// Amount of bytes from the beginning of the EEPROM not write-protected
+// by the block protection bits in the EEPROM status register.
+fn writable_size(status: u8, size: usize) -> usize {
+    match (status >> 2) & 0b11 {
+        0 => size,
+        1 => size / 4 * 3,
+        2 => size / 2,
+        _ => 0,
+    }
+}
+
+impl<DI, E, IC> ReadStorage for EepromStorage<'_, DI, IC>
+where
+    DI: interface::WriteData<Error = Error<E>> + interface::ReadData<Error = Error<E>>,
+    IC: marker::WithEeprom,
+{
+    type Error = StorageError<E>;
+
+    fn read(&mut self, offset: u32, bytes: &mut [u8]) -> Result<(), Self::Error> {
+        self.read_data(offset, bytes)
+    }
+
+    fn capacity(&self) -> usize {
+        IC::EEPROM_SIZE
+    }
+}
+
+impl<DI, E, IC> Storage for EepromStorage<'_, DI, IC>
+where
+    DI: interface::WriteData<Error = Error<E>> + interface::ReadData<Error = Error<E>>,
+    IC: marker::WithEeprom,
+{
+    fn write(&mut self, offset: u32, bytes: &[u8]) -> Result<(), Self::Error> {
+        self.write_data(offset, bytes)
+    }
+}
+
+impl<E: core::fmt::Debug> NorFlashError for StorageError<E> {
+    fn kind(&self) -> NorFlashErrorKind {
+        match self {
+            StorageError::Device(Error::InvalidInputData) => NorFlashErrorKind::OutOfBounds,
+            StorageError::NotAligned => NorFlashErrorKind::NotAligned,
+            _ => NorFlashErrorKind::Other,
+        }
+    }
+}
+
+impl<DI, E, IC> ErrorType for EepromStorage<'_, DI, IC>
+where
+    DI: interface::WriteData<Error = Error<E>> + interface::ReadData<Error = Error<E>>,
+    IC: marker::WithEeprom,
+    E: core::fmt::Debug,
+{
+    type Error = StorageError<E>;
+}
+
+impl<DI, E, IC> ReadNorFlash for EepromStorage<'_, DI, IC>
+where
+    DI: interface::WriteData<Error = Error<E>> + interface::ReadData<Error = Error<E>>,
+    IC: marker::WithEeprom,
+    E: core::fmt::Debug,
+{
+    const READ_SIZE: usize = 1;
+
+    fn read(&mut self, offset: u32, bytes: &mut [u8]) -> Result<(), Self::Error> {
+        self.read_data(offset, bytes)
+    }
+
+    fn capacity(&self) -> usize {
+        IC::EEPROM_SIZE
+    }
+}
+
+impl<DI, E, IC> NorFlash for EepromStorage<'_, DI, IC>
+where
+    DI: interface::WriteData<Error = Error<E>> + interface::ReadData<Error = Error<E>>,
+    IC: marker::WithEeprom,
+    E: core::fmt::Debug,
+{
+    const WRITE_SIZE: usize = EEPROM_PAGE_SIZE;
+    const ERASE_SIZE: usize = EEPROM_PAGE_SIZE;
+
+    fn erase(&mut self, from: u32, to: u32) -> Result<(), Self::Error> {
+        if from > to {
+            return Err(StorageError::Device(Error::InvalidInputData));
+        }
+        let len = (to - from) as usize;
+        Self::check_aligned(from, len)?;
+        if len == 0 {
+            return Ok(());
+        }
+        let address = Self::check_range(from, len)?;
+        self.check_writable(address, len)?;
+        let erased = [0xFF; EEPROM_PAGE_SIZE];
+        for page in (usize::from(address)..usize::from(address) + len).step_by(EEPROM_PAGE_SIZE) {
+            self.rtc.write_eeprom_data(page as u8, &erased)?;
+        }
+        Ok(())
+    }
+
+    fn write(&mut self, offset: u32, bytes: &[u8]) -> Result<(), Self::Error> {
+        Self::check_aligned(offset, bytes.len())?;
+        self.write_data(offset, bytes)
+    }
+}
+
+impl<DI, E, IC> MultiwriteNorFlash for EepromStorage<'_, DI, IC>
+where
+    DI: interface::WriteData<Error = Error<E>> + interface::ReadData<Error = Error<E>>,
+    IC: marker::WithEeprom,
+    E: core::fmt::Debug,
+{
+}
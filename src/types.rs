@@ -1,5 +1,8 @@
 //! Data types
-use rtcc::Hours;
+use crate::DateTime;
+#[cfg(feature = "rtcc")]
+use crate::Hours;
+use core::time::Duration;
 
 /// All possible errors in this crate
 #[derive(Debug)]
@@ -12,6 +15,17 @@ pub enum Error<E> {
     InvalidTimeData(u32, u32, u32),
     /// Invalid date set in device: (years, months, days)
     InvalidDateData(u32, u32, u32),
+    /// Invalid alarm matching configuration set in device: (ALMxMSK bits)
+    InvalidAlarmData(u8),
+    /// The EEPROM write cycle did not complete within the configured
+    /// amount of polling attempts
+    EepromWriteTimeout,
+    /// The oscillator did not stop within the configured amount of polling
+    /// attempts after clearing the ST bit
+    OscillatorStopTimeout,
+    /// The oscillator did not start within the configured amount of polling
+    /// attempts after setting the ST bit
+    OscillatorStartTimeout,
 }
 
 /// Square-wave output frequency
@@ -71,6 +85,20 @@ pub enum Alarm {
     One,
 }
 
+/// Hours in either 12-hour (AM/PM) or 24-hour format
+///
+/// With the `rtcc` feature this is the `rtcc::Hours` type instead.
+#[cfg(not(feature = "rtcc"))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hours {
+    /// AM [1-12]
+    AM(u8),
+    /// PM [1-12]
+    PM(u8),
+    /// 24H format [0-23]
+    H24(u8),
+}
+
 /// Alarm date/time
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct AlarmDateTime {
@@ -88,6 +116,54 @@ pub struct AlarmDateTime {
     pub second: u8,
 }
 
+/// Alarm configuration
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AlarmConfig {
+    /// Alarm date/time
+    pub when: AlarmDateTime,
+    /// Alarm trigger rate
+    pub matching: AlarmMatching,
+    /// Alarm interrupt output pin polarity
+    pub polarity: AlarmOutputPinPolarity,
+    /// Whether the alarm is enabled
+    pub is_enabled: bool,
+}
+
+/// Device status
+///
+/// See: [`status()`](struct.Mcp794xx.html#method.status)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Status {
+    /// Whether the oscillator is enabled (ST)
+    pub is_enabled: bool,
+    /// Whether the oscillator is running (OSCRUN)
+    pub is_oscillator_running: bool,
+    /// Whether the primary power has failed (PWRFAIL)
+    pub has_power_failed: bool,
+    /// Whether the backup battery power is enabled (VBATEN)
+    pub is_battery_power_enabled: bool,
+    /// Whether the current year is a leap year (LPYR)
+    pub is_leap_year: bool,
+    /// Whether alarm 0 has matched (ALM0IF)
+    pub has_alarm0_matched: bool,
+    /// Whether alarm 1 has matched (ALM1IF)
+    pub has_alarm1_matched: bool,
+    /// Whether alarm 0 is enabled (ALM0EN)
+    pub is_alarm0_enabled: bool,
+    /// Whether alarm 1 is enabled (ALM1EN)
+    pub is_alarm1_enabled: bool,
+    /// Whether the square-wave output is enabled (SQWEN)
+    pub is_square_wave_enabled: bool,
+    /// Whether the external oscillator source is enabled (EXTOSC)
+    pub is_external_oscillator_enabled: bool,
+    /// Whether coarse trim is enabled (CRSTRIM)
+    pub is_coarse_trim_enabled: bool,
+    /// General purpose output pin logic level (OUT)
+    pub output_pin_level: OutputPinLevel,
+    /// Trimming value (OSCTRIM)
+    pub trimming: i8,
+}
+
 /// Power fail date/time
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct PowerFailDateTime {
@@ -103,7 +179,30 @@ pub struct PowerFailDateTime {
     pub minute: u8,
 }
 
+/// Power outage reconstructed from the power-fail time-stamps
+///
+/// The device does not store the year nor the seconds in the time-stamps.
+/// The year is inferred from the current date and the seconds are always 0.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PowerOutage {
+    /// Date/time when the power went down (under Vtrip)
+    pub power_down: DateTime,
+    /// Date/time when the power went back up (above Vtrip)
+    pub power_up: DateTime,
+}
+
+impl PowerOutage {
+    /// Time without primary power
+    pub fn duration(&self) -> Duration {
+        let seconds = self.power_up.timestamp() - self.power_down.timestamp();
+        Duration::from_secs(seconds.max(0) as u64)
+    }
+}
+
 /// EEPROM block write protection
+///
+/// The addresses correspond to devices with 1 Kbit of EEPROM. On devices with
+/// 2 Kbit of EEPROM the same fractions of the address range are protected.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum EepromWriteProtection {
     /// None of the addresses is write-protected (default)
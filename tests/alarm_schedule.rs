@@ -0,0 +1,245 @@
+use mcp794xx::{AlarmDateTime, AlarmMatching as AM, DateTime, Hours};
+
+const WHEN: AlarmDateTime = AlarmDateTime {
+    month: 8,
+    day: 13,
+    weekday: 2,
+    hour: Hours::H24(10),
+    minute: 30,
+    second: 15,
+};
+
+fn dt(year: u16, month: u8, day: u8, hour: u8, minute: u8, second: u8) -> DateTime {
+    DateTime::new(year, month, day, hour, minute, second).unwrap()
+}
+
+mod would_match {
+    use super::*;
+
+    #[test]
+    fn seconds() {
+        assert!(WHEN.would_match(AM::SecondsMatch, &dt(2020, 1, 1, 0, 0, 15)));
+        assert!(!WHEN.would_match(AM::SecondsMatch, &dt(2020, 1, 1, 0, 0, 16)));
+    }
+
+    #[test]
+    fn minutes() {
+        assert!(WHEN.would_match(AM::MinutesMatch, &dt(2020, 1, 1, 0, 30, 0)));
+        assert!(WHEN.would_match(AM::MinutesMatch, &dt(2020, 1, 1, 23, 30, 59)));
+        assert!(!WHEN.would_match(AM::MinutesMatch, &dt(2020, 1, 1, 0, 31, 0)));
+    }
+
+    #[test]
+    fn hours() {
+        assert!(WHEN.would_match(AM::HoursMatch, &dt(2020, 1, 1, 10, 0, 0)));
+        assert!(!WHEN.would_match(AM::HoursMatch, &dt(2020, 1, 1, 22, 0, 0)));
+    }
+
+    #[test]
+    fn hours_in_12h_format() {
+        let when = AlarmDateTime {
+            hour: Hours::PM(12),
+            ..WHEN
+        };
+        assert!(when.would_match(AM::HoursMatch, &dt(2020, 1, 1, 12, 0, 0)));
+        let when = AlarmDateTime {
+            hour: Hours::AM(12),
+            ..WHEN
+        };
+        assert!(when.would_match(AM::HoursMatch, &dt(2020, 1, 1, 0, 0, 0)));
+        let when = AlarmDateTime {
+            hour: Hours::PM(10),
+            ..WHEN
+        };
+        assert!(when.would_match(AM::HoursMatch, &dt(2020, 1, 1, 22, 0, 0)));
+    }
+
+    #[test]
+    fn weekday_during_the_whole_day() {
+        // Monday
+        assert!(WHEN.would_match(AM::WeekdayMatches, &dt(2018, 8, 13, 0, 0, 0)));
+        assert!(WHEN.would_match(AM::WeekdayMatches, &dt(2018, 8, 13, 23, 59, 59)));
+        assert!(!WHEN.would_match(AM::WeekdayMatches, &dt(2018, 8, 14, 0, 0, 0)));
+    }
+
+    #[test]
+    fn day_during_the_whole_day() {
+        assert!(WHEN.would_match(AM::DayMatches, &dt(2020, 2, 13, 12, 0, 0)));
+        assert!(!WHEN.would_match(AM::DayMatches, &dt(2020, 2, 14, 12, 0, 0)));
+    }
+
+    #[test]
+    fn all() {
+        assert!(WHEN.would_match(AM::AllMatch, &dt(2018, 8, 13, 10, 30, 15)));
+        assert!(!WHEN.would_match(AM::AllMatch, &dt(2018, 8, 13, 10, 30, 16)));
+        // 2019-08-13 is a Tuesday
+        assert!(!WHEN.would_match(AM::AllMatch, &dt(2019, 8, 13, 10, 30, 15)));
+    }
+}
+
+mod next_match {
+    use super::*;
+
+    #[test]
+    fn seconds() {
+        let now = dt(2020, 1, 1, 0, 0, 0);
+        assert_eq!(
+            Some(dt(2020, 1, 1, 0, 0, 15)),
+            WHEN.next_match(AM::SecondsMatch, &now)
+        );
+        let now = dt(2020, 1, 1, 0, 0, 15);
+        assert_eq!(
+            Some(dt(2020, 1, 1, 0, 1, 15)),
+            WHEN.next_match(AM::SecondsMatch, &now)
+        );
+    }
+
+    #[test]
+    fn seconds_across_new_year() {
+        let now = dt(2020, 12, 31, 23, 59, 30);
+        assert_eq!(
+            Some(dt(2021, 1, 1, 0, 0, 15)),
+            WHEN.next_match(AM::SecondsMatch, &now)
+        );
+    }
+
+    #[test]
+    fn minutes() {
+        let now = dt(2020, 1, 1, 0, 0, 0);
+        assert_eq!(
+            Some(dt(2020, 1, 1, 0, 30, 0)),
+            WHEN.next_match(AM::MinutesMatch, &now)
+        );
+        let now = dt(2020, 1, 1, 0, 30, 10);
+        assert_eq!(
+            Some(dt(2020, 1, 1, 0, 30, 11)),
+            WHEN.next_match(AM::MinutesMatch, &now)
+        );
+        let now = dt(2020, 1, 1, 23, 30, 59);
+        assert_eq!(
+            Some(dt(2020, 1, 2, 0, 30, 0)),
+            WHEN.next_match(AM::MinutesMatch, &now)
+        );
+    }
+
+    #[test]
+    fn hours() {
+        let now = dt(2020, 1, 1, 11, 0, 0);
+        assert_eq!(
+            Some(dt(2020, 1, 2, 10, 0, 0)),
+            WHEN.next_match(AM::HoursMatch, &now)
+        );
+        let now = dt(2020, 1, 1, 10, 59, 58);
+        assert_eq!(
+            Some(dt(2020, 1, 1, 10, 59, 59)),
+            WHEN.next_match(AM::HoursMatch, &now)
+        );
+    }
+
+    #[test]
+    fn weekday() {
+        // Tuesday
+        let now = dt(2018, 8, 14, 12, 0, 0);
+        assert_eq!(
+            Some(dt(2018, 8, 20, 0, 0, 0)),
+            WHEN.next_match(AM::WeekdayMatches, &now)
+        );
+        // Monday
+        let now = dt(2018, 8, 13, 12, 0, 0);
+        assert_eq!(
+            Some(dt(2018, 8, 13, 12, 0, 1)),
+            WHEN.next_match(AM::WeekdayMatches, &now)
+        );
+    }
+
+    #[test]
+    fn day_31_skips_short_months() {
+        let when = AlarmDateTime { day: 31, ..WHEN };
+        let now = dt(2020, 3, 31, 23, 59, 59);
+        assert_eq!(
+            Some(dt(2020, 5, 31, 0, 0, 0)),
+            when.next_match(AM::DayMatches, &now)
+        );
+    }
+
+    #[test]
+    fn all() {
+        let now = dt(2018, 8, 13, 10, 30, 15);
+        // Next time August 13th is a Monday
+        assert_eq!(
+            Some(dt(2029, 8, 13, 10, 30, 15)),
+            WHEN.next_match(AM::AllMatch, &now)
+        );
+    }
+
+    #[test]
+    fn all_in_12h_format() {
+        let when = AlarmDateTime {
+            hour: Hours::AM(10),
+            ..WHEN
+        };
+        let now = dt(2018, 8, 13, 0, 0, 0);
+        assert_eq!(
+            Some(dt(2018, 8, 13, 10, 30, 15)),
+            when.next_match(AM::AllMatch, &now)
+        );
+    }
+
+    #[test]
+    fn never_on_impossible_date() {
+        let when = AlarmDateTime {
+            month: 2,
+            day: 30,
+            ..WHEN
+        };
+        let now = dt(2020, 1, 1, 0, 0, 0);
+        assert_eq!(None, when.next_match(AM::AllMatch, &now));
+        let when = AlarmDateTime { day: 32, ..WHEN };
+        assert_eq!(None, when.next_match(AM::DayMatches, &now));
+        let when = AlarmDateTime { weekday: 0, ..WHEN };
+        assert_eq!(None, when.next_match(AM::WeekdayMatches, &now));
+    }
+
+    #[test]
+    fn never_on_invalid_time() {
+        let now = dt(2020, 1, 1, 0, 0, 0);
+        let when = AlarmDateTime {
+            hour: Hours::H24(24),
+            ..WHEN
+        };
+        assert_eq!(None, when.next_match(AM::HoursMatch, &now));
+        let when = AlarmDateTime { minute: 60, ..WHEN };
+        assert_eq!(None, when.next_match(AM::MinutesMatch, &now));
+        let when = AlarmDateTime { second: 60, ..WHEN };
+        assert_eq!(None, when.next_match(AM::SecondsMatch, &now));
+    }
+
+    #[test]
+    fn never_after_2099() {
+        let now = dt(2099, 12, 31, 10, 59, 58);
+        assert_eq!(
+            Some(dt(2099, 12, 31, 10, 59, 59)),
+            WHEN.next_match(AM::HoursMatch, &now)
+        );
+        let now = dt(2099, 12, 31, 10, 59, 59);
+        assert_eq!(None, WHEN.next_match(AM::HoursMatch, &now));
+    }
+
+    #[test]
+    fn next_match_would_match() {
+        let matchings = [
+            AM::SecondsMatch,
+            AM::MinutesMatch,
+            AM::HoursMatch,
+            AM::WeekdayMatches,
+            AM::DayMatches,
+            AM::AllMatch,
+        ];
+        let now = dt(2018, 8, 13, 10, 30, 15);
+        for matching in matchings {
+            let next = WHEN.next_match(matching, &now).unwrap();
+            assert!(next > now);
+            assert!(WHEN.would_match(matching, &next), "{matching:?}");
+        }
+    }
+}
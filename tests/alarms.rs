@@ -7,8 +7,8 @@ use crate::common::{
     DEVICE_ADDRESS as DEV_ADDR,
 };
 use mcp794xx::{
-    Alarm, AlarmDateTime, AlarmMatching, AlarmOutputPinPolarity, DateTimeAccess, Error, Hours,
-    NaiveDate,
+    Alarm, AlarmConfig, AlarmDateTime, AlarmMatching, AlarmOutputPinPolarity, DateTime, Error,
+    Hours,
 };
 
 macro_rules! invalid_dt_test {
@@ -42,10 +42,10 @@ macro_rules! call_set_alarm_test {
     $(, $value:expr)*) => {
         #[test]
         fn $name() {
-            let dt = NaiveDate::from_ymd_opt(2019, 11, 13).expect("Invalid date.").and_hms_opt(23,59, 58).expect("Invalid time.");
+            let dt = DateTime::new(2019, 11, 13, 23, 59, 58).expect("Invalid date/time.");
             let trans = $transactions;
             let mut dev = $create_method(&trans);
-            dev.set_datetime(&dt).unwrap();
+            dev.write_datetime(&dt).unwrap();
             dev.set_alarm($($value),*).unwrap();
             $destroy_method(dev);
         }
@@ -356,6 +356,102 @@ get_param_test!(
     [BitFlags::ALMIF]
 );
 
+mod get_alarm {
+    use super::*;
+    const ALM0: Alarm = Alarm::Zero;
+    const ALM1: Alarm = Alarm::One;
+
+    const ALM0_CONFIG: AlarmConfig = AlarmConfig {
+        when: AlarmDateTime {
+            month: 11,
+            day: 3,
+            weekday: 4,
+            hour: Hours::H24(15),
+            minute: 32,
+            second: 41,
+        },
+        matching: AlarmMatching::AllMatch,
+        polarity: AlarmOutputPinPolarity::High,
+        is_enabled: true,
+    };
+    for_all_ics!(
+        alm0,
+        get_test,
+        get_alarm,
+        [
+            I2cTrans::write_read(
+                DEV_ADDR,
+                vec![Register::ALM0SEC],
+                vec![
+                    0b0100_0001,
+                    0b0011_0010,
+                    0b0001_0101,
+                    0b0111_0100 | BitFlags::ALMPOL | BitFlags::ALMIF,
+                    0b0000_0011,
+                    0b0001_0001
+                ]
+            ),
+            I2cTrans::write_read(DEV_ADDR, vec![Register::CONTROL], vec![BitFlags::ALM0EN])
+        ],
+        ALM0_CONFIG,
+        ALM0
+    );
+
+    const ALM1_CONFIG: AlarmConfig = AlarmConfig {
+        when: AlarmDateTime {
+            month: 12,
+            day: 31,
+            weekday: 7,
+            hour: Hours::PM(3),
+            minute: 0,
+            second: 0,
+        },
+        matching: AlarmMatching::MinutesMatch,
+        polarity: AlarmOutputPinPolarity::Low,
+        is_enabled: false,
+    };
+    for_all_ics!(
+        alm1_polarity_is_read_from_alm0,
+        get_test,
+        get_alarm,
+        [
+            I2cTrans::write_read(
+                DEV_ADDR,
+                vec![Register::ALM1SEC],
+                vec![
+                    0,
+                    0,
+                    0b0110_0011,
+                    0b0001_0111 | BitFlags::ALMPOL,
+                    0b0011_0001,
+                    0b0001_0010
+                ]
+            ),
+            I2cTrans::write_read(DEV_ADDR, vec![Register::ALM0WKDAY], vec![0]),
+            I2cTrans::write_read(DEV_ADDR, vec![Register::CONTROL], vec![BitFlags::ALM0EN])
+        ],
+        ALM1_CONFIG,
+        ALM1
+    );
+
+    #[test]
+    fn reserved_matching_returns_error() {
+        let mut dev = new_mcp79410(&[
+            I2cTrans::write_read(
+                DEV_ADDR,
+                vec![Register::ALM0SEC],
+                vec![0, 0, 0, 0b0101_0001, 1, 1],
+            ),
+            I2cTrans::write_read(DEV_ADDR, vec![Register::CONTROL], vec![0]),
+        ]);
+        match dev.get_alarm(Alarm::Zero) {
+            Err(Error::InvalidAlarmData(5)) => (),
+            _ => panic!("InvalidAlarmData error not returned."),
+        }
+        destroy_mcp79410(dev);
+    }
+}
+
 macro_rules! call_update_method {
     ($name:ident, $method:ident, $arg:expr, $register:ident, $read:expr, $value:expr) => {
         for_all_ics!(
@@ -387,3 +483,285 @@ call_update_method!(
     BitFlags::ALMIF,
     0
 );
+
+#[cfg(feature = "rtcc")]
+mod set_alarm_at {
+    use super::*;
+    use mcp794xx::{NaiveDate, NaiveDateTime};
+    const ALM0: Alarm = Alarm::Zero;
+    const MATCH: AlarmMatching = AlarmMatching::AllMatch;
+    const POL: AlarmOutputPinPolarity = AlarmOutputPinPolarity::Low;
+
+    fn dt(year: i32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(year, 11, 13)
+            .unwrap()
+            .and_hms_opt(15, 32, 41)
+            .unwrap()
+    }
+
+    for_all_ics!(
+        in_12h_mode,
+        call_test,
+        set_alarm_at,
+        [I2cTrans::write(
+            DEV_ADDR,
+            vec![
+                Register::ALM0SEC,
+                0b0100_0001,
+                0b0011_0010,
+                0b0110_0011,
+                0b0111_0100,
+                0b0001_0011,
+                0b0001_0001
+            ]
+        )],
+        ALM0,
+        (&dt(2019)),
+        MATCH,
+        POL
+    );
+
+    #[test]
+    fn in_24h_mode() {
+        use mcp794xx::Rtcc;
+        let mut dev = new_mcp7940n(&[
+            I2cTrans::write(DEV_ADDR, vec![Register::HOURS, 0b0001_0000]),
+            I2cTrans::write(
+                DEV_ADDR,
+                vec![
+                    Register::ALM1SEC,
+                    0b0100_0001,
+                    0b0011_0010,
+                    0b0001_0101,
+                    0b0100_0100,
+                    0b0001_0011,
+                    0b0001_0001,
+                ],
+            ),
+        ]);
+        dev.set_hours(Hours::H24(10)).unwrap();
+        dev.set_alarm_at(
+            Alarm::One,
+            &dt(2019),
+            AlarmMatching::DayMatches,
+            AlarmOutputPinPolarity::Low,
+        )
+        .unwrap();
+        destroy_mcp7940n(dev);
+    }
+
+    set_invalid_param_test!(
+        year_too_small,
+        set_alarm_at,
+        Alarm::Zero,
+        &dt(1999),
+        AlarmMatching::AllMatch,
+        AlarmOutputPinPolarity::Low
+    );
+    set_invalid_param_test!(
+        year_too_big,
+        set_alarm_at,
+        Alarm::Zero,
+        &dt(2100),
+        AlarmMatching::AllMatch,
+        AlarmOutputPinPolarity::Low
+    );
+}
+
+#[cfg(feature = "rtcc")]
+mod set_weekly_alarm {
+    use super::*;
+    use mcp794xx::Weekday;
+    const ALM0: Alarm = Alarm::Zero;
+    const POL: AlarmOutputPinPolarity = AlarmOutputPinPolarity::Low;
+
+    for_all_ics!(
+        wednesday,
+        call_test,
+        set_weekly_alarm,
+        [I2cTrans::write(
+            DEV_ADDR,
+            vec![
+                Register::ALM0SEC,
+                0,
+                0,
+                0b0101_0010,
+                0b0011_0100,
+                0b0000_0001,
+                0b0000_0001
+            ]
+        )],
+        ALM0,
+        (Weekday::Wed),
+        POL
+    );
+
+    for_all_ics!(
+        sunday,
+        call_test,
+        set_weekly_alarm,
+        [I2cTrans::write(
+            DEV_ADDR,
+            vec![
+                Register::ALM0SEC,
+                0,
+                0,
+                0b0101_0010,
+                0b0011_0001,
+                0b0000_0001,
+                0b0000_0001
+            ]
+        )],
+        ALM0,
+        (Weekday::Sun),
+        POL
+    );
+}
+
+mod set_alarm_in {
+    use super::*;
+    use core::time::Duration;
+    use mcp794xx::DateTime;
+
+    fn read_datetime() -> I2cTrans {
+        // 2019-11-13 23:59:58, Wednesday
+        I2cTrans::write_read(
+            DEV_ADDR,
+            vec![Register::SECONDS],
+            vec![
+                0b0101_1000,
+                0b0101_1001,
+                0b0010_0011,
+                0b0000_0100,
+                0b0001_0011,
+                0b0001_0001,
+                0b0001_1001,
+            ],
+        )
+    }
+
+    fn seconds_until(year: u16, month: u8, day: u8, hour: u8, minute: u8, second: u8) -> Duration {
+        let now = DateTime::new(2019, 11, 13, 23, 59, 58).unwrap();
+        let target = DateTime::new(year, month, day, hour, minute, second).unwrap();
+        Duration::from_secs((target.timestamp() - now.timestamp()) as u64)
+    }
+
+    #[test]
+    fn within_a_minute_matches_all() {
+        let mut dev = new_mcp7940n(&[
+            read_datetime(),
+            I2cTrans::write(
+                DEV_ADDR,
+                vec![
+                    Register::ALM0SEC,
+                    0b0010_1000,
+                    0,
+                    0b0101_0010,
+                    0b0111_0101,
+                    0b0001_0100,
+                    0b0001_0001,
+                ],
+            ),
+            I2cTrans::write(
+                DEV_ADDR,
+                vec![Register::CONTROL, BitFlags::OUT | BitFlags::ALM0EN],
+            ),
+        ]);
+        let when = dev
+            .set_alarm_in(
+                Alarm::Zero,
+                Duration::from_millis(30_500),
+                AlarmOutputPinPolarity::Low,
+            )
+            .unwrap();
+        assert_eq!(DateTime::new(2019, 11, 14, 0, 0, 28).unwrap(), when);
+        destroy_mcp7940n(dev);
+    }
+
+    #[test]
+    fn beyond_a_minute_matches_all() {
+        let mut dev = new_mcp7940n(&[
+            read_datetime(),
+            I2cTrans::write(
+                DEV_ADDR,
+                vec![
+                    Register::ALM1SEC,
+                    0b0101_1000,
+                    0b0101_1001,
+                    0b0100_0001,
+                    0b0111_0110,
+                    0b0001_0101,
+                    0b0001_0001,
+                ],
+            ),
+            I2cTrans::write(
+                DEV_ADDR,
+                vec![Register::CONTROL, BitFlags::OUT | BitFlags::ALM1EN],
+            ),
+        ]);
+        let when = dev
+            .set_alarm_in(
+                Alarm::One,
+                Duration::from_secs(26 * 3600),
+                AlarmOutputPinPolarity::Low,
+            )
+            .unwrap();
+        assert_eq!(DateTime::new(2019, 11, 15, 1, 59, 58).unwrap(), when);
+        destroy_mcp7940n(dev);
+    }
+
+    #[test]
+    fn carries_into_next_year() {
+        let mut dev = new_mcp7940n(&[
+            read_datetime(),
+            I2cTrans::write(
+                DEV_ADDR,
+                vec![
+                    Register::ALM0SEC,
+                    0,
+                    0,
+                    0b0101_0010,
+                    0b0111_0100,
+                    0b0000_0001,
+                    0b0000_0001,
+                ],
+            ),
+            I2cTrans::write(
+                DEV_ADDR,
+                vec![Register::CONTROL, BitFlags::OUT | BitFlags::ALM0EN],
+            ),
+        ]);
+        let duration = seconds_until(2020, 1, 1, 0, 0, 0);
+        let when = dev
+            .set_alarm_in(Alarm::Zero, duration, AlarmOutputPinPolarity::Low)
+            .unwrap();
+        assert_eq!(DateTime::new(2020, 1, 1, 0, 0, 0).unwrap(), when);
+        destroy_mcp7940n(dev);
+    }
+
+    fn assert_cannot_set(duration: Duration) {
+        let mut dev = new_mcp7940n(&[read_datetime()]);
+        assert_invalid_input_data!(dev.set_alarm_in(
+            Alarm::Zero,
+            duration,
+            AlarmOutputPinPolarity::Low
+        ));
+        destroy_mcp7940n(dev);
+    }
+
+    #[test]
+    fn cannot_set_shorter_than_a_second() {
+        assert_cannot_set(Duration::from_millis(999));
+    }
+
+    #[test]
+    fn cannot_set_ambiguous() {
+        // 2024-11-13 is also a Wednesday
+        assert_cannot_set(seconds_until(2030, 11, 13, 23, 59, 58));
+    }
+
+    #[test]
+    fn cannot_set_after_2099() {
+        assert_cannot_set(seconds_until(2099, 12, 31, 23, 59, 59) + Duration::from_secs(1));
+    }
+}
@@ -0,0 +1,473 @@
+#![cfg(feature = "async")]
+use core::time::Duration;
+use embassy_futures::block_on;
+use embedded_hal::i2c::{ErrorKind, NoAcknowledgeSource};
+use embedded_hal_mock::eh1::delay::NoopDelay;
+use embedded_hal_mock::eh1::i2c::{Mock as I2cMock, Transaction as I2cTrans};
+use mcp794xx::{
+    ic, interface, Alarm, AlarmDateTime, AlarmMatching, AlarmOutputPinPolarity, Cron, DateTime,
+    Error, Hours, Mcp794xxAsync,
+};
+#[cfg(feature = "rtcc")]
+use mcp794xx::{NaiveDate, Weekday};
+mod common;
+use crate::common::{BitFlags, Register, DEVICE_ADDRESS as DEV_ADDR, EEPROM_ADDRESS};
+
+type Dev<IC> = Mcp794xxAsync<interface::AsyncI2cInterface<I2cMock>, IC>;
+
+fn new_mcp7940n(transactions: &[I2cTrans]) -> Dev<ic::Mcp7940n> {
+    Mcp794xxAsync::new_mcp7940n(I2cMock::new(transactions))
+}
+
+fn new_mcp79410(transactions: &[I2cTrans]) -> Dev<ic::Mcp79410> {
+    Mcp794xxAsync::new_mcp79410(I2cMock::new(transactions))
+}
+
+fn new_mcp79412(transactions: &[I2cTrans]) -> Dev<ic::Mcp79412> {
+    Mcp794xxAsync::new_mcp79412(I2cMock::new(transactions))
+}
+
+#[test]
+fn can_enable() {
+    let mut dev = new_mcp7940n(&[
+        I2cTrans::write_read(DEV_ADDR, vec![Register::SECONDS], vec![0]),
+        I2cTrans::write(DEV_ADDR, vec![Register::SECONDS, BitFlags::ST]),
+    ]);
+    block_on(dev.enable()).unwrap();
+    dev.destroy().done();
+}
+
+#[cfg(feature = "rtcc")]
+#[test]
+fn can_get_datetime() {
+    let mut dev = new_mcp7940n(&[I2cTrans::write_read(
+        DEV_ADDR,
+        vec![Register::SECONDS],
+        vec![
+            0b0101_1000,
+            0b0101_1001,
+            0b0010_0011,
+            0b0000_0010,
+            0b0001_0011,
+            0b0000_1000,
+            0b0001_1000,
+        ],
+    )]);
+    let dt = block_on(dev.datetime()).unwrap();
+    assert_eq!(
+        NaiveDate::from_ymd_opt(2018, 8, 13)
+            .unwrap()
+            .and_hms_opt(23, 59, 58)
+            .unwrap(),
+        dt
+    );
+    dev.destroy().done();
+}
+
+#[test]
+fn can_get_consistent_datetime() {
+    let data = vec![1, 0, 0, 6, 1, 0x12, 0x23];
+    let mut dev = new_mcp7940n(&[
+        I2cTrans::write_read(DEV_ADDR, vec![Register::SECONDS], data.clone()),
+        I2cTrans::write_read(DEV_ADDR, vec![Register::SECONDS], data),
+    ]);
+    let datetime = DateTime::new(2023, 12, 1, 0, 0, 1).unwrap();
+    assert_eq!(datetime, block_on(dev.consistent_datetime()).unwrap());
+    dev.destroy().done();
+}
+
+#[cfg(feature = "rtcc")]
+#[test]
+fn can_set_datetime() {
+    let mut dev = new_mcp7940n(&[I2cTrans::write(
+        DEV_ADDR,
+        vec![
+            Register::SECONDS,
+            0b0101_1000,
+            0b0101_1001,
+            0b0010_0011,
+            0b0000_0010,
+            0b0001_0011,
+            0b0000_1000,
+            0b0001_1000,
+        ],
+    )]);
+    let dt = NaiveDate::from_ymd_opt(2018, 8, 13)
+        .unwrap()
+        .and_hms_opt(23, 59, 58)
+        .unwrap();
+    block_on(dev.set_datetime(&dt)).unwrap();
+    dev.destroy().done();
+}
+
+#[test]
+fn can_set_timestamp() {
+    let mut dev = new_mcp7940n(&[I2cTrans::write(
+        DEV_ADDR,
+        vec![Register::SECONDS, 0x58, 0x59, 0x23, 2, 0x13, 8, 0x18],
+    )]);
+    block_on(dev.set_timestamp(1_534_204_798)).unwrap();
+    dev.destroy().done();
+}
+
+#[test]
+fn can_set_datetime_safely() {
+    let mut dev = new_mcp7940n(&[
+        I2cTrans::write(DEV_ADDR, vec![Register::SECONDS, 0]),
+        I2cTrans::write_read(DEV_ADDR, vec![Register::WEEKDAY], vec![0]),
+        I2cTrans::write(DEV_ADDR, vec![Register::SECONDS, 0, 0, 0, 6, 1, 0x12, 0x23]),
+    ]);
+    let datetime = DateTime::new(2023, 12, 1, 0, 0, 0).unwrap();
+    block_on(dev.set_datetime_safely(&datetime, &mut NoopDelay::new())).unwrap();
+    dev.destroy().done();
+}
+
+#[cfg(feature = "rtcc")]
+#[test]
+fn cannot_set_invalid_year() {
+    let mut dev = new_mcp7940n(&[]);
+    let dt = NaiveDate::from_ymd_opt(2100, 1, 1)
+        .unwrap()
+        .and_hms_opt(0, 0, 0)
+        .unwrap();
+    assert_invalid_input_data!(block_on(dev.set_datetime(&dt)));
+    dev.destroy().done();
+}
+
+#[test]
+fn can_set_alarm() {
+    let mut dev = new_mcp7940n(&[I2cTrans::write(
+        DEV_ADDR,
+        vec![
+            Register::ALM0SEC,
+            0b0100_0001,
+            0b0011_0010,
+            0b0100_0011,
+            0b0000_0100 | 0b0111_0000,
+            0b0000_0011,
+            0b0001_0001,
+        ],
+    )]);
+    let when = AlarmDateTime {
+        month: 11,
+        day: 3,
+        weekday: 4,
+        hour: Hours::AM(3),
+        minute: 32,
+        second: 41,
+    };
+    block_on(dev.set_alarm(
+        Alarm::Zero,
+        when,
+        AlarmMatching::AllMatch,
+        AlarmOutputPinPolarity::Low,
+    ))
+    .unwrap();
+    dev.destroy().done();
+}
+
+#[cfg(feature = "rtcc")]
+#[test]
+fn can_set_alarm_at() {
+    let mut dev = new_mcp7940n(&[I2cTrans::write(
+        DEV_ADDR,
+        vec![
+            Register::ALM0SEC,
+            0b0100_0001,
+            0b0011_0010,
+            0b0110_0011,
+            0b0111_0100,
+            0b0001_0011,
+            0b0001_0001,
+        ],
+    )]);
+    let datetime = NaiveDate::from_ymd_opt(2019, 11, 13)
+        .unwrap()
+        .and_hms_opt(15, 32, 41)
+        .unwrap();
+    block_on(dev.set_alarm_at(
+        Alarm::Zero,
+        &datetime,
+        AlarmMatching::AllMatch,
+        AlarmOutputPinPolarity::Low,
+    ))
+    .unwrap();
+    dev.destroy().done();
+}
+
+#[cfg(feature = "rtcc")]
+#[test]
+fn can_set_weekly_alarm() {
+    let mut dev = new_mcp7940n(&[I2cTrans::write(
+        DEV_ADDR,
+        vec![
+            Register::ALM0SEC,
+            0,
+            0,
+            0b0101_0010,
+            0b0011_0100,
+            0b0000_0001,
+            0b0000_0001,
+        ],
+    )]);
+    block_on(dev.set_weekly_alarm(Alarm::Zero, Weekday::Wed, AlarmOutputPinPolarity::Low)).unwrap();
+    dev.destroy().done();
+}
+
+#[test]
+fn can_set_alarm_in() {
+    let mut dev = new_mcp7940n(&[
+        I2cTrans::write_read(
+            DEV_ADDR,
+            vec![Register::SECONDS],
+            vec![
+                0b0101_1000,
+                0b0101_1001,
+                0b0010_0011,
+                0b0000_0100,
+                0b0001_0011,
+                0b0001_0001,
+                0b0001_1001,
+            ],
+        ),
+        I2cTrans::write(
+            DEV_ADDR,
+            vec![
+                Register::ALM0SEC,
+                0b0010_1000,
+                0,
+                0b0101_0010,
+                0b0111_0101,
+                0b0001_0100,
+                0b0001_0001,
+            ],
+        ),
+        I2cTrans::write(
+            DEV_ADDR,
+            vec![Register::CONTROL, BitFlags::OUT | BitFlags::ALM0EN],
+        ),
+    ]);
+    let when = block_on(dev.set_alarm_in(
+        Alarm::Zero,
+        Duration::from_secs(30),
+        AlarmOutputPinPolarity::Low,
+    ))
+    .unwrap();
+    assert_eq!(DateTime::new(2019, 11, 14, 0, 0, 28).unwrap(), when);
+    dev.destroy().done();
+}
+
+#[test]
+fn can_set_cron_alarm() {
+    let mut dev = new_mcp7940n(&[
+        I2cTrans::write_read(
+            DEV_ADDR,
+            vec![Register::SECONDS],
+            vec![
+                0b0101_1000,
+                0b0101_1001,
+                0b0010_0011,
+                0b0000_0100,
+                0b0001_0011,
+                0b0001_0001,
+                0b0001_1001,
+            ],
+        ),
+        I2cTrans::write(
+            DEV_ADDR,
+            vec![
+                Register::ALM1SEC,
+                0,
+                0,
+                0b0101_0010,
+                0b0111_0101,
+                0b0001_0100,
+                0b0001_0001,
+            ],
+        ),
+        I2cTrans::write(
+            DEV_ADDR,
+            vec![Register::CONTROL, BitFlags::OUT | BitFlags::ALM1EN],
+        ),
+    ]);
+    let cron = Cron::parse("0 0 * * *").unwrap();
+    let next =
+        block_on(dev.set_cron_alarm(Alarm::One, &cron, AlarmOutputPinPolarity::Low)).unwrap();
+    assert_eq!(DateTime::new(2019, 11, 14, 0, 0, 0).unwrap(), next);
+    dev.destroy().done();
+}
+
+#[test]
+fn can_get_status() {
+    let mut data = vec![0; 0x15];
+    data[Register::SECONDS as usize] = BitFlags::ST;
+    data[Register::OSCTRIM as usize] = 0b0000_0011;
+    data[Register::ALM0WKDAY as usize] = BitFlags::ALMIF;
+    let mut dev = new_mcp7940n(&[I2cTrans::write_read(
+        DEV_ADDR,
+        vec![Register::SECONDS],
+        data,
+    )]);
+    let status = block_on(dev.status()).unwrap();
+    assert!(status.is_enabled);
+    assert!(!status.is_oscillator_running);
+    assert!(status.has_alarm0_matched);
+    assert_eq!(3, status.trimming);
+    dev.destroy().done();
+}
+
+#[test]
+fn can_get_alarm() {
+    let mut dev = new_mcp7940n(&[
+        I2cTrans::write_read(
+            DEV_ADDR,
+            vec![Register::ALM0SEC],
+            vec![
+                0b0100_0001,
+                0b0011_0010,
+                0b0100_0011,
+                0b0000_0100 | 0b0111_0000,
+                0b0000_0011,
+                0b0001_0001,
+            ],
+        ),
+        I2cTrans::write_read(DEV_ADDR, vec![Register::CONTROL], vec![0]),
+    ]);
+    let config = block_on(dev.get_alarm(Alarm::Zero)).unwrap();
+    let when = AlarmDateTime {
+        month: 11,
+        day: 3,
+        weekday: 4,
+        hour: Hours::AM(3),
+        minute: 32,
+        second: 41,
+    };
+    assert_eq!(when, config.when);
+    assert_eq!(AlarmMatching::AllMatch, config.matching);
+    assert_eq!(AlarmOutputPinPolarity::Low, config.polarity);
+    assert!(!config.is_enabled);
+    dev.destroy().done();
+}
+
+#[test]
+fn can_check_alarm_matched() {
+    let mut dev = new_mcp7940n(&[I2cTrans::write_read(
+        DEV_ADDR,
+        vec![Register::ALM1WKDAY],
+        vec![BitFlags::ALMIF],
+    )]);
+    assert!(block_on(dev.has_alarm_matched(Alarm::One)).unwrap());
+    dev.destroy().done();
+}
+
+#[test]
+fn can_write_sram_data() {
+    let mut dev = new_mcp7940n(&[I2cTrans::write(DEV_ADDR, vec![0x20, 1, 2, 3])]);
+    block_on(dev.write_sram_data(0x20, &[1, 2, 3])).unwrap();
+    dev.destroy().done();
+}
+
+#[test]
+fn cannot_read_sram_out_of_range() {
+    let mut dev = new_mcp7940n(&[]);
+    assert_invalid_input_data!(block_on(dev.read_sram_data(0x5F, &mut [0; 2])));
+    dev.destroy().done();
+}
+
+#[test]
+fn can_check_power_failed() {
+    let mut dev = new_mcp7940n(&[I2cTrans::write_read(
+        DEV_ADDR,
+        vec![Register::WEEKDAY],
+        vec![BitFlags::PWRFAIL],
+    )]);
+    assert!(block_on(dev.has_power_failed()).unwrap());
+    dev.destroy().done();
+}
+
+#[test]
+fn can_get_power_outage() {
+    let mut dev = new_mcp7940n(&[
+        I2cTrans::write_read(
+            DEV_ADDR,
+            vec![Register::SECONDS],
+            vec![0, 0, 0x12, 3, 5, 3, 0x24],
+        ),
+        I2cTrans::write_read(
+            DEV_ADDR,
+            vec![Register::PWRDNMIN],
+            vec![0, 0x10, 0x04, 0x03, 0x30, 0x10, 0x04, 0x03],
+        ),
+    ]);
+    let outage = block_on(dev.get_power_outage()).unwrap();
+    assert_eq!(DateTime::new(2024, 3, 4, 10, 0, 0), Some(outage.power_down));
+    assert_eq!(DateTime::new(2024, 3, 4, 10, 30, 0), Some(outage.power_up));
+    dev.destroy().done();
+}
+
+#[test]
+fn can_read_eeprom_data() {
+    let mut dev = new_mcp79410(&[I2cTrans::write_read(
+        EEPROM_ADDRESS,
+        vec![0x10],
+        vec![1, 2, 3],
+    )]);
+    let mut data = [0; 3];
+    block_on(dev.read_eeprom_data(0x10, &mut data)).unwrap();
+    assert_eq!([1, 2, 3], data);
+    dev.destroy().done();
+}
+
+#[test]
+fn can_write_eeprom_data_across_pages() {
+    let mut dev = new_mcp79410(&[
+        I2cTrans::write(EEPROM_ADDRESS, vec![0x06, 1, 2]),
+        I2cTrans::write(EEPROM_ADDRESS, vec![])
+            .with_error(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address)),
+        I2cTrans::write(EEPROM_ADDRESS, vec![]),
+        I2cTrans::write(EEPROM_ADDRESS, vec![0x08, 3]),
+        I2cTrans::write(EEPROM_ADDRESS, vec![]),
+    ]);
+    block_on(dev.write_eeprom_data(0x06, &[1, 2, 3])).unwrap();
+    dev.destroy().done();
+}
+
+#[test]
+fn can_write_protected_eeprom_byte() {
+    let mut dev = new_mcp79410(&[
+        I2cTrans::write(DEV_ADDR, vec![0x09, 0x55]),
+        I2cTrans::write(DEV_ADDR, vec![0x09, 0xAA]),
+        I2cTrans::write(EEPROM_ADDRESS, vec![0xF0, 15]),
+        I2cTrans::write(EEPROM_ADDRESS, vec![]),
+    ]);
+    block_on(dev.write_protected_eeprom_byte(0xF0, 15)).unwrap();
+    dev.destroy().done();
+}
+
+#[test]
+fn can_read_eui64() {
+    let mut dev = new_mcp79412(&[I2cTrans::write_read(
+        EEPROM_ADDRESS,
+        vec![0xF0],
+        vec![1, 2, 3, 4, 5, 6, 7, 8],
+    )]);
+    assert_eq!(
+        [1, 2, 3, 4, 5, 6, 7, 8],
+        block_on(dev.read_eui64()).unwrap()
+    );
+    dev.destroy().done();
+}
+
+#[test]
+fn can_dump_registers() {
+    let mut registers = [0; 0x20];
+    registers[0] = BitFlags::ST;
+    let mut dev = new_mcp79410(&[I2cTrans::write_read(
+        DEV_ADDR,
+        vec![Register::SECONDS],
+        registers.to_vec(),
+    )]);
+    let dump = block_on(dev.dump_registers()).unwrap();
+    assert_eq!(&registers, dump.registers());
+    dev.destroy().done();
+}
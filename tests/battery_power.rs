@@ -1,5 +1,7 @@
+use core::time::Duration;
+use embedded_hal::i2c::ErrorKind;
 use embedded_hal_mock::eh1::i2c::Transaction as I2cTrans;
-use mcp794xx::{Hours, PowerFailDateTime};
+use mcp794xx::{DateTime, Error, Hours, PowerFailDateTime, PowerOutage};
 mod common;
 use crate::common::{
     destroy_mcp79400, destroy_mcp79401, destroy_mcp79402, destroy_mcp7940n, destroy_mcp79410,
@@ -87,3 +89,102 @@ for_all_ics_with_bat_power!(
     )],
     false
 );
+
+mod get_power_outage {
+    use super::*;
+
+    fn new_datetime(year: u16, month: u8, day: u8, hour: u8, minute: u8) -> DateTime {
+        DateTime::new(year, month, day, hour, minute, 0).unwrap()
+    }
+
+    // now: 2024-01-01 00:20:30, power down: 12-31 23:50, power up: 01-01 00:10
+    fn transactions() -> [I2cTrans; 2] {
+        [
+            I2cTrans::write_read(
+                DEV_ADDR,
+                vec![Register::SECONDS],
+                vec![BitFlags::ST | 0x30, 0x20, 0, 2, 1, 1, 0x24],
+            ),
+            I2cTrans::write_read(
+                DEV_ADDR,
+                vec![Register::PWRDNMIN],
+                vec![0x50, 0x23, 0x31, 0x12, 0x10, 0, 1, 0b0010_0001],
+            ),
+        ]
+    }
+
+    fn outage() -> PowerOutage {
+        PowerOutage {
+            power_down: new_datetime(2023, 12, 31, 23, 50),
+            power_up: new_datetime(2024, 1, 1, 0, 10),
+        }
+    }
+
+    for_all_ics_with_bat_power!(
+        can_get_outage_across_new_year,
+        get_test,
+        get_power_outage,
+        { transactions() },
+        { outage() }
+    );
+
+    #[test]
+    fn can_get_duration() {
+        assert_eq!(Duration::from_secs(20 * 60), outage().duration());
+    }
+
+    fn get_outage(now: [u8; 7], timestamps: [u8; 8]) -> Result<PowerOutage, Error<ErrorKind>> {
+        let mut dev = new_mcp7940n(&[
+            I2cTrans::write_read(DEV_ADDR, vec![Register::SECONDS], now.to_vec()),
+            I2cTrans::write_read(DEV_ADDR, vec![Register::PWRDNMIN], timestamps.to_vec()),
+        ]);
+        let outage = dev.get_power_outage();
+        destroy_mcp7940n(dev);
+        outage
+    }
+
+    #[test]
+    fn power_up_month_after_current_month_is_in_previous_year() {
+        // now: 2024-03-05 12:00, power down: 11-30 08:00 PM, power up: 12-01 09:30 AM
+        let outage = get_outage(
+            [0, 0, 0x12, 3, 5, 3, 0x24],
+            [0, 0b0110_1000, 0x30, 0x11, 0x30, 0b0100_1001, 1, 0x12],
+        )
+        .unwrap();
+        assert_eq!(new_datetime(2023, 11, 30, 20, 0), outage.power_down);
+        assert_eq!(new_datetime(2023, 12, 1, 9, 30), outage.power_up);
+        assert_eq!(Duration::from_secs((13 * 60 + 30) * 60), outage.duration());
+    }
+
+    #[test]
+    fn power_up_month_before_current_month_is_in_current_year() {
+        // now: 2024-03-05 12:00, power down: 01-31 23:00, power up: 02-01 01:00
+        let outage = get_outage(
+            [0, 0, 0x12, 3, 5, 3, 0x24],
+            [0, 0x23, 0x31, 0x01, 0, 0x01, 1, 0x02],
+        )
+        .unwrap();
+        assert_eq!(new_datetime(2024, 1, 31, 23, 0), outage.power_down);
+        assert_eq!(new_datetime(2024, 2, 1, 1, 0), outage.power_up);
+    }
+
+    #[test]
+    fn leap_day_is_in_last_leap_year() {
+        // now: 2025-03-01 00:00, power down: 02-29 10:00, power up: 02-29 11:00
+        let outage = get_outage(
+            [0, 0, 0, 7, 1, 3, 0x25],
+            [0, 0x10, 0x29, 0x02, 0, 0x11, 0x29, 0x02],
+        )
+        .unwrap();
+        assert_eq!(new_datetime(2024, 2, 29, 10, 0), outage.power_down);
+        assert_eq!(new_datetime(2024, 2, 29, 11, 0), outage.power_up);
+    }
+
+    #[test]
+    fn cleared_timestamps_are_invalid() {
+        match get_outage([0, 0, 0, 2, 1, 1, 0x24], [0; 8]) {
+            Err(Error::InvalidDateData(2024, 0, 0)) => (),
+            _ => panic!("InvalidDateData error not returned."),
+        }
+    }
+}
@@ -0,0 +1,105 @@
+use core::time::Duration;
+use embedded_hal_mock::eh1::i2c::Transaction as I2cTrans;
+use mcp794xx::{Error, BOOT_COUNTER_SIZE};
+mod common;
+use crate::common::{
+    destroy_mcp7940n, new_mcp7940n, BitFlags, Register, DEVICE_ADDRESS as DEV_ADDR,
+};
+
+fn read_region(data: [u8; 14]) -> I2cTrans {
+    I2cTrans::write_read(DEV_ADDR, vec![0x20], data.to_vec())
+}
+
+fn read_seconds(seconds: u8) -> I2cTrans {
+    I2cTrans::write_read(DEV_ADDR, vec![Register::SECONDS], vec![seconds])
+}
+
+fn read_started_oscillator() -> I2cTrans {
+    read_seconds(BitFlags::ST | 5)
+}
+
+fn read_datetime(seconds: u8) -> I2cTrans {
+    I2cTrans::write_read(
+        DEV_ADDR,
+        vec![Register::SECONDS],
+        vec![BitFlags::ST | seconds, 0, 0x10, 6, 1, 0x12, 0x23],
+    )
+}
+
+fn write_region(data: [u8; 14]) -> I2cTrans {
+    let mut payload = vec![0x20];
+    payload.extend_from_slice(&data);
+    I2cTrans::write(DEV_ADDR, payload)
+}
+
+const STORED: [u8; 14] = [4, 0, 0, 0, 100, 0, 0, 0, 243, 106, 252, 44, 224, 129];
+const INCREMENTED: [u8; 14] = [5, 0, 0, 0, 100, 0, 0, 0, 37, 107, 252, 44, 91, 94];
+const RESET: [u8; 14] = [1, 0, 0, 0, 0, 0, 0, 0, 37, 107, 252, 44, 212, 95];
+
+#[test]
+fn cannot_use_region_outside_sram() {
+    let mut dev = new_mcp7940n(&[]);
+    assert_invalid_input_data!(dev.init_boot_counter(0x60 - BOOT_COUNTER_SIZE as u8 + 1));
+    assert_invalid_input_data!(dev.init_boot_counter(0x1F));
+    destroy_mcp7940n(dev);
+}
+
+#[test]
+fn increments_boot_count() {
+    let mut dev = new_mcp7940n(&[
+        read_region(STORED),
+        read_started_oscillator(),
+        read_datetime(5),
+        write_region(INCREMENTED),
+    ]);
+    let counter = dev.init_boot_counter(0x20).unwrap();
+    assert_eq!(5, counter.boot_count());
+    assert_eq!(Duration::from_secs(100), counter.uptime());
+    assert!(!counter.was_lost());
+    destroy_mcp7940n(dev);
+}
+
+#[test]
+fn resets_if_crc_does_not_match() {
+    let mut dev = new_mcp7940n(&[
+        read_region([0; 14]),
+        read_started_oscillator(),
+        read_datetime(5),
+        write_region(RESET),
+    ]);
+    let counter = dev.init_boot_counter(0x20).unwrap();
+    assert_eq!(1, counter.boot_count());
+    assert_eq!(Duration::from_secs(0), counter.uptime());
+    assert!(counter.was_lost());
+    destroy_mcp7940n(dev);
+}
+
+#[test]
+fn resets_if_oscillator_was_reset() {
+    let mut dev = new_mcp7940n(&[
+        read_region(STORED),
+        read_seconds(5),
+        read_datetime(5),
+        write_region(RESET),
+    ]);
+    let counter = dev.init_boot_counter(0x20).unwrap();
+    assert_eq!(1, counter.boot_count());
+    assert!(counter.was_lost());
+    destroy_mcp7940n(dev);
+}
+
+#[test]
+fn tick_adds_elapsed_time() {
+    let mut dev = new_mcp7940n(&[
+        read_region(STORED),
+        read_started_oscillator(),
+        read_datetime(5),
+        write_region(INCREMENTED),
+        read_datetime(0x55),
+        write_region([5, 0, 0, 0, 150, 0, 0, 0, 87, 107, 252, 44, 54, 104]),
+    ]);
+    let mut counter = dev.init_boot_counter(0x20).unwrap();
+    assert_eq!(Duration::from_secs(50), counter.tick().unwrap());
+    assert_eq!(Duration::from_secs(150), counter.uptime());
+    destroy_mcp7940n(dev);
+}
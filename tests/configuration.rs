@@ -1,5 +1,7 @@
 use embedded_hal_mock::eh1::i2c::Transaction as I2cTrans;
-use mcp794xx::{OutputPinLevel, SqWFreq};
+#[cfg(feature = "rtcc")]
+use mcp794xx::Rtcc;
+use mcp794xx::{OutputPinLevel, SqWFreq, Status};
 mod common;
 use crate::common::{
     destroy_mcp79400, destroy_mcp79401, destroy_mcp79402, destroy_mcp7940m, destroy_mcp7940n,
@@ -121,3 +123,101 @@ set_param_test!(set_trim_m128, set_trimming, OSCTRIM, -128, [0b1000_0000]);
 set_param_test!(set_trim_m252, set_trimming, OSCTRIM, -127, [0b1111_1111]);
 set_param_test!(set_trim_m4, set_trimming, OSCTRIM, -2, [0b1000_0010]);
 set_param_test!(set_trim_m2, set_trimming, OSCTRIM, -1, [0b1000_0001]);
+
+#[cfg(feature = "rtcc")]
+macro_rules! sync_test {
+    ($name:ident, $create_method:ident, $destroy_method:ident, $weekday:expr) => {
+        #[test]
+        fn $name() {
+            let trans = [
+                I2cTrans::write_read(
+                    DEV_ADDR,
+                    vec![Register::SECONDS],
+                    vec![
+                        BitFlags::ST | 0b0001_0010,
+                        0,
+                        0b0101_0010,
+                        BitFlags::VBATEN | 1,
+                        1,
+                        1,
+                        0,
+                        BitFlags::SQWEN,
+                    ],
+                ),
+                I2cTrans::write_read(DEV_ADDR, vec![Register::ALM0WKDAY], vec![BitFlags::ALMPOL]),
+                I2cTrans::write(
+                    DEV_ADDR,
+                    vec![Register::SECONDS, BitFlags::ST | 0b0001_0011],
+                ),
+                I2cTrans::write(
+                    DEV_ADDR,
+                    vec![Register::WEEKDAY, BitFlags::VBATEN | $weekday],
+                ),
+                I2cTrans::write(
+                    DEV_ADDR,
+                    vec![Register::CONTROL, BitFlags::SQWEN | BitFlags::CRSTRIM],
+                ),
+            ];
+            let mut dev = $create_method(&trans);
+            dev.sync().unwrap();
+            dev.set_seconds(13).unwrap();
+            dev.set_weekday($weekday).unwrap();
+            dev.enable_coarse_trim().unwrap();
+            $destroy_method(dev);
+        }
+    };
+}
+
+#[cfg(feature = "rtcc")]
+for_all_ics!(sync_state, sync_test, 2);
+
+const STATUS: Status = Status {
+    is_enabled: true,
+    is_oscillator_running: true,
+    has_power_failed: true,
+    is_battery_power_enabled: true,
+    is_leap_year: true,
+    has_alarm0_matched: false,
+    has_alarm1_matched: true,
+    is_alarm0_enabled: false,
+    is_alarm1_enabled: true,
+    is_square_wave_enabled: true,
+    is_external_oscillator_enabled: false,
+    is_coarse_trim_enabled: true,
+    output_pin_level: OutputPinLevel::High,
+    trimming: -5,
+};
+
+for_all_ics!(
+    can_get_status,
+    get_test,
+    status,
+    [I2cTrans::write_read(
+        DEV_ADDR,
+        vec![Register::SECONDS],
+        vec![
+            BitFlags::ST | 0b0001_0010,
+            0,
+            0b0101_0010,
+            BitFlags::OSCRUN | BitFlags::PWRFAIL | BitFlags::VBATEN | 1,
+            1,
+            BitFlags::LEAPYEAR | 1,
+            0b0010_0000,
+            BitFlags::OUT | BitFlags::SQWEN | BitFlags::ALM1EN | BitFlags::CRSTRIM,
+            0b1000_0101,
+            0,
+            0,
+            0,
+            0,
+            0b0111_0001,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            BitFlags::ALMIF | 1
+        ]
+    )],
+    STATUS
+);
@@ -0,0 +1,253 @@
+use embedded_hal_mock::eh1::i2c::Transaction as I2cTrans;
+use mcp794xx::{Alarm, AlarmOutputPinPolarity, Cron, CronError, CronField, DateTime, Error};
+mod common;
+use crate::common::{
+    destroy_mcp7940n, new_mcp7940n, BitFlags, Register, DEVICE_ADDRESS as DEV_ADDR,
+};
+
+fn dt(year: u16, month: u8, day: u8, hour: u8, minute: u8) -> DateTime {
+    DateTime::new(year, month, day, hour, minute, 0).unwrap()
+}
+
+fn cron(expression: &str) -> Cron {
+    Cron::parse(expression).unwrap()
+}
+
+mod parse {
+    use super::*;
+
+    #[test]
+    fn can_parse_from_str() {
+        assert_eq!(Ok(cron("*/15 * * * *")), "*/15 * * * *".parse::<Cron>());
+    }
+
+    #[test]
+    fn ignores_surrounding_whitespace() {
+        assert_eq!(cron("0 12 * * *"), cron("  0\t12  *\n* *  "));
+    }
+
+    #[test]
+    fn lists_ranges_and_steps_are_equivalent() {
+        assert_eq!(cron("0,15,30,45 * * * *"), cron("*/15 * * * *"));
+        assert_eq!(cron("0,15,30,45 * * * *"), cron("0-59/15 * * * *"));
+        assert_eq!(cron("5,20,35,50 * * * *"), cron("5/15 * * * *"));
+        assert_eq!(cron("0 9-11 * * *"), cron("0 9,10,11 * * *"));
+    }
+
+    #[test]
+    fn names_are_case_insensitive() {
+        assert_eq!(cron("0 0 * 1-3 1-5"), cron("0 0 * JAN-mar Mon-FRI"));
+    }
+
+    #[test]
+    fn sunday_is_0_and_7() {
+        assert_eq!(cron("0 0 * * 0"), cron("0 0 * * 7"));
+        assert_eq!(cron("0 0 * * 0"), cron("0 0 * * SUN"));
+    }
+
+    fn assert_error(error: CronError, expression: &str) {
+        assert_eq!(Err(error), Cron::parse(expression), "{expression}");
+    }
+
+    #[test]
+    fn wrong_field_count() {
+        assert_error(CronError::FieldCount, "");
+        assert_error(CronError::FieldCount, "* * * *");
+        assert_error(CronError::FieldCount, "0 * * * * *");
+    }
+
+    #[test]
+    fn nicknames() {
+        assert_error(CronError::Nickname, "@daily");
+        assert_error(CronError::Nickname, "@reboot");
+    }
+
+    #[test]
+    fn invalid_values() {
+        assert_error(CronError::InvalidValue(CronField::Minute), "60 * * * *");
+        assert_error(CronError::InvalidValue(CronField::Hour), "0 24 * * *");
+        assert_error(CronError::InvalidValue(CronField::DayOfMonth), "0 0 0 * *");
+        assert_error(CronError::InvalidValue(CronField::DayOfMonth), "0 0 32 * *");
+        assert_error(CronError::InvalidValue(CronField::Month), "0 0 * 13 *");
+        assert_error(CronError::InvalidValue(CronField::Month), "0 0 * MON *");
+        assert_error(CronError::InvalidValue(CronField::DayOfWeek), "0 0 * * 8");
+        assert_error(CronError::InvalidValue(CronField::DayOfWeek), "0 0 * * JAN");
+        assert_error(CronError::InvalidValue(CronField::Minute), "+5 * * * *");
+        assert_error(CronError::InvalidValue(CronField::Minute), "1,,2 * * * *");
+        assert_error(CronError::InvalidValue(CronField::Hour), "0 1- * * *");
+    }
+
+    #[test]
+    fn invalid_ranges() {
+        assert_error(CronError::InvalidRange(CronField::Hour), "0 5-2 * * *");
+        assert_error(CronError::InvalidRange(CronField::Minute), "*/0 * * * *");
+    }
+
+    #[test]
+    fn unsupported_syntax() {
+        assert_error(
+            CronError::UnsupportedSyntax(CronField::DayOfMonth),
+            "0 0 L * *",
+        );
+        assert_error(
+            CronError::UnsupportedSyntax(CronField::DayOfMonth),
+            "0 0 15W * *",
+        );
+        assert_error(
+            CronError::UnsupportedSyntax(CronField::DayOfMonth),
+            "0 0 ? * 1",
+        );
+        assert_error(
+            CronError::UnsupportedSyntax(CronField::DayOfWeek),
+            "0 0 * * 5#3",
+        );
+    }
+}
+
+mod next_after {
+    use super::*;
+
+    #[test]
+    fn every_15_minutes() {
+        let cron = cron("*/15 * * * *");
+        let next = cron.next_after(&DateTime::new(2023, 12, 1, 10, 7, 30).unwrap());
+        assert_eq!(Some(dt(2023, 12, 1, 10, 15)), next);
+        let next = cron.next_after(&dt(2023, 12, 1, 10, 15));
+        assert_eq!(Some(dt(2023, 12, 1, 10, 30)), next);
+        let next = cron.next_after(&dt(2023, 12, 31, 23, 59));
+        assert_eq!(Some(dt(2024, 1, 1, 0, 0)), next);
+    }
+
+    #[test]
+    fn workdays() {
+        let cron = cron("30 7 * * MON-FRI");
+        // Friday
+        let next = cron.next_after(&dt(2023, 12, 1, 8, 0));
+        assert_eq!(Some(dt(2023, 12, 4, 7, 30)), next);
+    }
+
+    #[test]
+    fn first_of_each_month() {
+        let cron = cron("0 0 1 * *");
+        let next = cron.next_after(&dt(2023, 12, 1, 0, 0));
+        assert_eq!(Some(dt(2024, 1, 1, 0, 0)), next);
+    }
+
+    #[test]
+    fn day_of_month_or_day_of_week() {
+        // 13th of the month or Friday
+        let cron = cron("0 0 13 * 5");
+        // Wednesday
+        let next = cron.next_after(&dt(2023, 12, 6, 0, 0));
+        assert_eq!(Some(dt(2023, 12, 8, 0, 0)), next);
+        let next = cron.next_after(&dt(2023, 12, 8, 0, 0));
+        assert_eq!(Some(dt(2023, 12, 13, 0, 0)), next);
+    }
+
+    #[test]
+    fn day_of_month_and_day_of_week_with_star() {
+        // Every other day of the month that is a Friday
+        let cron = cron("0 0 */2 * 5");
+        let next = cron.next_after(&dt(2023, 12, 1, 0, 0));
+        assert_eq!(Some(dt(2023, 12, 15, 0, 0)), next);
+    }
+
+    #[test]
+    fn leap_day() {
+        let cron = cron("0 12 29 2 *");
+        let next = cron.next_after(&dt(2024, 3, 1, 0, 0));
+        assert_eq!(Some(dt(2028, 2, 29, 12, 0)), next);
+    }
+
+    #[test]
+    fn never() {
+        let impossible = cron("0 0 30 2 *");
+        assert_eq!(None, impossible.next_after(&dt(2023, 1, 1, 0, 0)));
+        let daily = cron("0 0 * * *");
+        assert_eq!(None, daily.next_after(&dt(2099, 12, 31, 0, 0)));
+    }
+
+    #[test]
+    fn next_occurrence_matches() {
+        let cron = cron("5,35 */6 1-10 * MON");
+        let mut datetime = dt(2023, 1, 1, 0, 0);
+        for _ in 0..100 {
+            let next = cron.next_after(&datetime).unwrap();
+            assert!(next > datetime);
+            assert!(cron.matches(&next), "{next:?}");
+            datetime = next;
+        }
+    }
+}
+
+#[test]
+fn can_set_cron_alarm() {
+    let mut dev = new_mcp7940n(&[
+        // 2019-11-13 23:59:58, Wednesday
+        I2cTrans::write_read(
+            DEV_ADDR,
+            vec![Register::SECONDS],
+            vec![
+                0b0101_1000,
+                0b0101_1001,
+                0b0010_0011,
+                0b0000_0100,
+                0b0001_0011,
+                0b0001_0001,
+                0b0001_1001,
+            ],
+        ),
+        I2cTrans::write(
+            DEV_ADDR,
+            vec![
+                Register::ALM0SEC,
+                0,
+                0b0011_0000,
+                0b0100_0111,
+                0b0111_0101,
+                0b0001_0100,
+                0b0001_0001,
+            ],
+        ),
+        I2cTrans::write(
+            DEV_ADDR,
+            vec![Register::CONTROL, BitFlags::OUT | BitFlags::ALM0EN],
+        ),
+    ]);
+    let next = dev
+        .set_cron_alarm(
+            Alarm::Zero,
+            &cron("30 7 * * MON-FRI"),
+            AlarmOutputPinPolarity::Low,
+        )
+        .unwrap();
+    assert_eq!(dt(2019, 11, 14, 7, 30), next);
+    destroy_mcp7940n(dev);
+}
+
+#[test]
+fn cannot_set_cron_alarm_without_occurrence() {
+    let mut dev = new_mcp7940n(&[I2cTrans::write_read(
+        DEV_ADDR,
+        vec![Register::SECONDS],
+        vec![
+            0b0101_1000,
+            0b0101_1001,
+            0b0010_0011,
+            0b0000_0100,
+            0b0001_0011,
+            0b0001_0001,
+            0b0001_1001,
+        ],
+    )]);
+    let result = dev.set_cron_alarm(
+        Alarm::Zero,
+        &cron("0 0 30 2 *"),
+        AlarmOutputPinPolarity::Low,
+    );
+    match result {
+        Err(Error::InvalidInputData) => (),
+        _ => panic!("InvalidInputData error not returned."),
+    }
+    destroy_mcp7940n(dev);
+}
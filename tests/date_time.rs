@@ -0,0 +1,163 @@
+use mcp794xx::DateTime;
+
+#[test]
+fn can_create_valid_datetime() {
+    let dt = DateTime::new(2024, 2, 29, 23, 59, 58).unwrap();
+    assert_eq!(
+        (2024, 2, 29, 23, 59, 58),
+        (
+            dt.year(),
+            dt.month(),
+            dt.day(),
+            dt.hour(),
+            dt.minute(),
+            dt.second()
+        )
+    );
+}
+
+#[test]
+fn cannot_create_invalid_datetime() {
+    assert_eq!(None, DateTime::new(1999, 12, 31, 0, 0, 0));
+    assert_eq!(None, DateTime::new(2100, 1, 1, 0, 0, 0));
+    assert_eq!(None, DateTime::new(2024, 0, 1, 0, 0, 0));
+    assert_eq!(None, DateTime::new(2024, 13, 1, 0, 0, 0));
+    assert_eq!(None, DateTime::new(2024, 1, 0, 0, 0, 0));
+    assert_eq!(None, DateTime::new(2024, 4, 31, 0, 0, 0));
+    assert_eq!(None, DateTime::new(2023, 2, 29, 0, 0, 0));
+    assert_eq!(None, DateTime::new(2024, 1, 1, 24, 0, 0));
+    assert_eq!(None, DateTime::new(2024, 1, 1, 0, 60, 0));
+    assert_eq!(None, DateTime::new(2024, 1, 1, 0, 0, 60));
+}
+
+#[test]
+fn calculates_weekday_from_sunday() {
+    // Saturday
+    assert_eq!(7, DateTime::new(2000, 1, 1, 0, 0, 0).unwrap().weekday());
+    // Sunday
+    assert_eq!(1, DateTime::new(2000, 1, 2, 0, 0, 0).unwrap().weekday());
+    // Monday
+    assert_eq!(2, DateTime::new(2018, 8, 13, 0, 0, 0).unwrap().weekday());
+    // Thursday
+    assert_eq!(5, DateTime::new(2099, 12, 31, 0, 0, 0).unwrap().weekday());
+}
+
+#[test]
+fn can_convert_timestamp() {
+    let dt = DateTime::new(2018, 8, 13, 23, 59, 58).unwrap();
+    assert_eq!(1_534_204_798, dt.timestamp());
+    assert_eq!(Some(dt), DateTime::from_timestamp(1_534_204_798));
+    assert_eq!(
+        DateTime::new(2000, 1, 1, 0, 0, 0),
+        DateTime::from_timestamp(946_684_800)
+    );
+    assert_eq!(
+        DateTime::new(2099, 12, 31, 23, 59, 59),
+        DateTime::from_timestamp(4_102_444_799)
+    );
+    assert_eq!(None, DateTime::from_timestamp(946_684_799));
+    assert_eq!(None, DateTime::from_timestamp(4_102_444_800));
+}
+
+#[cfg(feature = "rtcc")]
+mod chrono_conversion {
+    use mcp794xx::{DateTime, NaiveDate, NaiveDateTime, OutOfRange};
+
+    #[test]
+    fn matches_naive_datetime() {
+        let mut timestamp = 946_684_800;
+        while timestamp < 4_102_444_800 {
+            let dt = DateTime::from_timestamp(timestamp).unwrap();
+            let naive = NaiveDateTime::from(dt);
+            assert_eq!(timestamp, naive.and_utc().timestamp());
+            assert_eq!(dt.weekday(), chrono_weekday_from_sunday(&naive), "{naive}");
+            assert_eq!(Ok(dt), DateTime::try_from(naive));
+            timestamp += 86_400 * 7 + 3_661;
+        }
+    }
+
+    fn chrono_weekday_from_sunday(datetime: &NaiveDateTime) -> u8 {
+        use mcp794xx::Datelike;
+        datetime.weekday().number_from_sunday() as u8
+    }
+
+    #[test]
+    fn cannot_convert_out_of_range_naive_datetime() {
+        let naive = NaiveDate::from_ymd_opt(2100, 1, 1)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap();
+        assert_eq!(Err(OutOfRange), DateTime::try_from(naive));
+    }
+}
+
+#[cfg(feature = "time")]
+mod time_conversion {
+    use mcp794xx::{DateTime, OutOfRange};
+    use time::{Date, Month, PrimitiveDateTime, Time};
+
+    fn primitive(year: i32, month: Month, day: u8, h: u8, min: u8, s: u8) -> PrimitiveDateTime {
+        PrimitiveDateTime::new(
+            Date::from_calendar_date(year, month, day).unwrap(),
+            Time::from_hms(h, min, s).unwrap(),
+        )
+    }
+
+    #[test]
+    fn can_convert_primitive_datetime() {
+        let dt = DateTime::new(2018, 8, 13, 23, 59, 58).unwrap();
+        let primitive = primitive(2018, Month::August, 13, 23, 59, 58);
+        assert_eq!(primitive, PrimitiveDateTime::from(dt));
+        assert_eq!(Ok(dt), DateTime::try_from(primitive));
+    }
+
+    #[test]
+    fn discards_fractions_of_second() {
+        let primitive = primitive(2018, Month::August, 13, 23, 59, 58)
+            .replace_nanosecond(500_000_000)
+            .unwrap();
+        assert_eq!(
+            DateTime::new(2018, 8, 13, 23, 59, 58).ok_or(OutOfRange),
+            DateTime::try_from(primitive)
+        );
+    }
+
+    #[test]
+    fn cannot_convert_out_of_range_primitive_datetime() {
+        let before = primitive(1999, Month::December, 31, 23, 59, 59);
+        let after = primitive(2100, Month::January, 1, 0, 0, 0);
+        assert_eq!(Err(OutOfRange), DateTime::try_from(before));
+        assert_eq!(Err(OutOfRange), DateTime::try_from(after));
+    }
+}
+
+#[cfg(feature = "jiff")]
+mod jiff_conversion {
+    use jiff::civil;
+    use mcp794xx::{DateTime, OutOfRange};
+
+    #[test]
+    fn can_convert_civil_datetime() {
+        let dt = DateTime::new(2018, 8, 13, 23, 59, 58).unwrap();
+        let civil = civil::date(2018, 8, 13).at(23, 59, 58, 0);
+        assert_eq!(civil, civil::DateTime::from(dt));
+        assert_eq!(Ok(dt), DateTime::try_from(civil));
+    }
+
+    #[test]
+    fn discards_fractions_of_second() {
+        let civil = civil::date(2018, 8, 13).at(23, 59, 58, 500_000_000);
+        assert_eq!(
+            DateTime::new(2018, 8, 13, 23, 59, 58).ok_or(OutOfRange),
+            DateTime::try_from(civil)
+        );
+    }
+
+    #[test]
+    fn cannot_convert_out_of_range_civil_datetime() {
+        let before = civil::date(1999, 12, 31).at(23, 59, 59, 0);
+        let after = civil::date(2100, 1, 1).at(0, 0, 0, 0);
+        assert_eq!(Err(OutOfRange), DateTime::try_from(before));
+        assert_eq!(Err(OutOfRange), DateTime::try_from(after));
+    }
+}
@@ -6,8 +6,11 @@ use crate::common::{
     new_mcp7940m, new_mcp7940n, new_mcp79410, new_mcp79411, new_mcp79412, BitFlags, Register,
     DEVICE_ADDRESS as DEV_ADDR,
 };
-use mcp794xx::{DateTimeAccess, Error, Hours, NaiveDate, NaiveTime, Rtcc};
+use mcp794xx::{DateTime, Error};
+#[cfg(feature = "rtcc")]
+use mcp794xx::{DateTimeAccess, Hours, NaiveDate, NaiveDateTime, NaiveTime, Rtcc};
 
+#[cfg(feature = "rtcc")]
 macro_rules! set_invalid_param_range_test {
     ($name:ident, $method:ident, $too_small_value:expr, $too_big_value:expr) => {
         mod $name {
@@ -18,6 +21,7 @@ macro_rules! set_invalid_param_range_test {
     };
 }
 
+#[cfg(feature = "rtcc")]
 mod seconds {
     use super::*;
     get_param_test!(get, seconds, SECONDS, 12, [18]);
@@ -25,6 +29,7 @@ mod seconds {
     set_invalid_param_test!(invalid, set_seconds, 60);
 }
 
+#[cfg(feature = "rtcc")]
 mod minutes {
     use super::*;
     get_param_test!(get, minutes, MINUTES, 13, [19]);
@@ -32,6 +37,7 @@ mod minutes {
     set_invalid_param_test!(invalid, set_minutes, 60);
 }
 
+#[cfg(feature = "rtcc")]
 mod hours_24h {
     use super::*;
     get_param_test!(get, hours, HOURS, Hours::H24(21), [0b0010_0001]);
@@ -39,6 +45,7 @@ mod hours_24h {
     set_invalid_param_test!(invalid, set_hours, Hours::H24(24));
 }
 
+#[cfg(feature = "rtcc")]
 mod hours_12h_am {
     use super::*;
     get_param_test!(get, hours, HOURS, Hours::AM(12), [0b0101_0010]);
@@ -46,6 +53,7 @@ mod hours_12h_am {
     set_invalid_param_range_test!(invalid, set_hours, Hours::AM(0), Hours::AM(13));
 }
 
+#[cfg(feature = "rtcc")]
 mod hours_12h_pm {
     use super::*;
     get_param_test!(get, hours, HOURS, Hours::PM(12), [0b0111_0010]);
@@ -53,6 +61,7 @@ mod hours_12h_pm {
     set_invalid_param_range_test!(invalid, set_hours, Hours::PM(0), Hours::PM(13));
 }
 
+#[cfg(feature = "rtcc")]
 mod weekday {
     use super::*;
     get_param_test!(get, weekday, WEEKDAY, 5, [5]);
@@ -60,6 +69,7 @@ mod weekday {
     set_invalid_param_range_test!(invalid, set_weekday, 0, 8);
 }
 
+#[cfg(feature = "rtcc")]
 mod day {
     use super::*;
     get_param_test!(get, day, DAY, 23, [0b0010_0011]);
@@ -67,6 +77,7 @@ mod day {
     set_invalid_param_range_test!(invalid, set_day, 0, 32);
 }
 
+#[cfg(feature = "rtcc")]
 mod month {
     use super::*;
     get_param_test!(get, month, MONTH, 12, [0b0001_0010]);
@@ -74,6 +85,7 @@ mod month {
     set_invalid_param_range_test!(invalid, set_month, 0, 13);
 }
 
+#[cfg(feature = "rtcc")]
 mod year {
     use super::*;
     get_param_test!(get, year, YEAR, 2045, [0b0100_0101]);
@@ -81,14 +93,15 @@ mod year {
     set_invalid_param_test!(invalid, set_year, 2100);
 }
 
+#[cfg(feature = "rtcc")]
 mod date {
     use super::*;
     get_param_test!(
         get,
         date,
-        DAY,
+        SECONDS,
         NaiveDate::from_ymd_opt(2018, 8, 13).expect("Invalid date."),
-        [0b0001_0011, 0b0000_1000, 0b0001_1000]
+        [1, 0, 0, 0, 0b0001_0011, 0b0000_1000, 0b0001_1000]
     );
 
     set_param_test!(
@@ -100,6 +113,7 @@ mod date {
     );
 }
 
+#[cfg(feature = "rtcc")]
 mod time {
     use super::*;
     get_param_test!(
@@ -119,6 +133,7 @@ mod time {
     );
 }
 
+#[cfg(feature = "rtcc")]
 mod datetime {
     use super::*;
     get_param_test!(
@@ -177,8 +192,292 @@ mod datetime {
     );
 }
 
+mod native_datetime {
+    use super::*;
+    get_param_test!(
+        get,
+        read_datetime,
+        SECONDS,
+        DateTime::new(2018, 8, 13, 23, 59, 58).unwrap(),
+        [
+            0b0101_1000,
+            0b0101_1001,
+            0b0010_0011,
+            0b0000_0010,
+            0b0001_0011,
+            0b0000_1000,
+            0b0001_1000
+        ]
+    );
+    set_param_test!(
+        set,
+        write_datetime,
+        SECONDS,
+        &DateTime::new(2018, 8, 13, 23, 59, 58).unwrap(),
+        [
+            0b0101_1000,
+            0b0101_1001,
+            0b0010_0011,
+            0b0000_0010,
+            0b0001_0011,
+            0b0000_1000,
+            0b0001_1000
+        ]
+    );
+}
+
+mod timestamp {
+    use super::*;
+    get_param_test!(
+        get,
+        timestamp,
+        SECONDS,
+        1_534_204_798,
+        [
+            0b0101_1000,
+            0b0101_1001,
+            0b0010_0011,
+            0b0000_0010,
+            0b0001_0011,
+            0b0000_1000,
+            0b0001_1000
+        ]
+    );
+    set_param_test!(
+        set,
+        set_timestamp,
+        SECONDS,
+        1_534_204_798,
+        [
+            0b0101_1000,
+            0b0101_1001,
+            0b0010_0011,
+            0b0000_0010,
+            0b0001_0011,
+            0b0000_1000,
+            0b0001_1000
+        ]
+    );
+    set_invalid_param_test!(too_small, set_timestamp, 946_684_799);
+    set_invalid_param_test!(too_big, set_timestamp, 4_102_444_800);
+    set_invalid_param_test!(negative, set_timestamp, -1);
+}
+
+#[cfg(feature = "rtcc")]
+mod consistent_read {
+    use super::*;
+
+    const DATETIME: [u8; 7] = [0, 0, 0, 0b0000_0010, 0b0001_0011, 0b0000_1000, 0b0001_1000];
+    const NEXT: [u8; 7] = [1, 0, 0, 0b0000_0010, 0b0001_0011, 0b0000_1000, 0b0001_1000];
+
+    fn read(data: [u8; 7]) -> I2cTrans {
+        I2cTrans::write_read(DEV_ADDR, vec![Register::SECONDS], data.to_vec())
+    }
+
+    fn datetime(second: u32) -> mcp794xx::NaiveDateTime {
+        NaiveDate::from_ymd_opt(2018, 8, 13)
+            .expect("Invalid date.")
+            .and_hms_opt(0, 0, second)
+            .expect("Invalid time.")
+    }
+
+    #[test]
+    fn datetime_is_read_again_if_seconds_are_zero() {
+        let mut dev = new_mcp7940n(&[read(DATETIME), read(DATETIME)]);
+        assert_eq!(datetime(0), dev.datetime().unwrap());
+        destroy_mcp7940n(dev);
+    }
+
+    #[test]
+    fn datetime_is_read_until_stable() {
+        let mut dev = new_mcp7940n(&[read(DATETIME), read(NEXT), read(NEXT)]);
+        assert_eq!(datetime(1), dev.datetime().unwrap());
+        destroy_mcp7940n(dev);
+    }
+
+    #[test]
+    fn time_is_read_again_if_seconds_are_zero() {
+        let mut dev = new_mcp7940n(&[
+            I2cTrans::write_read(DEV_ADDR, vec![Register::SECONDS], vec![0, 0, 0]),
+            I2cTrans::write_read(DEV_ADDR, vec![Register::SECONDS], vec![1, 0, 0]),
+            I2cTrans::write_read(DEV_ADDR, vec![Register::SECONDS], vec![1, 0, 0]),
+        ]);
+        assert_eq!(datetime(1).time(), dev.time().unwrap());
+        destroy_mcp7940n(dev);
+    }
+
+    #[test]
+    fn date_is_read_again_if_seconds_are_zero() {
+        let mut dev = new_mcp7940n(&[read(DATETIME), read(DATETIME)]);
+        assert_eq!(datetime(0).date(), dev.date().unwrap());
+        destroy_mcp7940n(dev);
+    }
+
+    #[test]
+    fn consistent_datetime_is_always_read_again() {
+        let mut dev = new_mcp7940n(&[read(NEXT), read(NEXT)]);
+        assert_eq!(
+            datetime(1),
+            NaiveDateTime::from(dev.consistent_datetime().unwrap())
+        );
+        destroy_mcp7940n(dev);
+    }
+
+    #[test]
+    fn read_attempts_are_bounded() {
+        let mut data = NEXT;
+        let mut trans = vec![read(DATETIME)];
+        for second in 1..=3 {
+            data[0] = second;
+            trans.push(read(data));
+        }
+        let mut dev = new_mcp7940n(&trans);
+        assert_eq!(
+            datetime(3),
+            NaiveDateTime::from(dev.consistent_datetime().unwrap())
+        );
+        destroy_mcp7940n(dev);
+    }
+}
+
 mod leapyear {
     use super::*;
     get_param_test!(yes, is_leap_year, MONTH, true, [BitFlags::LEAPYEAR]);
     get_param_test!(no, is_leap_year, MONTH, false, [!BitFlags::LEAPYEAR]);
 }
+
+mod safe_set {
+    use super::*;
+    use embedded_hal_mock::eh1::delay::NoopDelay;
+
+    const DATETIME: [u8; 7] = [
+        0b0101_1000,
+        0b0101_1001,
+        0b0010_0011,
+        0b0000_0010,
+        0b0001_0011,
+        0b0000_1000,
+        0b0001_1000,
+    ];
+
+    fn datetime() -> DateTime {
+        DateTime::new(2018, 8, 13, 23, 59, 58).unwrap()
+    }
+
+    fn enable_transactions() -> [I2cTrans; 2] {
+        [
+            I2cTrans::write_read(DEV_ADDR, vec![Register::SECONDS], vec![0]),
+            I2cTrans::write(DEV_ADDR, vec![Register::SECONDS, BitFlags::ST]),
+        ]
+    }
+
+    fn oscrun(value: u8) -> I2cTrans {
+        I2cTrans::write_read(DEV_ADDR, vec![Register::WEEKDAY], vec![value])
+    }
+
+    #[test]
+    fn writes_without_restarting_if_disabled() {
+        let mut payload = vec![Register::SECONDS];
+        payload.extend_from_slice(&DATETIME);
+        let mut dev = new_mcp7940n(&[
+            I2cTrans::write(DEV_ADDR, vec![Register::SECONDS, DATETIME[0]]),
+            oscrun(0),
+            I2cTrans::write(DEV_ADDR, payload),
+        ]);
+        dev.set_datetime_safely(&datetime(), &mut NoopDelay::new())
+            .unwrap();
+        destroy_mcp7940n(dev);
+    }
+
+    #[test]
+    fn stops_and_restarts_oscillator() {
+        let mut payload = vec![Register::SECONDS];
+        payload.extend_from_slice(&DATETIME);
+        let mut trans = enable_transactions().to_vec();
+        trans.extend([
+            I2cTrans::write(DEV_ADDR, vec![Register::SECONDS, DATETIME[0]]),
+            oscrun(BitFlags::OSCRUN),
+            oscrun(0),
+            I2cTrans::write(DEV_ADDR, payload),
+            I2cTrans::write(
+                DEV_ADDR,
+                vec![Register::SECONDS, BitFlags::ST | DATETIME[0]],
+            ),
+            oscrun(0),
+            oscrun(BitFlags::OSCRUN),
+        ]);
+        let mut dev = new_mcp7940n(&trans);
+        dev.enable().unwrap();
+        dev.set_datetime_safely(&datetime(), &mut NoopDelay::new())
+            .unwrap();
+        destroy_mcp7940n(dev);
+    }
+
+    #[cfg(feature = "rtcc")]
+    #[test]
+    fn can_set_time() {
+        let mut trans = enable_transactions().to_vec();
+        trans.extend([
+            I2cTrans::write(DEV_ADDR, vec![Register::SECONDS, DATETIME[0]]),
+            oscrun(0),
+            I2cTrans::write(
+                DEV_ADDR,
+                vec![Register::SECONDS, DATETIME[0], DATETIME[1], DATETIME[2]],
+            ),
+            I2cTrans::write(
+                DEV_ADDR,
+                vec![Register::SECONDS, BitFlags::ST | DATETIME[0]],
+            ),
+            oscrun(BitFlags::OSCRUN),
+        ]);
+        let mut dev = new_mcp7940n(&trans);
+        dev.enable().unwrap();
+        dev.set_time_safely(
+            &NaiveDateTime::from(datetime()).time(),
+            &mut NoopDelay::new(),
+        )
+        .unwrap();
+        destroy_mcp7940n(dev);
+    }
+
+    #[test]
+    fn returns_error_if_oscillator_does_not_stop() {
+        let mut dev = new_mcp7940n(&[
+            I2cTrans::write(DEV_ADDR, vec![Register::SECONDS, DATETIME[0]]),
+            oscrun(BitFlags::OSCRUN),
+            oscrun(BitFlags::OSCRUN),
+        ]);
+        dev.set_oscillator_poll_attempts(2);
+        match dev.set_datetime_safely(&datetime(), &mut NoopDelay::new()) {
+            Err(Error::OscillatorStopTimeout) => (),
+            _ => panic!("OscillatorStopTimeout error not returned."),
+        }
+        destroy_mcp7940n(dev);
+    }
+
+    #[test]
+    fn returns_error_if_oscillator_does_not_start() {
+        let mut payload = vec![Register::SECONDS];
+        payload.extend_from_slice(&DATETIME);
+        let mut trans = enable_transactions().to_vec();
+        trans.extend([
+            I2cTrans::write(DEV_ADDR, vec![Register::SECONDS, DATETIME[0]]),
+            oscrun(0),
+            I2cTrans::write(DEV_ADDR, payload),
+            I2cTrans::write(
+                DEV_ADDR,
+                vec![Register::SECONDS, BitFlags::ST | DATETIME[0]],
+            ),
+            oscrun(0),
+            oscrun(0),
+        ]);
+        let mut dev = new_mcp7940n(&trans);
+        dev.enable().unwrap();
+        dev.set_oscillator_poll_attempts(2);
+        match dev.set_datetime_safely(&datetime(), &mut NoopDelay::new()) {
+            Err(Error::OscillatorStartTimeout) => (),
+            _ => panic!("OscillatorStartTimeout error not returned."),
+        }
+        destroy_mcp7940n(dev);
+    }
+}
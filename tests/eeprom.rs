@@ -1,3 +1,4 @@
+use embedded_hal::i2c::{ErrorKind, NoAcknowledgeSource};
 use embedded_hal_mock::eh1::i2c::Transaction as I2cTrans;
 use mcp794xx::{EepromWriteProtection, Error};
 mod common;
@@ -76,7 +77,10 @@ for_all_ics_with_eeprom!(
     can_write_byte,
     call_test,
     write_eeprom_byte,
-    [I2cTrans::write(EEPROM_ADDRESS, vec![0x00, 15])],
+    [
+        I2cTrans::write(EEPROM_ADDRESS, vec![0x00, 15]),
+        I2cTrans::write(EEPROM_ADDRESS, vec![])
+    ],
     0x00,
     15
 );
@@ -104,11 +108,74 @@ for_all_ics_with_eeprom!(
     can_write_data,
     write_data_test,
     write_eeprom_data,
-    [I2cTrans::write(EEPROM_ADDRESS, vec![0x00, 1, 2, 3, 4, 5])],
+    [
+        I2cTrans::write(EEPROM_ADDRESS, vec![0x00, 1, 2, 3, 4, 5]),
+        I2cTrans::write(EEPROM_ADDRESS, vec![])
+    ],
     0x00,
     [1, 2, 3, 4, 5]
 );
 
+for_all_ics_with_eeprom!(
+    can_write_data_up_to_last_address,
+    write_data_test,
+    write_eeprom_data,
+    [
+        I2cTrans::write(EEPROM_ADDRESS, vec![0x7E, 1, 2]),
+        I2cTrans::write(EEPROM_ADDRESS, vec![])
+    ],
+    0x7E,
+    [1, 2]
+);
+
+for_all_ics_with_eeprom!(
+    can_write_data_across_pages,
+    write_data_test,
+    write_eeprom_data,
+    [
+        I2cTrans::write(EEPROM_ADDRESS, vec![0x06, 1, 2]),
+        I2cTrans::write(EEPROM_ADDRESS, vec![]),
+        I2cTrans::write(EEPROM_ADDRESS, vec![0x08, 3, 4, 5, 6, 7, 8, 9, 10]),
+        I2cTrans::write(EEPROM_ADDRESS, vec![]),
+        I2cTrans::write(EEPROM_ADDRESS, vec![0x10, 11]),
+        I2cTrans::write(EEPROM_ADDRESS, vec![])
+    ],
+    0x06,
+    [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11]
+);
+
+fn busy_poll() -> I2cTrans {
+    I2cTrans::write(EEPROM_ADDRESS, vec![])
+        .with_error(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address))
+}
+
+#[test]
+fn waits_for_write_cycle_to_complete() {
+    let mut dev = new_mcp79410(&[
+        I2cTrans::write(EEPROM_ADDRESS, vec![0x00, 15]),
+        busy_poll(),
+        busy_poll(),
+        I2cTrans::write(EEPROM_ADDRESS, vec![]),
+    ]);
+    dev.write_eeprom_byte(0x00, 15).unwrap();
+    destroy_mcp79410(dev);
+}
+
+#[test]
+fn returns_error_if_write_cycle_does_not_complete() {
+    let mut dev = new_mcp79410(&[
+        I2cTrans::write(EEPROM_ADDRESS, vec![0x00, 15]),
+        busy_poll(),
+        busy_poll(),
+    ]);
+    dev.set_eeprom_write_poll_attempts(2);
+    match dev.write_eeprom_byte(0x00, 15) {
+        Err(Error::EepromWriteTimeout) => (),
+        _ => panic!("EepromWriteTimeout error not returned."),
+    }
+    destroy_mcp79410(dev);
+}
+
 mod write_protection {
     use super::*;
     const EEPROM_STATUS: u8 = 0xFF;
@@ -121,37 +188,40 @@ mod write_protection {
         can_set_protection_none,
         call_test,
         set_eeprom_write_protection,
-        [I2cTrans::write(EEPROM_ADDRESS, vec![EEPROM_STATUS, 0])],
+        [
+            I2cTrans::write(EEPROM_ADDRESS, vec![EEPROM_STATUS, 0]),
+            I2cTrans::write(EEPROM_ADDRESS, vec![])
+        ],
         NONE
     );
     for_all_ics_with_eeprom!(
         can_set_protection_upper_quarter,
         call_test,
         set_eeprom_write_protection,
-        [I2cTrans::write(
-            EEPROM_ADDRESS,
-            vec![EEPROM_STATUS, 0b0000_0100]
-        )],
+        [
+            I2cTrans::write(EEPROM_ADDRESS, vec![EEPROM_STATUS, 0b0000_0100]),
+            I2cTrans::write(EEPROM_ADDRESS, vec![])
+        ],
         UPPERQUARTER
     );
     for_all_ics_with_eeprom!(
         can_set_protection_upper_half,
         call_test,
         set_eeprom_write_protection,
-        [I2cTrans::write(
-            EEPROM_ADDRESS,
-            vec![EEPROM_STATUS, 0b0000_1000]
-        )],
+        [
+            I2cTrans::write(EEPROM_ADDRESS, vec![EEPROM_STATUS, 0b0000_1000]),
+            I2cTrans::write(EEPROM_ADDRESS, vec![])
+        ],
         UPPERHALF
     );
     for_all_ics_with_eeprom!(
         can_set_protection_all,
         call_test,
         set_eeprom_write_protection,
-        [I2cTrans::write(
-            EEPROM_ADDRESS,
-            vec![EEPROM_STATUS, 0b0000_1100]
-        )],
+        [
+            I2cTrans::write(EEPROM_ADDRESS, vec![EEPROM_STATUS, 0b0000_1100]),
+            I2cTrans::write(EEPROM_ADDRESS, vec![])
+        ],
         ALL
     );
 }
@@ -0,0 +1,98 @@
+use embedded_hal_mock::eh1::i2c::Transaction as I2cTrans;
+use mcp794xx::{DateTime, Error, Event};
+mod common;
+use crate::common::{
+    destroy_mcp7940n, new_mcp7940n, BitFlags, Register, DEVICE_ADDRESS as DEV_ADDR,
+};
+
+fn read_header(header: [u8; 4]) -> I2cTrans {
+    I2cTrans::write_read(DEV_ADDR, vec![0x20], header.to_vec())
+}
+
+fn event(second: u8, code: u8) -> Event {
+    Event {
+        datetime: DateTime::new(2023, 12, 1, 10, 0, second).unwrap(),
+        code,
+    }
+}
+
+#[test]
+fn cannot_use_region_outside_sram() {
+    let mut dev = new_mcp7940n(&[]);
+    assert_invalid_input_data!(dev.event_log(0x58, 9));
+    assert_invalid_input_data!(dev.event_log(0x20, 8));
+    destroy_mcp7940n(dev);
+}
+
+#[test]
+fn invalid_header_is_empty() {
+    let mut dev = new_mcp7940n(&[read_header([0, 1, 0, 0])]);
+    let mut log = dev.event_log(0x20, 14).unwrap();
+    assert_eq!(2, log.capacity());
+    assert!(log.is_empty());
+    assert!(log.iter().next().is_none());
+    destroy_mcp7940n(dev);
+}
+
+#[test]
+fn can_append() {
+    let mut dev = new_mcp7940n(&[
+        read_header([0, 0, 0xEC, 0xDE]),
+        I2cTrans::write_read(
+            DEV_ADDR,
+            vec![Register::SECONDS],
+            vec![BitFlags::ST | 5, 0, 0x10, 6, 1, 0x12, 0x23],
+        ),
+        I2cTrans::write(DEV_ADDR, vec![0x24, 37, 107, 252, 44, 7]),
+        I2cTrans::write(DEV_ADDR, vec![0x20, 1, 1, 0xE8, 0xDF]),
+    ]);
+    let mut log = dev.event_log(0x20, 14).unwrap();
+    log.append(7).unwrap();
+    assert_eq!(1, log.len());
+    destroy_mcp7940n(dev);
+}
+
+#[test]
+fn iterates_newest_first() {
+    let mut dev = new_mcp7940n(&[
+        read_header([1, 2, 0xBD, 0x8C]),
+        I2cTrans::write_read(DEV_ADDR, vec![0x24], vec![37, 107, 252, 44, 2]),
+        I2cTrans::write_read(DEV_ADDR, vec![0x29], vec![36, 107, 252, 44, 1]),
+    ]);
+    let mut log = dev.event_log(0x20, 14).unwrap();
+    assert_eq!(2, log.len());
+    let mut events = log.iter();
+    assert_eq!(event(5, 2), events.next().unwrap().unwrap());
+    assert_eq!(event(4, 1), events.next().unwrap().unwrap());
+    assert!(events.next().is_none());
+    destroy_mcp7940n(dev);
+}
+
+#[test]
+fn skips_corrupt_entries() {
+    let mut dev = new_mcp7940n(&[
+        read_header([1, 2, 0xBD, 0x8C]),
+        I2cTrans::write_read(DEV_ADDR, vec![0x24], vec![0xFF, 0xFF, 0xFF, 0xFF, 2]),
+        I2cTrans::write_read(DEV_ADDR, vec![0x24], vec![0xFF, 0xFF, 0xFF, 0xFF, 2]),
+        I2cTrans::write_read(DEV_ADDR, vec![0x29], vec![36, 107, 252, 44, 1]),
+    ]);
+    let mut log = dev.event_log(0x20, 14).unwrap();
+    assert_eq!(None, log.get(0).unwrap());
+    let mut events = log.iter();
+    assert_eq!(event(4, 1), events.next().unwrap().unwrap());
+    assert!(events.next().is_none());
+    destroy_mcp7940n(dev);
+}
+
+#[test]
+fn can_clear() {
+    let mut dev = new_mcp7940n(&[
+        read_header([0, 2, 0x8A, 0xBC]),
+        I2cTrans::write(DEV_ADDR, vec![0x20, 0, 0, 0xEC, 0xDE]),
+    ]);
+    let mut log = dev.event_log(0x20, 14).unwrap();
+    assert_eq!(2, log.len());
+    log.clear().unwrap();
+    assert!(log.is_empty());
+    destroy_mcp7940n(dev);
+}
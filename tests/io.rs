@@ -0,0 +1,132 @@
+#![cfg(feature = "embedded-io")]
+use embedded_hal_mock::eh1::i2c::Transaction as I2cTrans;
+use embedded_io::{Error as _, ErrorKind, Read, Seek, SeekFrom, Write};
+use mcp794xx::IoError;
+mod common;
+use crate::common::{
+    destroy_mcp7940n, destroy_mcp79410, new_mcp7940n, new_mcp79410, DEVICE_ADDRESS as DEV_ADDR,
+    EEPROM_ADDRESS,
+};
+
+fn poll() -> I2cTrans {
+    I2cTrans::write(EEPROM_ADDRESS, vec![])
+}
+
+mod sram {
+    use super::*;
+
+    #[test]
+    fn can_read() {
+        let mut dev = new_mcp7940n(&[
+            I2cTrans::write_read(DEV_ADDR, vec![0x20], vec![1, 2]),
+            I2cTrans::write_read(DEV_ADDR, vec![0x22], vec![3]),
+        ]);
+        let mut cursor = dev.sram_cursor();
+        let mut data = [0; 2];
+        assert_eq!(2, cursor.read(&mut data).unwrap());
+        assert_eq!([1, 2], data);
+        assert_eq!(1, cursor.read(&mut data[..1]).unwrap());
+        assert_eq!(3, data[0]);
+        destroy_mcp7940n(dev);
+    }
+
+    #[test]
+    fn can_write_after_seeking() {
+        let mut dev = new_mcp7940n(&[I2cTrans::write(DEV_ADDR, vec![0x30, 1, 2, 3])]);
+        let mut cursor = dev.sram_cursor();
+        assert_eq!(16, cursor.seek(SeekFrom::Start(16)).unwrap());
+        cursor.write_all(&[1, 2, 3]).unwrap();
+        assert_eq!(19, cursor.stream_position().unwrap());
+        destroy_mcp7940n(dev);
+    }
+
+    #[test]
+    fn read_is_truncated_at_end() {
+        let mut dev = new_mcp7940n(&[I2cTrans::write_read(DEV_ADDR, vec![0x5E], vec![1, 2])]);
+        let mut cursor = dev.sram_cursor();
+        cursor.seek(SeekFrom::End(-2)).unwrap();
+        let mut data = [0; 4];
+        assert_eq!(2, cursor.read(&mut data).unwrap());
+        assert_eq!(0, cursor.read(&mut data).unwrap());
+        destroy_mcp7940n(dev);
+    }
+
+    #[test]
+    fn write_returns_write_zero_at_end() {
+        let mut dev = new_mcp7940n(&[I2cTrans::write(DEV_ADDR, vec![0x5F, 1])]);
+        let mut cursor = dev.sram_cursor();
+        cursor.seek(SeekFrom::Start(63)).unwrap();
+        let error = cursor.write_all(&[1, 2]).unwrap_err();
+        assert_eq!(ErrorKind::WriteZero, error.kind());
+        destroy_mcp7940n(dev);
+    }
+
+    #[test]
+    fn cannot_seek_out_of_region() {
+        let mut dev = new_mcp7940n(&[]);
+        let mut cursor = dev.sram_cursor();
+        assert_eq!(64, cursor.seek(SeekFrom::End(0)).unwrap());
+        assert_eq!(
+            ErrorKind::InvalidInput,
+            cursor.seek(SeekFrom::Current(1)).unwrap_err().kind()
+        );
+        assert_eq!(
+            ErrorKind::InvalidInput,
+            cursor.seek(SeekFrom::End(-65)).unwrap_err().kind()
+        );
+        assert_eq!(
+            ErrorKind::InvalidInput,
+            cursor.seek(SeekFrom::Start(u64::MAX)).unwrap_err().kind()
+        );
+        destroy_mcp7940n(dev);
+    }
+}
+
+mod eeprom {
+    use super::*;
+
+    #[test]
+    fn can_read_to_end() {
+        let mut dev = new_mcp79410(&[I2cTrans::write_read(
+            EEPROM_ADDRESS,
+            vec![0x7C],
+            vec![1, 2, 3, 4],
+        )]);
+        let mut cursor = dev.eeprom_cursor();
+        cursor.seek(SeekFrom::Start(0x7C)).unwrap();
+        let mut data = [0; 8];
+        assert_eq!(4, cursor.read(&mut data).unwrap());
+        assert_eq!([1, 2, 3, 4], data[..4]);
+        assert_eq!(0, cursor.read(&mut data).unwrap());
+        destroy_mcp79410(dev);
+    }
+
+    #[test]
+    fn writes_are_split_at_pages() {
+        let mut dev = new_mcp79410(&[
+            I2cTrans::write(EEPROM_ADDRESS, vec![0x06, 1, 2]),
+            poll(),
+            I2cTrans::write(EEPROM_ADDRESS, vec![0x08, 3]),
+            poll(),
+        ]);
+        let mut cursor = dev.eeprom_cursor();
+        cursor.seek(SeekFrom::Current(6)).unwrap();
+        cursor.write_all(&[1, 2, 3]).unwrap();
+        cursor.flush().unwrap();
+        assert_eq!(9, cursor.stream_position().unwrap());
+        destroy_mcp79410(dev);
+    }
+
+    #[test]
+    fn write_returns_end_of_region_at_end() {
+        let mut dev = new_mcp79410(&[]);
+        let mut cursor = dev.eeprom_cursor();
+        cursor.seek(SeekFrom::End(0)).unwrap();
+        match cursor.write(&[1]) {
+            Err(IoError::EndOfRegion) => (),
+            _ => panic!("EndOfRegion error not returned."),
+        }
+        assert_eq!(0, cursor.write(&[]).unwrap());
+        destroy_mcp79410(dev);
+    }
+}
@@ -0,0 +1,88 @@
+use core::time::Duration;
+use embedded_hal_mock::eh1::i2c::Transaction as I2cTrans;
+use mcp794xx::{Error, OutageStats};
+mod common;
+use crate::common::{
+    destroy_mcp7940n, destroy_mcp79410, new_mcp7940n, new_mcp79410, BitFlags, Register,
+    DEVICE_ADDRESS as DEV_ADDR, EEPROM_ADDRESS,
+};
+
+const HEADER: [u8; 16] = [1, 2, 3, 0, 0, 0, 0x58, 2, 0, 0, 0x2C, 1, 0, 0, 0xA6, 0x4F];
+const STATS: OutageStats = OutageStats {
+    count: 3,
+    total: Duration::from_secs(600),
+    longest: Duration::from_secs(300),
+};
+
+fn read_header(header: [u8; 16]) -> I2cTrans {
+    I2cTrans::write_read(DEV_ADDR, vec![0x20], header.to_vec())
+}
+
+#[test]
+fn cannot_use_region_outside_memory() {
+    let mut dev = new_mcp79410(&[]);
+    assert_invalid_input_data!(dev.outage_history(0x49, 24));
+    assert_invalid_input_data!(dev.outage_history(0x20, 23));
+    assert_invalid_input_data!(dev.eeprom_outage_history(0x69, 24));
+    assert_invalid_input_data!(dev.eeprom_outage_history(0x00, 23));
+    destroy_mcp79410(dev);
+}
+
+#[test]
+fn invalid_header_is_empty() {
+    let mut dev = new_mcp7940n(&[read_header([0; 16])]);
+    let history = dev.outage_history(0x20, 32).unwrap();
+    assert_eq!(2, history.capacity());
+    assert!(history.is_empty());
+    assert_eq!(OutageStats::default(), history.stats());
+    destroy_mcp7940n(dev);
+}
+
+#[test]
+fn can_read_stats() {
+    let mut dev = new_mcp7940n(&[read_header(HEADER)]);
+    let history = dev.outage_history(0x20, 32).unwrap();
+    assert_eq!(2, history.len());
+    assert_eq!(STATS, history.stats());
+    destroy_mcp7940n(dev);
+}
+
+#[test]
+fn can_read_stats_from_eeprom() {
+    let mut dev = new_mcp79410(&[I2cTrans::write_read(
+        EEPROM_ADDRESS,
+        vec![0x00],
+        HEADER.to_vec(),
+    )]);
+    let history = dev.eeprom_outage_history(0x00, 32).unwrap();
+    assert_eq!(STATS, history.stats());
+    destroy_mcp79410(dev);
+}
+
+#[test]
+fn records_nothing_if_power_has_not_failed() {
+    let mut dev = new_mcp7940n(&[
+        read_header(HEADER),
+        I2cTrans::write_read(DEV_ADDR, vec![Register::WEEKDAY], vec![BitFlags::VBATEN]),
+    ]);
+    let mut history = dev.outage_history(0x20, 32).unwrap();
+    assert_eq!(None, history.record().unwrap());
+    assert_eq!(2, history.len());
+    destroy_mcp7940n(dev);
+}
+
+#[test]
+fn can_clear() {
+    let mut header = [0; 17];
+    header[0] = 0x20;
+    header[15..].copy_from_slice(&[0x6E, 0x81]);
+    let mut dev = new_mcp7940n(&[
+        read_header(HEADER),
+        I2cTrans::write(DEV_ADDR, header.to_vec()),
+    ]);
+    let mut history = dev.outage_history(0x20, 32).unwrap();
+    history.clear().unwrap();
+    assert!(history.is_empty());
+    assert_eq!(OutageStats::default(), history.stats());
+    destroy_mcp7940n(dev);
+}
@@ -143,7 +143,8 @@ for_all_ics_with_protected_eeprom!(
     [
         I2cTrans::write(DEV_ADDR, vec![EEUNLOCK, 0x55]),
         I2cTrans::write(DEV_ADDR, vec![EEUNLOCK, 0xAA]),
-        I2cTrans::write(EEPROM_ADDRESS, vec![0xF0, 15])
+        I2cTrans::write(EEPROM_ADDRESS, vec![0xF0, 15]),
+        I2cTrans::write(EEPROM_ADDRESS, vec![])
     ],
     0xF0,
     15
@@ -195,7 +196,8 @@ for_all_ics_with_protected_eeprom!(
     [
         I2cTrans::write(DEV_ADDR, vec![EEUNLOCK, 0x55]),
         I2cTrans::write(DEV_ADDR, vec![EEUNLOCK, 0xAA]),
-        I2cTrans::write(EEPROM_ADDRESS, vec![0xF0, 1, 2, 3, 4, 5])
+        I2cTrans::write(EEPROM_ADDRESS, vec![0xF0, 1, 2, 3, 4, 5]),
+        I2cTrans::write(EEPROM_ADDRESS, vec![])
     ],
     0xF0,
     [1, 2, 3, 4, 5]
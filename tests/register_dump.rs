@@ -0,0 +1,105 @@
+use embedded_hal_mock::eh1::i2c::Transaction as I2cTrans;
+use mcp794xx::RegisterDump;
+mod common;
+use crate::common::{
+    destroy_mcp79400, destroy_mcp79410, new_mcp79400, new_mcp79410, BitFlags, Register,
+    DEVICE_ADDRESS as DEV_ADDR, EEPROM_ADDRESS,
+};
+
+fn registers() -> [u8; 0x20] {
+    let mut registers = [0; 0x20];
+    registers[0] = BitFlags::ST | 0x12;
+    registers[1] = 0x34;
+    registers[2] = 0b0110_0101;
+    registers[3] = BitFlags::OSCRUN | BitFlags::VBATEN | 6;
+    registers[4] = 0x01;
+    registers[5] = 0x12;
+    registers[6] = 0x23;
+    registers[7] = BitFlags::SQWEN | 0b11;
+    registers[0x0D] = BitFlags::ALMPOL | BitFlags::ALMIF | 0b0111_0000 | 2;
+    registers[0x1B] = 0b0100_0000 | 0x10;
+    registers
+}
+
+#[test]
+fn can_dump_registers() {
+    let mut dev = new_mcp79400(&[I2cTrans::write_read(
+        DEV_ADDR,
+        vec![Register::SECONDS],
+        registers().to_vec(),
+    )]);
+    let dump = dev.dump_registers().unwrap();
+    assert_eq!(RegisterDump::from_registers(registers()), dump);
+    assert_eq!(None, dump.sram());
+    assert_eq!(None, dump.eeprom_status());
+    destroy_mcp79400(dev);
+}
+
+#[test]
+fn can_dump_registers_and_sram() {
+    let mut data = registers().to_vec();
+    data.extend(0..64);
+    let mut dev = new_mcp79400(&[I2cTrans::write_read(
+        DEV_ADDR,
+        vec![Register::SECONDS],
+        data,
+    )]);
+    let dump = dev.dump_registers_and_sram().unwrap();
+    assert_eq!(&registers(), dump.registers());
+    assert_eq!(Some(&core::array::from_fn(|i| i as u8)), dump.sram());
+    destroy_mcp79400(dev);
+}
+
+#[test]
+fn can_read_eeprom_status() {
+    let mut dev = new_mcp79410(&[I2cTrans::write_read(
+        EEPROM_ADDRESS,
+        vec![0xFF],
+        vec![0b0000_1000],
+    )]);
+    assert_eq!(0b0000_1000, dev.read_eeprom_status().unwrap());
+    destroy_mcp79410(dev);
+}
+
+#[test]
+fn displays_decoded_registers() {
+    let dump = RegisterDump::from_registers(registers()).with_eeprom_status(0b0000_0100);
+    let text = format!("{}", dump);
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!(33, lines.len());
+    assert_eq!("RTCSEC    (0x00) = 0x92: ST=1 SECONDS=12", lines[0]);
+    assert_eq!("RTCHOUR   (0x02) = 0x65: 12/24=1 AM/PM=1 HOURS=5", lines[2]);
+    assert_eq!(
+        "RTCWKDAY  (0x03) = 0x2E: OSCRUN=1 PWRFAIL=0 VBATEN=1 WKDAY=6",
+        lines[3]
+    );
+    assert_eq!(
+        "CONTROL   (0x07) = 0x43: OUT=0 SQWEN=1 ALM1EN=0 ALM0EN=0 EXTOSC=0 CRSTRIM=0 SQWFS=3",
+        lines[7]
+    );
+    assert_eq!(
+        "ALM0WKDAY (0x0D) = 0xFA: ALMPOL=1 ALMxMSK=7 ALMxIF=1 WKDAY=2",
+        lines[0x0D]
+    );
+    assert_eq!("PWRDNMTH  (0x1B) = 0x50: WKDAY=2 MONTH=10", lines[0x1B]);
+    assert_eq!("EESTATUS  (0xFF) = 0x04: BP=1", lines[32]);
+}
+
+#[test]
+fn displays_sram() {
+    let dump = RegisterDump::from_registers([0; 0x20]).with_sram([0xAB; 64]);
+    let text = format!("{}", dump);
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!(36, lines.len());
+    assert_eq!(
+        "SRAM      (0x50) = AB AB AB AB AB AB AB AB AB AB AB AB AB AB AB AB",
+        lines[35]
+    );
+}
+
+#[test]
+fn debug_output_is_decoded() {
+    let text = format!("{:?}", RegisterDump::from_registers(registers()));
+    assert!(text.starts_with("RegisterDump { RTCSEC: 0x92 { ST=1 SECONDS=12 }, RTCMIN: 0x34 {"));
+    assert!(text.contains("EEUNLOCK: 0x00, ALM0SEC: 0x00 { SECONDS=0 }"));
+}
@@ -0,0 +1,162 @@
+use mcp794xx::{AlarmOutputPinPolarity, DateTime, Error, Recurrence, SCHEDULER_SIZE};
+mod common;
+use crate::common::{destroy_mcp7940n, new_mcp7940n};
+
+fn dt(month: u8, day: u8, hour: u8, minute: u8, second: u8) -> DateTime {
+    DateTime::new(2023, month, day, hour, minute, second).unwrap()
+}
+
+const EVERY_15_MINUTES: Recurrence = Recurrence::Interval { minutes: 15 };
+
+#[test]
+fn interval_occurs_at_multiples_from_midnight() {
+    let next = EVERY_15_MINUTES.next_after(&dt(12, 1, 10, 7, 30));
+    assert_eq!(Some(dt(12, 1, 10, 15, 0)), next);
+    let next = EVERY_15_MINUTES.next_after(&dt(12, 1, 10, 15, 0));
+    assert_eq!(Some(dt(12, 1, 10, 30, 0)), next);
+}
+
+#[test]
+fn interval_restarts_at_midnight() {
+    let every_7_minutes = Recurrence::Interval { minutes: 7 };
+    let next = every_7_minutes.next_after(&dt(12, 31, 23, 55, 0));
+    assert_eq!(DateTime::new(2024, 1, 1, 0, 0, 0), next);
+}
+
+#[test]
+fn weekly_skips_weekend() {
+    let rule = Recurrence::Weekly {
+        weekdays: Recurrence::WORKDAYS,
+        hour: 7,
+        minute: 30,
+    };
+    // Friday
+    assert_eq!(
+        Some(dt(12, 1, 7, 30, 0)),
+        rule.next_after(&dt(12, 1, 6, 0, 0))
+    );
+    assert_eq!(
+        Some(dt(12, 4, 7, 30, 0)),
+        rule.next_after(&dt(12, 1, 7, 30, 0))
+    );
+}
+
+#[test]
+fn weekly_occurs_again_next_week() {
+    // Sunday
+    let rule = Recurrence::Weekly {
+        weekdays: 1,
+        hour: 12,
+        minute: 0,
+    };
+    assert_eq!(
+        Some(dt(12, 10, 12, 0, 0)),
+        rule.next_after(&dt(12, 3, 12, 0, 0))
+    );
+}
+
+#[test]
+fn monthly_skips_short_months() {
+    let rule = Recurrence::Monthly {
+        day: 31,
+        hour: 0,
+        minute: 0,
+    };
+    assert_eq!(
+        Some(dt(5, 31, 0, 0, 0)),
+        rule.next_after(&dt(3, 31, 0, 0, 0))
+    );
+    let rule = Recurrence::Monthly {
+        day: 29,
+        hour: 8,
+        minute: 0,
+    };
+    assert_eq!(
+        Some(dt(3, 29, 8, 0, 0)),
+        rule.next_after(&dt(1, 29, 8, 0, 0))
+    );
+}
+
+#[test]
+fn invalid_rules_do_not_occur() {
+    let now = dt(1, 1, 0, 0, 0);
+    for rule in [
+        Recurrence::Interval { minutes: 0 },
+        Recurrence::Interval { minutes: 1441 },
+        Recurrence::Weekly {
+            weekdays: 0,
+            hour: 0,
+            minute: 0,
+        },
+        Recurrence::Weekly {
+            weekdays: 0x80,
+            hour: 0,
+            minute: 0,
+        },
+        Recurrence::Weekly {
+            weekdays: 1,
+            hour: 24,
+            minute: 0,
+        },
+        Recurrence::Monthly {
+            day: 32,
+            hour: 0,
+            minute: 0,
+        },
+        Recurrence::Monthly {
+            day: 1,
+            hour: 0,
+            minute: 60,
+        },
+    ] {
+        assert_eq!(None, rule.next_after(&now), "{rule:?}");
+    }
+}
+
+#[test]
+fn no_occurrences_after_2099() {
+    let now = DateTime::new(2099, 12, 31, 23, 45, 0).unwrap();
+    assert_eq!(None, EVERY_15_MINUTES.next_after(&now));
+}
+
+fn assert_cannot_create(address: u8, rules: &[Recurrence]) {
+    let mut dev = new_mcp7940n(&[]);
+    match dev.scheduler(address, rules, AlarmOutputPinPolarity::Low) {
+        Err(Error::InvalidInputData) => (),
+        _ => panic!("InvalidInputData error not returned."),
+    }
+    destroy_mcp7940n(dev);
+}
+
+#[test]
+fn cannot_create_out_of_sram() {
+    let size = SCHEDULER_SIZE as u8;
+    assert_cannot_create(0x1F, &[EVERY_15_MINUTES]);
+    assert_cannot_create(0x60 - size + 1, &[EVERY_15_MINUTES]);
+}
+
+#[test]
+fn cannot_create_with_invalid_rule() {
+    assert_cannot_create(
+        0x20,
+        &[EVERY_15_MINUTES, Recurrence::Interval { minutes: 0 }],
+    );
+}
+
+#[test]
+fn cannot_create_with_too_many_rules() {
+    assert_cannot_create(0x20, &[EVERY_15_MINUTES; 33]);
+}
+
+#[test]
+fn can_create_with_32_rules() {
+    let mut dev = new_mcp7940n(&[]);
+    let size = SCHEDULER_SIZE as u8;
+    dev.scheduler(
+        0x60 - size,
+        &[EVERY_15_MINUTES; 32],
+        AlarmOutputPinPolarity::Low,
+    )
+    .unwrap();
+    destroy_mcp7940n(dev);
+}
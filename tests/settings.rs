@@ -0,0 +1,185 @@
+use embedded_hal::i2c::ErrorKind;
+use embedded_hal_mock::eh1::i2c::Transaction as I2cTrans;
+use mcp794xx::{Error, Settings, SettingsError};
+mod common;
+use crate::common::{destroy_mcp79410, new_mcp79410, EEPROM_ADDRESS};
+
+#[derive(Debug, PartialEq)]
+struct Config {
+    brightness: u8,
+    volume: u8,
+}
+
+impl Settings for Config {
+    const VERSION: u8 = 2;
+
+    fn serialize(&self, buffer: &mut [u8]) -> Option<usize> {
+        buffer
+            .get_mut(..2)?
+            .copy_from_slice(&[self.brightness, self.volume]);
+        Some(2)
+    }
+
+    fn deserialize(data: &[u8]) -> Option<Self> {
+        match data {
+            [brightness, volume] => Some(Config {
+                brightness: *brightness,
+                volume: *volume,
+            }),
+            _ => None,
+        }
+    }
+
+    fn migrate(version: u8, data: &[u8]) -> Option<Self> {
+        match (version, data) {
+            (1, [brightness]) => Some(Config {
+                brightness: *brightness,
+                volume: 50,
+            }),
+            _ => None,
+        }
+    }
+}
+
+const BLANK: [u8; 8] = [0xFF; 8];
+// version 2, sequence 0: brightness 10, volume 20
+const RECORD0: [u8; 8] = [2, 0, 2, 10, 20, 0x86, 0x91, 0xFF];
+// version 2, sequence 1: brightness 11, volume 21
+const RECORD1: [u8; 8] = [2, 1, 2, 11, 21, 0xD3, 0x35, 0xFF];
+
+fn read_slots(first: [u8; 8], second: [u8; 8]) -> [I2cTrans; 2] {
+    [
+        I2cTrans::write_read(EEPROM_ADDRESS, vec![0x10], first.to_vec()),
+        I2cTrans::write_read(EEPROM_ADDRESS, vec![0x18], second.to_vec()),
+    ]
+}
+
+fn load(first: [u8; 8], second: [u8; 8]) -> Result<Config, SettingsError<ErrorKind>> {
+    let mut dev = new_mcp79410(&read_slots(first, second));
+    let config = dev.settings_store(0x10, 8).unwrap().load::<Config>();
+    destroy_mcp79410(dev);
+    config
+}
+
+#[test]
+fn cannot_create_store_outside_eeprom() {
+    let mut dev = new_mcp79410(&[]);
+    assert_invalid_input_data!(dev.settings_store(0x70, 9));
+    assert_invalid_input_data!(dev.settings_store(0x00, 5));
+    destroy_mcp79410(dev);
+}
+
+#[test]
+fn can_load_first_slot() {
+    let expected = Config {
+        brightness: 10,
+        volume: 20,
+    };
+    assert_eq!(expected, load(RECORD0, BLANK).unwrap());
+}
+
+#[test]
+fn loads_most_recent_slot() {
+    let expected = Config {
+        brightness: 11,
+        volume: 21,
+    };
+    assert_eq!(expected, load(RECORD0, RECORD1).unwrap());
+    assert_eq!(expected, load(RECORD1, RECORD0).unwrap());
+}
+
+#[test]
+fn loads_previous_slot_if_most_recent_is_corrupt() {
+    let mut corrupt = RECORD1;
+    corrupt[4] = 0;
+    let expected = Config {
+        brightness: 10,
+        volume: 20,
+    };
+    assert_eq!(expected, load(RECORD0, corrupt).unwrap());
+}
+
+#[test]
+fn returns_blank_error() {
+    match load(BLANK, BLANK) {
+        Err(SettingsError::Blank) => (),
+        _ => panic!("Blank error not returned."),
+    }
+}
+
+#[test]
+fn returns_corrupt_error() {
+    let mut corrupt = RECORD0;
+    corrupt[5] = 0;
+    match load(corrupt, BLANK) {
+        Err(SettingsError::Corrupt) => (),
+        _ => panic!("Corrupt error not returned."),
+    }
+}
+
+#[test]
+fn migrates_older_version() {
+    // version 1, sequence 5: brightness 10
+    let record = [1, 5, 1, 10, 0x8B, 0xFF, 0xFF, 0xFF];
+    let expected = Config {
+        brightness: 10,
+        volume: 50,
+    };
+    assert_eq!(expected, load(BLANK, record).unwrap());
+}
+
+#[test]
+fn returns_version_mismatch_for_newer_version() {
+    // version 3, sequence 0: brightness 10, volume 20
+    let record = [3, 0, 2, 10, 20, 0x2C, 0xC0, 0xFF];
+    match load(record, BLANK) {
+        Err(SettingsError::VersionMismatch(3)) => (),
+        _ => panic!("VersionMismatch error not returned."),
+    }
+}
+
+#[test]
+fn saves_to_first_slot_if_blank() {
+    let mut trans = read_slots(BLANK, BLANK).to_vec();
+    trans.extend([
+        I2cTrans::write(EEPROM_ADDRESS, vec![0x10, 2, 0, 2, 10, 20, 0x86, 0x91]),
+        I2cTrans::write(EEPROM_ADDRESS, vec![]),
+    ]);
+    let mut dev = new_mcp79410(&trans);
+    let config = Config {
+        brightness: 10,
+        volume: 20,
+    };
+    dev.settings_store(0x10, 8).unwrap().save(&config).unwrap();
+    destroy_mcp79410(dev);
+}
+
+#[test]
+fn saves_to_other_slot_than_most_recent() {
+    let mut trans = read_slots(RECORD0, BLANK).to_vec();
+    trans.extend([
+        I2cTrans::write(EEPROM_ADDRESS, vec![0x18, 2, 1, 2, 11, 21, 0xD3, 0x35]),
+        I2cTrans::write(EEPROM_ADDRESS, vec![]),
+    ]);
+    let mut dev = new_mcp79410(&trans);
+    let config = Config {
+        brightness: 11,
+        volume: 21,
+    };
+    dev.settings_store(0x10, 8).unwrap().save(&config).unwrap();
+    destroy_mcp79410(dev);
+}
+
+#[test]
+fn cannot_save_settings_larger_than_slot() {
+    let mut dev = new_mcp79410(&[]);
+    let config = Config {
+        brightness: 10,
+        volume: 20,
+    };
+    match dev.settings_store(0x10, 6).unwrap().save(&config) {
+        Err(SettingsError::Device(Error::InvalidInputData)) => (),
+        _ => panic!("InvalidInputData error not returned."),
+    }
+    destroy_mcp79410(dev);
+}
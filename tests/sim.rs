@@ -0,0 +1,560 @@
+#![cfg(feature = "sim")]
+use core::time::Duration;
+#[cfg(feature = "rtcc")]
+use mcp794xx::Rtcc;
+use mcp794xx::{
+    sim::Mcp794xxSim, Alarm, AlarmDateTime, AlarmMatching, AlarmOutputPinPolarity, DateTime,
+    EepromWriteProtection, Error, Hours, Mcp794xx, PowerFailDateTime, Recurrence, SettingsError,
+};
+
+fn datetime(day: u8, hour: u8, minute: u8, second: u8) -> DateTime {
+    DateTime::new(2023, 12, day, hour, minute, second).unwrap()
+}
+
+fn start_clock(sim: &mut Mcp794xxSim, begin: DateTime) {
+    let mut rtc = Mcp794xx::new_mcp79410(sim);
+    rtc.write_datetime(&begin).unwrap();
+    rtc.enable().unwrap();
+}
+
+#[test]
+fn oscillator_is_stopped_after_reset() {
+    let mut sim = Mcp794xxSim::new();
+    let mut rtc = Mcp794xx::new_mcp79410(&mut sim);
+    assert!(!rtc.is_oscillator_running().unwrap());
+    rtc.enable().unwrap();
+    assert!(rtc.is_oscillator_running().unwrap());
+}
+
+#[test]
+fn time_does_not_advance_if_not_enabled() {
+    let mut sim = Mcp794xxSim::new();
+    let mut rtc = Mcp794xx::new_mcp79410(&mut sim);
+    rtc.write_datetime(&datetime(1, 10, 0, 0)).unwrap();
+    sim.advance(Duration::from_secs(10));
+    let mut rtc = Mcp794xx::new_mcp79410(&mut sim);
+    assert_eq!(datetime(1, 10, 0, 0), rtc.read_datetime().unwrap());
+}
+
+#[test]
+fn can_set_datetime_safely_while_running() {
+    let mut sim = Mcp794xxSim::new();
+    let mut rtc = Mcp794xx::new_mcp79410(&mut sim);
+    rtc.enable().unwrap();
+    let mut delay = embedded_hal_mock::eh1::delay::NoopDelay::new();
+    rtc.set_datetime_safely(&datetime(1, 10, 0, 0), &mut delay)
+        .unwrap();
+    assert!(rtc.is_oscillator_running().unwrap());
+    sim.advance(Duration::from_secs(10));
+    let mut rtc = Mcp794xx::new_mcp79410(&mut sim);
+    assert_eq!(datetime(1, 10, 0, 10), rtc.read_datetime().unwrap());
+}
+
+#[test]
+fn can_roll_over_year() {
+    let mut sim = Mcp794xxSim::new();
+    start_clock(&mut sim, datetime(31, 23, 59, 59));
+    sim.advance(Duration::from_millis(1500));
+    let mut rtc = Mcp794xx::new_mcp79410(&mut sim);
+    let expected = DateTime::new(2024, 1, 1, 0, 0, 0).unwrap();
+    assert_eq!(expected, rtc.read_datetime().unwrap());
+    assert!(rtc.is_leap_year().unwrap());
+}
+
+#[cfg(feature = "rtcc")]
+#[test]
+fn can_roll_over_noon_in_24h_mode() {
+    let mut sim = Mcp794xxSim::new();
+    let mut rtc = Mcp794xx::new_mcp79410(&mut sim);
+    rtc.set_hours(Hours::H24(11)).unwrap();
+    rtc.set_minutes(59).unwrap();
+    rtc.set_seconds(59).unwrap();
+    rtc.enable().unwrap();
+    sim.advance(Duration::from_secs(2));
+    let mut rtc = Mcp794xx::new_mcp79410(&mut sim);
+    rtc.sync().unwrap();
+    assert_eq!(Hours::H24(12), rtc.hours().unwrap());
+    assert_eq!(1, rtc.seconds().unwrap());
+}
+
+#[cfg(feature = "rtcc")]
+#[test]
+fn can_roll_over_midnight_in_12h_mode() {
+    let mut sim = Mcp794xxSim::new();
+    let mut rtc = Mcp794xx::new_mcp79410(&mut sim);
+    rtc.set_day(31).unwrap();
+    rtc.set_hours(Hours::PM(11)).unwrap();
+    rtc.set_minutes(59).unwrap();
+    rtc.set_seconds(59).unwrap();
+    rtc.enable().unwrap();
+    sim.advance(Duration::from_secs(1));
+    let mut rtc = Mcp794xx::new_mcp79410(&mut sim);
+    assert_eq!(Hours::AM(12), rtc.hours().unwrap());
+    assert_eq!(1, rtc.day().unwrap());
+    assert_eq!(2, rtc.month().unwrap());
+}
+
+#[test]
+fn alarm_sets_matched_flag() {
+    let mut sim = Mcp794xxSim::new();
+    start_clock(&mut sim, datetime(1, 10, 0, 0));
+    let mut rtc = Mcp794xx::new_mcp79410(&mut sim);
+    rtc.sync().unwrap();
+    let when = AlarmDateTime {
+        month: 12,
+        day: 1,
+        weekday: 6,
+        hour: Hours::H24(10),
+        minute: 1,
+        second: 30,
+    };
+    rtc.set_alarm(
+        Alarm::Zero,
+        when,
+        AlarmMatching::AllMatch,
+        AlarmOutputPinPolarity::High,
+    )
+    .unwrap();
+    rtc.enable_alarm(Alarm::Zero).unwrap();
+    sim.advance(Duration::from_secs(89));
+    let mut rtc = Mcp794xx::new_mcp79410(&mut sim);
+    assert!(!rtc.has_alarm_matched(Alarm::Zero).unwrap());
+    sim.advance(Duration::from_secs(1));
+    let mut rtc = Mcp794xx::new_mcp79410(&mut sim);
+    assert!(rtc.has_alarm_matched(Alarm::Zero).unwrap());
+    assert!(!rtc.has_alarm_matched(Alarm::One).unwrap());
+    rtc.clear_alarm_matched_flag(Alarm::Zero).unwrap();
+    assert!(!rtc.has_alarm_matched(Alarm::Zero).unwrap());
+}
+
+#[test]
+fn relative_alarm_matches_once_after_duration() {
+    let mut sim = Mcp794xxSim::new();
+    start_clock(&mut sim, datetime(31, 23, 59, 0));
+    let mut rtc = Mcp794xx::new_mcp79410(&mut sim);
+    let when = rtc
+        .set_alarm_in(
+            Alarm::Zero,
+            Duration::from_secs(90 * 60),
+            AlarmOutputPinPolarity::High,
+        )
+        .unwrap();
+    assert_eq!(DateTime::new(2024, 1, 1, 1, 29, 0).unwrap(), when);
+    sim.advance(Duration::from_secs(90 * 60 - 1));
+    let mut rtc = Mcp794xx::new_mcp79410(&mut sim);
+    assert!(!rtc.has_alarm_matched(Alarm::Zero).unwrap());
+    sim.advance(Duration::from_secs(1));
+    let mut rtc = Mcp794xx::new_mcp79410(&mut sim);
+    assert!(rtc.has_alarm_matched(Alarm::Zero).unwrap());
+    assert_eq!(when, rtc.read_datetime().unwrap());
+}
+
+#[test]
+fn relative_alarm_within_a_minute_matches() {
+    let mut sim = Mcp794xxSim::new();
+    start_clock(&mut sim, datetime(1, 10, 0, 50));
+    let mut rtc = Mcp794xx::new_mcp79410(&mut sim);
+    rtc.set_alarm_in(
+        Alarm::One,
+        Duration::from_secs(15),
+        AlarmOutputPinPolarity::Low,
+    )
+    .unwrap();
+    sim.advance(Duration::from_secs(14));
+    let mut rtc = Mcp794xx::new_mcp79410(&mut sim);
+    assert!(!rtc.has_alarm_matched(Alarm::One).unwrap());
+    sim.advance(Duration::from_secs(1));
+    let mut rtc = Mcp794xx::new_mcp79410(&mut sim);
+    assert!(rtc.has_alarm_matched(Alarm::One).unwrap());
+}
+
+#[test]
+fn disabled_alarm_does_not_match() {
+    let mut sim = Mcp794xxSim::new();
+    start_clock(&mut sim, datetime(1, 10, 0, 0));
+    let mut rtc = Mcp794xx::new_mcp79410(&mut sim);
+    let when = AlarmDateTime {
+        month: 1,
+        day: 1,
+        weekday: 1,
+        hour: Hours::H24(0),
+        minute: 0,
+        second: 1,
+    };
+    rtc.set_alarm(
+        Alarm::One,
+        when,
+        AlarmMatching::SecondsMatch,
+        AlarmOutputPinPolarity::Low,
+    )
+    .unwrap();
+    sim.advance(Duration::from_secs(1));
+    let mut rtc = Mcp794xx::new_mcp79410(&mut sim);
+    assert!(!rtc.has_alarm_matched(Alarm::One).unwrap());
+}
+
+#[test]
+fn can_read_and_write_sram() {
+    let mut sim = Mcp794xxSim::new();
+    let mut rtc = Mcp794xx::new_mcp79410(&mut sim);
+    rtc.write_sram_data(0x5E, &[1, 2]).unwrap();
+    let mut data = [0; 2];
+    rtc.read_sram_data(0x5E, &mut data).unwrap();
+    assert_eq!([1, 2], data);
+    assert_eq!(&[1, 2], &sim.sram()[0x3E..]);
+}
+
+#[test]
+fn can_write_eeprom_across_pages_with_write_cycles() {
+    let mut sim = Mcp794xxSim::new();
+    sim.set_eeprom_write_cycle_polls(3);
+    let mut rtc = Mcp794xx::new_mcp79410(&mut sim);
+    let data = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+    rtc.write_eeprom_data(0x04, &data).unwrap();
+    let mut read = [0; 10];
+    rtc.read_eeprom_data(0x04, &mut read).unwrap();
+    assert_eq!(data, read);
+    assert_eq!(&data, &sim.eeprom()[0x04..0x0E]);
+}
+
+#[test]
+fn eeprom_write_times_out_if_write_cycle_does_not_complete() {
+    let mut sim = Mcp794xxSim::new();
+    sim.set_eeprom_write_cycle_polls(3);
+    let mut rtc = Mcp794xx::new_mcp79410(&mut sim);
+    rtc.set_eeprom_write_poll_attempts(2);
+    match rtc.write_eeprom_byte(0x00, 1) {
+        Err(Error::EepromWriteTimeout) => (),
+        _ => panic!("EepromWriteTimeout error not returned."),
+    }
+    assert!(sim.is_eeprom_write_in_progress());
+    sim.advance(Duration::from_millis(5));
+    assert!(!sim.is_eeprom_write_in_progress());
+}
+
+#[test]
+fn eeprom_block_protection_prevents_writes() {
+    let mut sim = Mcp794xxSim::new();
+    let mut rtc = Mcp794xx::new_mcp79410(&mut sim);
+    rtc.set_eeprom_write_protection(EepromWriteProtection::UpperHalf)
+        .unwrap();
+    rtc.write_eeprom_data(0x3E, &[1, 2, 3, 4]).unwrap();
+    assert_eq!(&[1, 2, 0xFF, 0xFF], &sim.eeprom()[0x3E..0x42]);
+}
+
+#[test]
+fn can_write_protected_eeprom() {
+    let mut sim = Mcp794xxSim::new();
+    let mut rtc = Mcp794xx::new_mcp79410(&mut sim);
+    rtc.write_protected_eeprom_data(0xF6, &[1, 2]).unwrap();
+    assert_eq!(2, rtc.read_protected_eeprom_byte(0xF7).unwrap());
+    assert_eq!(&[1, 2], &sim.protected_eeprom()[6..]);
+}
+
+#[test]
+fn can_read_eui64() {
+    let mut sim = Mcp794xxSim::new();
+    sim.protected_eeprom_mut()
+        .copy_from_slice(&[1, 2, 3, 4, 5, 6, 7, 8]);
+    let mut rtc = Mcp794xx::new_mcp79412(&mut sim);
+    assert_eq!([1, 2, 3, 4, 5, 6, 7, 8], rtc.read_eui64().unwrap());
+}
+
+#[cfg(feature = "rtcc")]
+#[test]
+fn device_does_not_acknowledge_when_powered_down() {
+    let mut sim = Mcp794xxSim::new();
+    sim.power_down();
+    let mut rtc = Mcp794xx::new_mcp79410(&mut sim);
+    assert!(matches!(rtc.seconds(), Err(Error::Comm(_))));
+}
+
+#[test]
+fn keeps_time_and_records_power_failure_with_battery() {
+    let mut sim = Mcp794xxSim::new();
+    start_clock(&mut sim, datetime(1, 10, 0, 0));
+    let mut rtc = Mcp794xx::new_mcp79410(&mut sim);
+    rtc.sync().unwrap();
+    rtc.enable_backup_battery_power().unwrap();
+    rtc.write_sram_byte(0x20, 0xAB).unwrap();
+    sim.advance(Duration::from_secs(60));
+    sim.power_down();
+    sim.advance(Duration::from_secs(3600));
+    sim.power_up();
+
+    let mut rtc = Mcp794xx::new_mcp79410(&mut sim);
+    rtc.sync().unwrap();
+    assert_eq!(datetime(1, 11, 1, 0), rtc.read_datetime().unwrap());
+    assert_eq!(0xAB, rtc.read_sram_byte(0x20).unwrap());
+    assert!(rtc.has_power_failed().unwrap());
+    let down = PowerFailDateTime {
+        month: 12,
+        day: 1,
+        weekday: 6,
+        hour: Hours::H24(10),
+        minute: 1,
+    };
+    assert_eq!(down, rtc.get_power_down_datetime().unwrap());
+    let up = PowerFailDateTime {
+        hour: Hours::H24(11),
+        ..down
+    };
+    assert_eq!(up, rtc.get_power_up_datetime().unwrap());
+
+    rtc.clear_power_failed().unwrap();
+    assert!(!rtc.has_power_failed().unwrap());
+    assert_eq!(&[0; 8], &sim.rtcc_registers()[0x18..]);
+}
+
+#[test]
+fn loses_time_and_sram_without_battery() {
+    let mut sim = Mcp794xxSim::new();
+    start_clock(&mut sim, datetime(1, 10, 0, 0));
+    let mut rtc = Mcp794xx::new_mcp79410(&mut sim);
+    rtc.write_sram_byte(0x20, 0xAB).unwrap();
+    rtc.write_eeprom_byte(0x00, 0xCD).unwrap();
+    sim.power_down();
+    sim.power_up();
+
+    let mut rtc = Mcp794xx::new_mcp79410(&mut sim);
+    assert!(!rtc.is_oscillator_running().unwrap());
+    assert!(!rtc.has_power_failed().unwrap());
+    assert_eq!(0, rtc.read_sram_byte(0x20).unwrap());
+    assert_eq!(0xCD, rtc.read_eeprom_byte(0x00).unwrap());
+}
+
+#[test]
+fn settings_survive_corrupt_write() {
+    struct Counter(u8);
+    impl mcp794xx::Settings for Counter {
+        const VERSION: u8 = 1;
+        fn serialize(&self, buffer: &mut [u8]) -> Option<usize> {
+            *buffer.first_mut()? = self.0;
+            Some(1)
+        }
+        fn deserialize(data: &[u8]) -> Option<Self> {
+            data.first().map(|value| Counter(*value))
+        }
+    }
+
+    let mut sim = Mcp794xxSim::new();
+    let mut rtc = Mcp794xx::new_mcp79410(&mut sim);
+    let mut store = rtc.settings_store(0x40, 16).unwrap();
+    match store.load::<Counter>() {
+        Err(SettingsError::Blank) => (),
+        _ => panic!("Blank error not returned."),
+    }
+    for value in 1..=3 {
+        store.save(&Counter(value)).unwrap();
+    }
+    assert_eq!(3, store.load::<Counter>().unwrap().0);
+    // The third record is stored in the first slot. Interrupt its write.
+    sim.eeprom_mut()[0x43] ^= 0xFF;
+    let mut rtc = Mcp794xx::new_mcp79410(&mut sim);
+    let mut store = rtc.settings_store(0x40, 16).unwrap();
+    assert_eq!(2, store.load::<Counter>().unwrap().0);
+    store.clear().unwrap();
+    match store.load::<Counter>() {
+        Err(SettingsError::Blank) => (),
+        _ => panic!("Blank error not returned."),
+    }
+}
+
+#[test]
+fn event_log_keeps_newest_events() {
+    let mut sim = Mcp794xxSim::new();
+    start_clock(&mut sim, datetime(1, 10, 0, 1));
+    for code in 1..=14 {
+        let mut rtc = Mcp794xx::new_mcp79410(&mut sim);
+        rtc.event_log(0x20, 64).unwrap().append(code).unwrap();
+        sim.advance(Duration::from_secs(1));
+    }
+    let mut rtc = Mcp794xx::new_mcp79410(&mut sim);
+    let mut log = rtc.event_log(0x20, 64).unwrap();
+    assert_eq!(12, log.len());
+    let newest = log.iter().next().unwrap().unwrap();
+    assert_eq!((datetime(1, 10, 0, 14), 14), (newest.datetime, newest.code));
+    let oldest = log.iter().last().unwrap().unwrap();
+    assert_eq!((datetime(1, 10, 0, 3), 3), (oldest.datetime, oldest.code));
+}
+
+#[test]
+fn boot_counter_survives_power_loss_with_battery() {
+    let mut sim = Mcp794xxSim::new();
+    start_clock(&mut sim, datetime(1, 10, 0, 0));
+    let mut rtc = Mcp794xx::new_mcp79410(&mut sim);
+    rtc.enable_backup_battery_power().unwrap();
+    let counter = rtc.init_boot_counter(0x20).unwrap();
+    assert!(counter.was_lost());
+    assert_eq!(1, counter.boot_count());
+    sim.power_down();
+    sim.power_up();
+    let mut rtc = Mcp794xx::new_mcp79410(&mut sim);
+    let counter = rtc.init_boot_counter(0x20).unwrap();
+    assert!(!counter.was_lost());
+    assert_eq!(2, counter.boot_count());
+}
+
+#[test]
+fn boot_counter_is_lost_without_battery() {
+    let mut sim = Mcp794xxSim::new();
+    start_clock(&mut sim, datetime(1, 10, 0, 0));
+    let mut rtc = Mcp794xx::new_mcp79410(&mut sim);
+    rtc.init_boot_counter(0x20).unwrap();
+    assert_eq!(2, rtc.init_boot_counter(0x20).unwrap().boot_count());
+    sim.power_down();
+    sim.power_up();
+    let mut rtc = Mcp794xx::new_mcp79410(&mut sim);
+    let counter = rtc.init_boot_counter(0x20).unwrap();
+    assert!(counter.was_lost());
+    assert_eq!(1, counter.boot_count());
+}
+
+#[test]
+fn outage_history_records_outages_at_boot() {
+    let mut sim = Mcp794xxSim::new();
+    start_clock(&mut sim, datetime(1, 10, 0, 0));
+    let mut rtc = Mcp794xx::new_mcp79410(&mut sim);
+    rtc.enable_backup_battery_power().unwrap();
+    assert_eq!(
+        None,
+        rtc.eeprom_outage_history(0x00, 32)
+            .unwrap()
+            .record()
+            .unwrap()
+    );
+    for minutes in [2, 5, 3] {
+        sim.power_down();
+        sim.advance(Duration::from_secs(minutes * 60));
+        sim.power_up();
+        sim.advance(Duration::from_secs(60));
+        let mut rtc = Mcp794xx::new_mcp79410(&mut sim);
+        let outage = rtc
+            .eeprom_outage_history(0x00, 32)
+            .unwrap()
+            .record()
+            .unwrap();
+        assert_eq!(
+            Duration::from_secs(minutes * 60),
+            outage.unwrap().duration()
+        );
+        assert!(!rtc.has_power_failed().unwrap());
+    }
+    let mut rtc = Mcp794xx::new_mcp79410(&mut sim);
+    let mut history = rtc.eeprom_outage_history(0x00, 32).unwrap();
+    let stats = history.stats();
+    assert_eq!(3, stats.count);
+    assert_eq!(Duration::from_secs(10 * 60), stats.total);
+    assert_eq!(Duration::from_secs(5 * 60), stats.longest);
+    assert_eq!(2, history.len());
+    let newest = history.get(0).unwrap().unwrap();
+    assert_eq!(datetime(1, 10, 9, 0), newest.power_down);
+    assert_eq!(datetime(1, 10, 12, 0), newest.power_up);
+    let durations: Vec<_> = history.iter().map(|o| o.unwrap().duration()).collect();
+    assert_eq!(
+        vec![Duration::from_secs(3 * 60), Duration::from_secs(5 * 60)],
+        durations
+    );
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn can_use_async_driver() {
+    use embassy_futures::block_on;
+    use mcp794xx::Mcp794xxAsync;
+    let mut sim = Mcp794xxSim::new();
+    let mut rtc = Mcp794xxAsync::new_mcp79410(&mut sim);
+    let begin = datetime(1, 10, 0, 0);
+    block_on(rtc.write_datetime(&begin)).unwrap();
+    block_on(rtc.enable()).unwrap();
+    sim.advance(Duration::from_secs(1));
+    let mut rtc = Mcp794xxAsync::new_mcp79410(&mut sim);
+    assert_eq!(
+        datetime(1, 10, 0, 1),
+        block_on(rtc.read_datetime()).unwrap()
+    );
+}
+
+#[test]
+fn scheduler_sets_alarms_again_after_match() {
+    let rules = [Recurrence::Interval { minutes: 15 }];
+    let mut sim = Mcp794xxSim::new();
+    start_clock(&mut sim, datetime(1, 10, 7, 0));
+    let mut rtc = Mcp794xx::new_mcp79410(&mut sim);
+    let mut scheduler = rtc
+        .scheduler(0x20, &rules, AlarmOutputPinPolarity::High)
+        .unwrap();
+    assert!(scheduler.service().unwrap().is_empty());
+    sim.advance(Duration::from_secs(8 * 60 - 1));
+    let mut rtc = Mcp794xx::new_mcp79410(&mut sim);
+    assert!(!rtc.has_alarm_matched(Alarm::Zero).unwrap());
+    sim.advance(Duration::from_secs(1));
+    let mut rtc = Mcp794xx::new_mcp79410(&mut sim);
+    assert!(rtc.has_alarm_matched(Alarm::Zero).unwrap());
+    let mut scheduler = rtc
+        .scheduler(0x20, &rules, AlarmOutputPinPolarity::High)
+        .unwrap();
+    let due = scheduler.service().unwrap();
+    assert!(due.is_due(0));
+    assert!(!due.was_missed(0));
+    let mut rtc = Mcp794xx::new_mcp79410(&mut sim);
+    assert!(!rtc.has_alarm_matched(Alarm::Zero).unwrap());
+    sim.advance(Duration::from_secs(15 * 60));
+    let mut rtc = Mcp794xx::new_mcp79410(&mut sim);
+    assert!(rtc.has_alarm_matched(Alarm::Zero).unwrap());
+    assert!(!rtc.has_alarm_matched(Alarm::One).unwrap());
+}
+
+#[test]
+fn scheduler_reports_missed_occurrences_after_power_loss() {
+    let rules = [
+        Recurrence::Weekly {
+            weekdays: Recurrence::WORKDAYS,
+            hour: 7,
+            minute: 30,
+        },
+        Recurrence::Monthly {
+            day: 1,
+            hour: 0,
+            minute: 0,
+        },
+    ];
+    // Friday
+    let mut sim = Mcp794xxSim::new();
+    start_clock(&mut sim, datetime(1, 10, 0, 0));
+    let mut rtc = Mcp794xx::new_mcp79410(&mut sim);
+    rtc.enable_backup_battery_power().unwrap();
+    let mut scheduler = rtc
+        .scheduler(0x20, &rules, AlarmOutputPinPolarity::High)
+        .unwrap();
+    assert!(scheduler.service().unwrap().is_empty());
+    sim.power_down();
+    // Until Tuesday 12:00
+    sim.advance(Duration::from_secs((4 * 24 + 2) * 3600));
+    sim.power_up();
+    let mut rtc = Mcp794xx::new_mcp79410(&mut sim);
+    assert!(rtc.has_alarm_matched(Alarm::Zero).unwrap());
+    let mut scheduler = rtc
+        .scheduler(0x20, &rules, AlarmOutputPinPolarity::High)
+        .unwrap();
+    let due = scheduler.service().unwrap();
+    assert!(due.is_due(0));
+    assert!(due.was_missed(0));
+    assert!(!due.is_due(1));
+    assert!(scheduler.service().unwrap().is_empty());
+}
+
+#[test]
+fn scheduler_ignores_out_of_range_last_service() {
+    let rules = [Recurrence::Interval { minutes: 15 }];
+    let mut sim = Mcp794xxSim::new();
+    start_clock(&mut sim, datetime(1, 10, 7, 0));
+    let mut rtc = Mcp794xx::new_mcp79410(&mut sim);
+    // Valid CRC but after 2099
+    rtc.write_sram_data(0x20, &[0xFF, 0xFF, 0xFF, 0xFF, 0x1D, 0x0F])
+        .unwrap();
+    let mut scheduler = rtc
+        .scheduler(0x20, &rules, AlarmOutputPinPolarity::High)
+        .unwrap();
+    assert!(scheduler.service().unwrap().is_empty());
+}
@@ -0,0 +1,491 @@
+use core::time::Duration;
+use embedded_hal_mock::eh1::spi::{Mock as SpiMock, Transaction as SpiTrans};
+use mcp794xx::{
+    ic, interface, Alarm, AlarmDateTime, AlarmMatching, AlarmOutputPinPolarity, Cron, DateTime,
+    EepromWriteProtection, Error, Hours, Mcp794xx, Recurrence,
+};
+#[cfg(feature = "rtcc")]
+use mcp794xx::{DateTimeAccess, NaiveDate};
+mod common;
+use crate::common::BitFlags;
+
+const READ: u8 = 0x13;
+const WRITE: u8 = 0x12;
+const EEREAD: u8 = 0x03;
+const EEWRITE: u8 = 0x02;
+const EEWREN: u8 = 0x06;
+const SRREAD: u8 = 0x05;
+const SRWRITE: u8 = 0x01;
+const UNLOCK: u8 = 0x14;
+const IDWRITE: u8 = 0x32;
+const IDREAD: u8 = 0x33;
+
+type Dev<IC> = Mcp794xx<interface::SpiInterface<SpiMock<u8>>, IC>;
+
+fn new_mcp79510(transactions: &[SpiTrans<u8>]) -> Dev<ic::Mcp79510> {
+    Mcp794xx::new_mcp79510(SpiMock::new(transactions))
+}
+
+fn new_mcp79511(transactions: &[SpiTrans<u8>]) -> Dev<ic::Mcp79511> {
+    Mcp794xx::new_mcp79511(SpiMock::new(transactions))
+}
+
+fn new_mcp79520(transactions: &[SpiTrans<u8>]) -> Dev<ic::Mcp79520> {
+    Mcp794xx::new_mcp79520(SpiMock::new(transactions))
+}
+
+fn read_trans(instruction: u8, address: u8, response: Vec<u8>) -> Vec<SpiTrans<u8>> {
+    vec![
+        SpiTrans::transaction_start(),
+        SpiTrans::write_vec(vec![instruction, address]),
+        SpiTrans::read_vec(response),
+        SpiTrans::transaction_end(),
+    ]
+}
+
+fn write_trans(instruction: u8, address: u8, data: Vec<u8>) -> Vec<SpiTrans<u8>> {
+    vec![
+        SpiTrans::transaction_start(),
+        SpiTrans::write_vec(vec![instruction, address]),
+        SpiTrans::write_vec(data),
+        SpiTrans::transaction_end(),
+    ]
+}
+
+fn status_trans(status: u8) -> Vec<SpiTrans<u8>> {
+    vec![
+        SpiTrans::transaction_start(),
+        SpiTrans::write_vec(vec![SRREAD]),
+        SpiTrans::read_vec(vec![status]),
+        SpiTrans::transaction_end(),
+    ]
+}
+
+fn command_trans(data: Vec<u8>) -> Vec<SpiTrans<u8>> {
+    vec![
+        SpiTrans::transaction_start(),
+        SpiTrans::write_vec(data),
+        SpiTrans::transaction_end(),
+    ]
+}
+
+#[cfg(feature = "rtcc")]
+#[test]
+fn can_get_datetime() {
+    let trans = read_trans(
+        READ,
+        0x01,
+        vec![
+            0b0101_1000,
+            0b0101_1001,
+            0b0010_0011,
+            0b0000_0010,
+            0b0001_0011,
+            0b0000_1000,
+            0b0001_1000,
+        ],
+    );
+    let mut dev = new_mcp79510(&trans);
+    let expected = NaiveDate::from_ymd_opt(2018, 8, 13)
+        .unwrap()
+        .and_hms_opt(23, 59, 58)
+        .unwrap();
+    assert_eq!(expected, dev.datetime().unwrap());
+    dev.destroy().done();
+}
+
+#[cfg(feature = "rtcc")]
+#[test]
+fn can_set_datetime() {
+    let trans = write_trans(
+        WRITE,
+        0x01,
+        vec![
+            0b0101_1000,
+            0b0101_1001,
+            0b0010_0011,
+            0b0000_0010,
+            0b0001_0011,
+            0b0000_1000,
+            0b0001_1000,
+        ],
+    );
+    let mut dev = new_mcp79510(&trans);
+    let datetime = NaiveDate::from_ymd_opt(2018, 8, 13)
+        .unwrap()
+        .and_hms_opt(23, 59, 58)
+        .unwrap();
+    dev.set_datetime(&datetime).unwrap();
+    dev.destroy().done();
+}
+
+#[test]
+fn can_check_oscillator_running() {
+    let trans = read_trans(READ, 0x04, vec![BitFlags::OSCRUN]);
+    let mut dev = new_mcp79510(&trans);
+    assert!(dev.is_oscillator_running().unwrap());
+    dev.destroy().done();
+}
+
+#[test]
+fn can_enable_square_wave() {
+    let trans = write_trans(WRITE, 0x08, vec![BitFlags::OUT | BitFlags::SQWEN]);
+    let mut dev = new_mcp79510(&trans);
+    dev.enable_square_wave().unwrap();
+    dev.destroy().done();
+}
+
+#[test]
+fn can_get_hundredths() {
+    let trans = read_trans(READ, 0x00, vec![0b1001_1000]);
+    let mut dev = new_mcp79510(&trans);
+    assert_eq!(98, dev.hundredths().unwrap());
+    dev.destroy().done();
+}
+
+#[test]
+fn can_set_hundredths() {
+    let trans = write_trans(WRITE, 0x00, vec![0b0101_0001]);
+    let mut dev = new_mcp79510(&trans);
+    dev.set_hundredths(51).unwrap();
+    dev.destroy().done();
+}
+
+#[test]
+fn cannot_set_invalid_hundredths() {
+    let mut dev = new_mcp79510(&[]);
+    assert_invalid_input_data!(dev.set_hundredths(100));
+    dev.destroy().done();
+}
+
+const ADT: AlarmDateTime = AlarmDateTime {
+    month: 11,
+    day: 3,
+    weekday: 4,
+    hour: Hours::AM(3),
+    minute: 32,
+    second: 41,
+};
+
+#[test]
+fn can_set_alarm0() {
+    let trans = write_trans(
+        WRITE,
+        0x0C,
+        vec![
+            0b0100_0001,
+            0b0011_0010,
+            0b0100_0011,
+            0b0111_0100,
+            0b0000_0011,
+            0b0001_0001,
+        ],
+    );
+    let mut dev = new_mcp79510(&trans);
+    dev.set_alarm(
+        Alarm::Zero,
+        ADT,
+        AlarmMatching::AllMatch,
+        AlarmOutputPinPolarity::Low,
+    )
+    .unwrap();
+    dev.destroy().done();
+}
+
+#[test]
+fn alarm1_month_is_not_written() {
+    let trans = write_trans(
+        WRITE,
+        0x13,
+        vec![
+            0b0100_0001,
+            0b0011_0010,
+            0b0100_0011,
+            0b0001_0100,
+            0b0000_0011,
+        ],
+    );
+    let mut dev = new_mcp79510(&trans);
+    dev.set_alarm(
+        Alarm::One,
+        ADT,
+        AlarmMatching::MinutesMatch,
+        AlarmOutputPinPolarity::Low,
+    )
+    .unwrap();
+    dev.destroy().done();
+}
+
+#[test]
+fn cannot_set_alarm1_all_match() {
+    let mut dev = new_mcp79510(&[]);
+    assert_invalid_input_data!(dev.set_alarm(
+        Alarm::One,
+        ADT,
+        AlarmMatching::AllMatch,
+        AlarmOutputPinPolarity::High,
+    ));
+    dev.destroy().done();
+}
+
+// 2018-08-13 23:59:58
+fn read_datetime_trans() -> Vec<SpiTrans<u8>> {
+    read_trans(
+        READ,
+        0x01,
+        vec![
+            0b0101_1000,
+            0b0101_1001,
+            0b0010_0011,
+            0b0000_0010,
+            0b0001_0011,
+            0b0000_1000,
+            0b0001_1000,
+        ],
+    )
+}
+
+#[test]
+fn can_set_alarm0_in_duration() {
+    let mut trans = read_datetime_trans();
+    trans.extend(write_trans(
+        WRITE,
+        0x0C,
+        vec![
+            0b0101_1000,
+            0b0000_0000,
+            0b0101_0010,
+            0b0111_0011,
+            0b0001_0100,
+            0b0000_1000,
+        ],
+    ));
+    trans.extend(write_trans(
+        WRITE,
+        0x08,
+        vec![BitFlags::OUT | BitFlags::ALM0EN],
+    ));
+    let mut dev = new_mcp79510(&trans);
+    let target = dev
+        .set_alarm_in(
+            Alarm::Zero,
+            Duration::from_secs(60),
+            AlarmOutputPinPolarity::Low,
+        )
+        .unwrap();
+    assert_eq!(DateTime::new(2018, 8, 14, 0, 0, 58).unwrap(), target);
+    dev.destroy().done();
+}
+
+#[test]
+fn cannot_set_alarm1_in_duration() {
+    let mut dev = new_mcp79510(&read_datetime_trans());
+    assert_invalid_input_data!(dev.set_alarm_in(
+        Alarm::One,
+        Duration::from_secs(60),
+        AlarmOutputPinPolarity::Low,
+    ));
+    dev.destroy().done();
+}
+
+#[test]
+fn can_set_cron_alarm0() {
+    let mut trans = read_datetime_trans();
+    trans.extend(write_trans(
+        WRITE,
+        0x0C,
+        vec![
+            0b0000_0000,
+            0b0000_0000,
+            0b0101_0010,
+            0b0111_0011,
+            0b0001_0100,
+            0b0000_1000,
+        ],
+    ));
+    trans.extend(write_trans(
+        WRITE,
+        0x08,
+        vec![BitFlags::OUT | BitFlags::ALM0EN],
+    ));
+    let mut dev = new_mcp79510(&trans);
+    let cron = Cron::parse("0 0 * * *").unwrap();
+    let next = dev
+        .set_cron_alarm(Alarm::Zero, &cron, AlarmOutputPinPolarity::Low)
+        .unwrap();
+    assert_eq!(DateTime::new(2018, 8, 14, 0, 0, 0).unwrap(), next);
+    dev.destroy().done();
+}
+
+#[test]
+fn cannot_set_cron_alarm1() {
+    let mut dev = new_mcp79510(&read_datetime_trans());
+    let cron = Cron::parse("0 0 * * *").unwrap();
+    assert_invalid_input_data!(dev.set_cron_alarm(Alarm::One, &cron, AlarmOutputPinPolarity::Low));
+    dev.destroy().done();
+}
+
+#[test]
+fn scheduler_uses_only_alarm0() {
+    let mut trans = read_datetime_trans();
+    trans.extend(read_trans(READ, 0x20, vec![0; 6]));
+    trans.extend(write_trans(
+        WRITE,
+        0x0C,
+        vec![
+            0b0000_0000,
+            0b0000_0000,
+            0b0101_0010,
+            0b0111_0011,
+            0b0001_0100,
+            0b0000_1000,
+        ],
+    ));
+    trans.extend(write_trans(
+        WRITE,
+        0x08,
+        vec![BitFlags::OUT | BitFlags::ALM0EN],
+    ));
+    trans.extend(write_trans(
+        WRITE,
+        0x08,
+        vec![BitFlags::OUT | BitFlags::ALM0EN],
+    ));
+    trans.extend(read_trans(READ, 0x16, vec![BitFlags::ALMIF]));
+    trans.extend(write_trans(WRITE, 0x16, vec![0]));
+    // 2018-08-13 23:59:58 as seconds since 2000 and the CRC
+    trans.extend(write_trans(
+        WRITE,
+        0x20,
+        vec![0xFE, 0xD7, 0x04, 0x23, 0x81, 0x16],
+    ));
+    let mut dev = new_mcp79510(&trans);
+    let rules = [Recurrence::Interval { minutes: 60 }];
+    let mut scheduler = dev
+        .scheduler(0x20, &rules, AlarmOutputPinPolarity::Low)
+        .unwrap();
+    assert!(scheduler.service().unwrap().is_empty());
+    dev.destroy().done();
+}
+
+#[test]
+fn alarm1_month_is_read_as_zero() {
+    let mut trans = read_trans(
+        READ,
+        0x13,
+        vec![
+            0b0100_0001,
+            0b0011_0010,
+            0b0100_0011,
+            0b0001_0100,
+            0b0000_0011,
+        ],
+    );
+    trans.extend(read_trans(READ, 0x0F, vec![BitFlags::ALMPOL]));
+    trans.extend(read_trans(READ, 0x08, vec![BitFlags::ALM1EN]));
+    let mut dev = new_mcp79510(&trans);
+    let config = dev.get_alarm(Alarm::One).unwrap();
+    assert_eq!(AlarmDateTime { month: 0, ..ADT }, config.when);
+    assert_eq!(AlarmMatching::MinutesMatch, config.matching);
+    assert_eq!(AlarmOutputPinPolarity::High, config.polarity);
+    assert!(config.is_enabled);
+    dev.destroy().done();
+}
+
+#[test]
+fn can_check_power_failed() {
+    let trans = read_trans(READ, 0x04, vec![BitFlags::PWRFAIL]);
+    let mut dev = new_mcp79510(&trans);
+    assert!(dev.has_power_failed().unwrap());
+    dev.destroy().done();
+}
+
+#[test]
+fn can_read_sram_data() {
+    let trans = read_trans(READ, 0x20, vec![1, 2, 3]);
+    let mut dev = new_mcp79510(&trans);
+    let mut data = [0; 3];
+    dev.read_sram_data(0x20, &mut data).unwrap();
+    assert_eq!([1, 2, 3], data);
+    dev.destroy().done();
+}
+
+#[test]
+fn can_read_eeprom_data() {
+    let trans = read_trans(EEREAD, 0x10, vec![1, 2, 3]);
+    let mut dev = new_mcp79510(&trans);
+    let mut data = [0; 3];
+    dev.read_eeprom_data(0x10, &mut data).unwrap();
+    assert_eq!([1, 2, 3], data);
+    dev.destroy().done();
+}
+
+#[test]
+fn can_write_eeprom_data() {
+    let mut trans = command_trans(vec![EEWREN]);
+    trans.extend(write_trans(EEWRITE, 0x10, vec![1, 2, 3]));
+    trans.extend(status_trans(0));
+    let mut dev = new_mcp79510(&trans);
+    dev.write_eeprom_data(0x10, &[1, 2, 3]).unwrap();
+    dev.destroy().done();
+}
+
+#[test]
+fn waits_for_eeprom_write_in_progress() {
+    let mut trans = command_trans(vec![EEWREN]);
+    trans.extend(write_trans(EEWRITE, 0x10, vec![1]));
+    trans.extend(status_trans(0b0000_0001));
+    trans.extend(status_trans(0b0000_0001));
+    trans.extend(status_trans(0));
+    let mut dev = new_mcp79510(&trans);
+    dev.write_eeprom_byte(0x10, 1).unwrap();
+    dev.destroy().done();
+}
+
+#[test]
+fn can_set_eeprom_write_protection() {
+    let mut trans = command_trans(vec![EEWREN]);
+    trans.extend(command_trans(vec![SRWRITE, 0b0000_1000]));
+    trans.extend(status_trans(0));
+    let mut dev = new_mcp79510(&trans);
+    dev.set_eeprom_write_protection(EepromWriteProtection::UpperHalf)
+        .unwrap();
+    dev.destroy().done();
+}
+
+#[test]
+fn can_read_upper_eeprom_on_2kbit_devices() {
+    let trans = read_trans(EEREAD, 0xF0, vec![1, 2]);
+    let mut dev = new_mcp79520(&trans);
+    let mut data = [0; 2];
+    dev.read_eeprom_data(0xF0, &mut data).unwrap();
+    assert_eq!([1, 2], data);
+    dev.destroy().done();
+}
+
+#[test]
+fn cannot_read_upper_eeprom_on_1kbit_devices() {
+    let mut dev = new_mcp79510(&[]);
+    assert_invalid_input_data!(dev.read_eeprom_byte(0x80));
+    dev.destroy().done();
+}
+
+#[test]
+fn can_write_protected_eeprom_data() {
+    let mut trans = command_trans(vec![UNLOCK, 0x55]);
+    trans.extend(command_trans(vec![UNLOCK, 0xAA]));
+    trans.extend(command_trans(vec![EEWREN]));
+    trans.extend(write_trans(IDWRITE, 0x01, vec![1, 2]));
+    trans.extend(status_trans(0));
+    let mut dev = new_mcp79510(&trans);
+    dev.write_protected_eeprom_data(0xF1, &[1, 2]).unwrap();
+    dev.destroy().done();
+}
+
+#[test]
+fn can_read_eui48() {
+    let trans = read_trans(IDREAD, 0x02, vec![1, 2, 3, 4, 5, 6]);
+    let mut dev = new_mcp79511(&trans);
+    assert_eq!([1, 2, 3, 4, 5, 6], dev.read_eui48().unwrap());
+    dev.destroy().done();
+}
@@ -0,0 +1,140 @@
+#![cfg(feature = "embedded-storage")]
+use embedded_hal_mock::eh1::i2c::Transaction as I2cTrans;
+use embedded_storage::nor_flash::{NorFlash, NorFlashError, NorFlashErrorKind, ReadNorFlash};
+use embedded_storage::{ReadStorage, Storage};
+use mcp794xx::StorageError;
+mod common;
+use crate::common::{destroy_mcp79410, new_mcp79410, EEPROM_ADDRESS};
+
+const EEPROM_STATUS: u8 = 0xFF;
+
+fn read_status(status: u8) -> I2cTrans {
+    I2cTrans::write_read(EEPROM_ADDRESS, vec![EEPROM_STATUS], vec![status])
+}
+
+fn poll() -> I2cTrans {
+    I2cTrans::write(EEPROM_ADDRESS, vec![])
+}
+
+#[test]
+fn has_eeprom_capacity() {
+    let mut dev = new_mcp79410(&[]);
+    assert_eq!(128, ReadStorage::capacity(&dev.eeprom_storage()));
+    assert_eq!(128, ReadNorFlash::capacity(&dev.eeprom_storage()));
+    destroy_mcp79410(dev);
+}
+
+#[test]
+fn can_read() {
+    let mut dev = new_mcp79410(&[I2cTrans::write_read(
+        EEPROM_ADDRESS,
+        vec![0x7D],
+        vec![1, 2, 3],
+    )]);
+    let mut data = [0; 3];
+    ReadStorage::read(&mut dev.eeprom_storage(), 0x7D, &mut data).unwrap();
+    assert_eq!([1, 2, 3], data);
+    destroy_mcp79410(dev);
+}
+
+#[test]
+fn cannot_read_out_of_bounds() {
+    let mut dev = new_mcp79410(&[]);
+    let mut storage = dev.eeprom_storage();
+    let error = ReadStorage::read(&mut storage, 0x7F, &mut [0; 2]).unwrap_err();
+    assert_eq!(NorFlashErrorKind::OutOfBounds, error.kind());
+    let error = ReadNorFlash::read(&mut storage, u32::MAX, &mut [0; 2]).unwrap_err();
+    assert_eq!(NorFlashErrorKind::OutOfBounds, error.kind());
+    destroy_mcp79410(dev);
+}
+
+#[test]
+fn can_write() {
+    let mut dev = new_mcp79410(&[
+        read_status(0),
+        I2cTrans::write(EEPROM_ADDRESS, vec![0x06, 1, 2]),
+        poll(),
+        I2cTrans::write(EEPROM_ADDRESS, vec![0x08, 3]),
+        poll(),
+    ]);
+    Storage::write(&mut dev.eeprom_storage(), 0x06, &[1, 2, 3]).unwrap();
+    destroy_mcp79410(dev);
+}
+
+#[test]
+fn can_write_below_protected_block() {
+    let mut dev = new_mcp79410(&[
+        read_status(0b0000_0100),
+        I2cTrans::write(EEPROM_ADDRESS, vec![0x5F, 1]),
+        poll(),
+    ]);
+    Storage::write(&mut dev.eeprom_storage(), 0x5F, &[1]).unwrap();
+    destroy_mcp79410(dev);
+}
+
+#[test]
+fn cannot_write_protected_block() {
+    let mut dev = new_mcp79410(&[read_status(0b0000_1000)]);
+    match Storage::write(&mut dev.eeprom_storage(), 0x3F, &[1, 2]) {
+        Err(StorageError::WriteProtected) => (),
+        _ => panic!("WriteProtected error not returned."),
+    }
+    destroy_mcp79410(dev);
+}
+
+#[test]
+fn can_write_pages() {
+    let mut dev = new_mcp79410(&[
+        read_status(0),
+        I2cTrans::write(EEPROM_ADDRESS, vec![0x08, 1, 2, 3, 4, 5, 6, 7, 8]),
+        poll(),
+    ]);
+    NorFlash::write(&mut dev.eeprom_storage(), 0x08, &[1, 2, 3, 4, 5, 6, 7, 8]).unwrap();
+    destroy_mcp79410(dev);
+}
+
+#[test]
+fn cannot_write_unaligned_pages() {
+    let mut dev = new_mcp79410(&[]);
+    let mut storage = dev.eeprom_storage();
+    let error = NorFlash::write(&mut storage, 0x04, &[0; 8]).unwrap_err();
+    assert_eq!(NorFlashErrorKind::NotAligned, error.kind());
+    let error = NorFlash::write(&mut storage, 0x08, &[0; 4]).unwrap_err();
+    assert_eq!(NorFlashErrorKind::NotAligned, error.kind());
+    destroy_mcp79410(dev);
+}
+
+#[test]
+fn can_erase() {
+    let mut dev = new_mcp79410(&[
+        read_status(0),
+        I2cTrans::write(
+            EEPROM_ADDRESS,
+            vec![0x70, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF],
+        ),
+        poll(),
+        I2cTrans::write(
+            EEPROM_ADDRESS,
+            vec![0x78, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF],
+        ),
+        poll(),
+    ]);
+    NorFlash::erase(&mut dev.eeprom_storage(), 0x70, 0x80).unwrap();
+    destroy_mcp79410(dev);
+}
+
+#[test]
+fn cannot_erase_out_of_bounds() {
+    let mut dev = new_mcp79410(&[]);
+    let error = NorFlash::erase(&mut dev.eeprom_storage(), 0x78, 0x88).unwrap_err();
+    assert_eq!(NorFlashErrorKind::OutOfBounds, error.kind());
+    destroy_mcp79410(dev);
+}
+
+#[test]
+fn cannot_erase_protected_block() {
+    let mut dev = new_mcp79410(&[read_status(0b0000_1100)]);
+    let error = NorFlash::erase(&mut dev.eeprom_storage(), 0x00, 0x08).unwrap_err();
+    assert_eq!(NorFlashErrorKind::Other, error.kind());
+    destroy_mcp79410(dev);
+}